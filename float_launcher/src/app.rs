@@ -1,30 +1,64 @@
+mod launcher;
 mod runtime;
+mod search;
 mod state;
 mod style;
 mod ui;
 
-use crate::config::{AppConfig, PinnedLaunchMeta};
+use crate::branding::APP_DISPLAY_NAME;
+use crate::config::{
+    clamp_ui_scale, dedupe_launch_meta, normalize_launch_key, normalize_path_key, AppConfig,
+    DockLevel, HideMethod, PinnedLaunchMeta, ThemeMode, WindowGeometry,
+};
 use crate::events::{IconRequest, UserEvent};
-use crate::system::get_auto_start_status;
+use crate::icons::TrayIcons;
+use crate::system::{
+    get_auto_start_status, system_high_contrast, system_prefers_reduced_motion, FrameEffects,
+    RoundedRegion, ShowState,
+};
 use eframe::egui;
-use state::{DropAnim, PinnedApp};
-use std::collections::{HashMap, HashSet};
+use launcher::{Launcher, ShellLauncher};
+use state::{
+    CommandDraft, DropAnim, HoverCard, ImportCandidate, LaunchOptionsDraft, PendingLaunch,
+    PinnedApp, Severity, Toast,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
-use std::time::Instant;
-use tray_icon::{menu::MenuItem, Icon, TrayIcon};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use style::LauncherTheme;
+use tray_icon::{
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    TrayIcon,
+};
 
 pub const WINDOW_WIDTH: f32 = 320.0;
 pub const WINDOW_HEIGHT: f32 = 640.0;
 pub const MIN_WINDOW_WIDTH: f32 = 260.0;
 pub const MIN_WINDOW_HEIGHT: f32 = 380.0;
-const MAX_PINNED_APPS: usize = 20;
+const HIGH_CONTRAST_POLL: Duration = Duration::from_secs(2);
+/// How often an idle dock checks whether the monitors changed under it.
+const DISPLAY_POLL: Duration = Duration::from_secs(2);
+const MAX_TOASTS: usize = 4;
+/// Pins listed at the top of the tray menu; the rest stay in the dock.
+const MAX_TRAY_PINS: usize = 8;
+/// How long the tray icon spins after a launch, and how long each frame shows.
+const TRAY_SPIN_DURATION: Duration = Duration::from_millis(1600);
+const TRAY_SPIN_FRAME: Duration = Duration::from_millis(80);
+/// Where `HideMethod::Hide` leaves the window: the spot Windows parks
+/// minimized windows at, well outside any desktop.
+const PARKED_POS: egui::Pos2 = egui::pos2(-32000.0, -32000.0);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum ResizeEdge {
     Left,
     Right,
+    Top,
     Bottom,
+    TopLeft,
+    TopRight,
     BottomLeft,
     BottomRight,
 }
@@ -35,19 +69,36 @@ pub(super) struct ResizeDragState {
     pub start_window_pos: egui::Pos2,
     pub start_window_size: egui::Vec2,
     pub start_global_mouse: egui::Pos2,
+    /// Geometry last sent to the window, so unchanged values aren't sent
+    /// again while the OS catches up.
+    pub sent_pos: egui::Pos2,
+    pub sent_size: egui::Vec2,
 }
 
 pub struct MyApp {
-    tray_icon: TrayIcon,
+    tray_icon: Option<TrayIcon>,
     rx: Receiver<UserEvent>,
+    ui_tx: Sender<UserEvent>,
+    /// Every pin launch goes through this; shared with group-launch workers.
+    launcher: Arc<dyn Launcher>,
     icon_req_tx: Sender<IconRequest>,
     is_visible: bool,
     pinned_apps: Vec<PinnedApp>,
     config: AppConfig,
     auto_start_enabled: bool,
     toggle_item: MenuItem,
-    icon_awake: Icon,
-    icon_sleep: Icon,
+    tray_menu: Menu,
+    tray_pin_items: Vec<MenuItem>,
+    tray_pin_separator: PredefinedMenuItem,
+    /// `(launch key, label)` of the entries currently in the tray menu.
+    tray_pins: Vec<(String, String)>,
+    /// "Profile" submenu of the tray, present while there's more than one.
+    tray_profile_menu: Option<(Submenu, Vec<CheckMenuItem>)>,
+    /// Labels of the profiles in the tray submenu.
+    tray_profiles: Vec<String>,
+    tray_icons: TrayIcons,
+    /// Launch animation start and the frame last put in the tray.
+    tray_spin: Option<(Instant, Option<usize>)>,
     is_dragging_window: bool,
     drag_start_window_pos: Option<egui::Pos2>,
     drag_start_global_mouse: Option<egui::Pos2>,
@@ -55,74 +106,132 @@ pub struct MyApp {
     flash_start_time: Option<Instant>,
     fade_in_start: Option<Instant>,
     fade_out_start: Option<Instant>,
+    /// Effects last requested from the compositor and what it granted; see
+    /// `sync_frame_effects`.
+    frame_effects: (FrameEffects, FrameEffects),
+    /// `exclude_from_capture` as last applied; see `sync_capture_exclusion`.
+    capture_excluded: bool,
+    /// Whether the window is currently a tool window; see `sync_tool_window`.
+    tool_window: bool,
+    /// Where the window was before `HideMethod::Hide` parked it off-screen.
+    parked_at: Option<egui::Pos2>,
+    /// Panel outline and corner radius painted this frame, in points.
+    panel_outline: Option<(egui::Rect, f32)>,
+    window_region: Option<RoundedRegion>,
     hide_after_fade: bool,
     dragging_app: Option<usize>,
     drag_target: Option<usize>,
+    /// Pin a list drag would land on (and merge into a folder with) on release.
+    merge_target: Option<usize>,
+    /// Window origin and size, in points, from before a `ui_scale` change,
+    /// with the scale they were measured at; see `finish_ui_rescale`.
+    ui_rescale: Option<(egui::Pos2, egui::Vec2, f32)>,
+    /// Last monitor size the viewport reported. Some sessions (RDP, docking
+    /// stations, GPU switches) briefly report none; see `monitor_size`.
+    last_monitor_size: Option<egui::Vec2>,
+    /// Desktop bounds in physical pixels the window was last kept inside; a
+    /// change means a resolution switch or a monitor coming or going.
+    display_pixels: Option<(i32, i32, i32, i32)>,
+    /// Open folder a list drag would drop into, at its slot among the folder's pins.
+    drop_folder: Option<usize>,
     grid_drag_target: Option<(usize, usize)>,
     selected_app: Option<usize>,
+    selected_apps: HashSet<usize>,
     press_candidate: Option<(usize, Instant, egui::Pos2)>,
     panel_frac: f32,
     panel_anim: Option<(f32, f32, Instant)>,
     drop_anim: Option<DropAnim>,
-    warning_message: Option<(String, Instant)>,
+    /// Pin whose icon is bouncing after a launch, and when it started.
+    launch_bounce: Option<(usize, Instant)>,
+    list_scroll_offset: f32,
+    restore_scroll: bool,
+    list_overflows: bool,
+    wheel_accum: f32,
+    wheel_preview: Option<Instant>,
+    toasts: VecDeque<Toast>,
+    hover_card: Option<HoverCard>,
+    import_candidates: Option<Vec<ImportCandidate>>,
+    command_draft: Option<CommandDraft>,
+    launch_options: Option<LaunchOptionsDraft>,
+    confirm_quit: Arc<AtomicBool>,
+    hide_in_fullscreen: Arc<AtomicBool>,
+    quit_prompt_open: bool,
+    pending_launch: Option<PendingLaunch>,
+    /// An "Open" dialog is up; see `pick_file`.
+    picking_file: bool,
+    /// Pin whose favorite star or menu entry was clicked this frame; applied
+    /// after drawing since it can reorder the list.
+    pending_favorite: Option<usize>,
+    /// Query of the open search box; `None` while it is closed.
+    search: Option<String>,
+    search_focus: bool,
+    hotkey_thread: Arc<AtomicU32>,
+    /// Files the background watcher checks for updates; see `refresh_watched_paths`.
+    watched_paths: runtime::WatchedPaths,
+    system_reduce_motion: bool,
+    system_high_contrast: (bool, Instant),
 }
 
 impl MyApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let mut config = AppConfig::load();
-        let (migrated_paths, migrated_meta) =
-            migrate_config_paths(&config.pinned_apps, &config.pinned_launch_meta);
-        if config.pinned_apps != migrated_paths || config.pinned_launch_meta != migrated_meta {
-            config.pinned_apps = migrated_paths;
-            config.pinned_launch_meta = migrated_meta;
+    /// Builds the dock around `config` as `AppConfig::load` returned it.
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        mut config: AppConfig,
+        start_hidden: bool,
+    ) -> Self {
+        let repairs = std::mem::take(&mut config.repairs);
+        let (migrated_paths, migrated_meta) = migrate_config_paths(
+            &config.profile.pinned_apps,
+            &dedupe_launch_meta(config.profile.pinned_launch_meta.clone()),
+        );
+        if config.profile.pinned_apps != migrated_paths
+            || config.profile.pinned_launch_meta != migrated_meta
+        {
+            config.profile.pinned_apps = migrated_paths;
+            config.profile.pinned_launch_meta = migrated_meta;
             config.save();
         }
 
-        if let Some((x, y)) = config.last_pos {
-            cc.egui_ctx
-                .send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(x, y)));
-        }
-        if let Some((w, h)) = config.last_size {
-            let restored = sanitize_window_size(egui::vec2(w, h));
-            cc.egui_ctx
-                .send_viewport_cmd(egui::ViewportCommand::InnerSize(restored));
-        }
+        // Auto-start with "start minimized" always boots to the tray; otherwise
+        // come back the way the dock was left.
+        let start_hidden = start_hidden || config.hidden;
+        cc.egui_ctx.set_zoom_factor(config.ui_scale());
+        // Zooming goes through `ui_scale`, so it's saved and the window follows.
+        cc.egui_ctx
+            .options_mut(|options| options.zoom_with_keyboard = false);
+        let confirm_quit = Arc::new(AtomicBool::new(config.confirm_quit));
+        let hide_in_fullscreen = Arc::new(AtomicBool::new(config.hide_in_fullscreen));
+        let runtime = runtime::build_runtime(
+            &cc.egui_ctx,
+            !start_hidden,
+            confirm_quit.clone(),
+            hide_in_fullscreen.clone(),
+            config.next_profile_vk(),
+        );
+        let pinned_apps = pins_from_config(&config);
 
-        let runtime = runtime::build_runtime(&cc.egui_ctx);
-        let launch_meta_by_path: HashMap<String, &PinnedLaunchMeta> = config
-            .pinned_launch_meta
-            .iter()
-            .map(|meta| (meta.key(), meta))
-            .collect();
-        let pinned_apps = config
-            .pinned_apps
-            .iter()
-            .cloned()
-            .map(|path| {
-                if let Some(meta) = launch_meta_by_path.get(&normalize_path_key(&path)) {
-                    PinnedApp::new(
-                        path,
-                        meta.display_name.clone(),
-                        meta.args.clone(),
-                        meta.working_dir.clone(),
-                    )
-                } else {
-                    PinnedApp::from_path(path)
-                }
-            })
-            .collect();
+        let panel_frac = if config.collapsed { 0.0 } else { 1.0 };
+        let list_scroll_offset = config.profile.scroll_offset.max(0.0);
 
-        Self {
-            tray_icon: runtime.tray_icon,
+        let mut app = Self {
+            tray_icon: Some(runtime.tray_icon),
             rx: runtime.rx,
+            ui_tx: runtime.ui_tx,
+            launcher: Arc::new(ShellLauncher),
             icon_req_tx: runtime.icon_req_tx,
             is_visible: true,
             pinned_apps,
             config,
             auto_start_enabled: get_auto_start_status(),
             toggle_item: runtime.toggle_item,
-            icon_awake: runtime.icon_awake,
-            icon_sleep: runtime.icon_sleep,
+            tray_menu: runtime.tray_menu,
+            tray_pin_items: Vec::new(),
+            tray_pin_separator: PredefinedMenuItem::separator(),
+            tray_pins: Vec::new(),
+            tray_profile_menu: None,
+            tray_profiles: Vec::new(),
+            tray_icons: runtime.icons,
+            tray_spin: None,
             is_dragging_window: false,
             drag_start_window_pos: None,
             drag_start_global_mouse: None,
@@ -130,58 +239,376 @@ impl MyApp {
             flash_start_time: None,
             fade_in_start: None,
             fade_out_start: None,
+            frame_effects: Default::default(),
+            capture_excluded: false,
+            tool_window: false,
+            parked_at: None,
+            panel_outline: None,
+            window_region: None,
             hide_after_fade: false,
             dragging_app: None,
             drag_target: None,
+            merge_target: None,
+            ui_rescale: None,
+            last_monitor_size: None,
+            display_pixels: None,
+            drop_folder: None,
             grid_drag_target: None,
             selected_app: None,
+            selected_apps: HashSet::new(),
             press_candidate: None,
-            panel_frac: 1.0,
+            panel_frac,
             panel_anim: None,
             drop_anim: None,
-            warning_message: None,
+            launch_bounce: None,
+            list_scroll_offset,
+            restore_scroll: true,
+            list_overflows: false,
+            wheel_accum: 0.0,
+            wheel_preview: None,
+            toasts: VecDeque::new(),
+            hover_card: None,
+            import_candidates: None,
+            command_draft: None,
+            launch_options: None,
+            confirm_quit,
+            hide_in_fullscreen,
+            quit_prompt_open: false,
+            pending_launch: None,
+            picking_file: false,
+            pending_favorite: None,
+            search: None,
+            search_focus: false,
+            hotkey_thread: runtime.hotkey_thread,
+            watched_paths: runtime.watched_paths,
+            system_reduce_motion: system_prefers_reduced_motion(),
+            system_high_contrast: (system_high_contrast(), Instant::now()),
+        };
+
+        app.gather_folders();
+        if let Some(summary) = repairs.summary() {
+            app.show_toast(Severity::Warning, summary);
+        }
+        app.refresh_tray_pins();
+        app.refresh_tray_profiles();
+        app.refresh_watched_paths();
+        if start_hidden {
+            // Go straight to the tray without showing the panel.
+            app.hide_now(&cc.egui_ctx);
         }
+        app
     }
 
-    fn start_hide_transition(&mut self) {
-        if self.is_visible {
-            self.is_visible = false;
-            self.fade_out_start = None;
-            self.hide_after_fade = false;
-            self.toggle_item.set_text("Show");
-            let _ = self.tray_icon.set_icon(Some(self.icon_sleep.clone()));
+    /// Puts the row carried by the drop animation in its slot right away.
+    fn land_drop_anim(&mut self) {
+        self.drop_folder = None;
+        if let Some(done) = self.drop_anim.take() {
+            let insert_at = done.insert_at.min(self.pinned_apps.len());
+            self.pinned_apps.insert(insert_at, done.item);
+            self.select_only(insert_at);
+            self.sync_config_pins();
+        }
+    }
+
+    fn save_scroll_offset(&mut self) {
+        if self.config.profile.scroll_offset != self.list_scroll_offset {
+            self.config.profile.scroll_offset = self.list_scroll_offset;
+            self.config.save();
+        }
+    }
+
+    fn save_visibility(&mut self) {
+        if self.config.hidden == self.is_visible {
+            self.config.hidden = !self.is_visible;
+            self.config.save();
+        }
+    }
+
+    /// Drops any half-finished reorder so a drag cut short by hiding doesn't
+    /// resume under the cursor on the next show.
+    fn reset_drag_state(&mut self) {
+        self.land_drop_anim();
+        self.dragging_app = None;
+        self.drag_target = None;
+        self.merge_target = None;
+        self.press_candidate = None;
+        self.grid_drag_target = None;
+        self.hover_card = None;
+    }
+
+    /// Fades the panel out; `handle_fade_out` hides the window once it's gone.
+    fn start_hide_transition(&mut self, ctx: &egui::Context) {
+        self.reset_drag_state();
+        self.save_scroll_offset();
+        if !self.is_visible || self.hide_after_fade {
+            return;
+        }
+        if self.reduce_motion() {
+            self.hide_now(ctx);
+            return;
         }
+        self.toggle_item.set_text("Show");
+        if let Some(tray) = &self.tray_icon {
+            let _ = tray.set_icon(Some(self.tray_icons.sleep.clone()));
+        }
+        self.fade_in_start = None;
+        self.fade_out_start = Some(Instant::now());
+        self.hide_after_fade = true;
+        self.refresh_tray_tooltip();
+        ctx.request_repaint();
+    }
+
+    /// Minimizes or hides to the tray, per `hide_method`, without fading.
+    fn hide_now(&mut self, ctx: &egui::Context) {
+        self.reset_drag_state();
+        self.is_visible = false;
+        self.fade_out_start = None;
+        self.hide_after_fade = false;
+        self.toggle_item.set_text("Show");
+        if let Some(tray) = &self.tray_icon {
+            let _ = tray.set_icon(Some(self.tray_icons.sleep.clone()));
+        }
+        self.refresh_tray_tooltip();
+        self.save_visibility();
+        match self.config.hide_method {
+            HideMethod::Minimize => ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true)),
+            HideMethod::Hide => self.park_window(ctx),
+        }
+    }
+
+    /// Moves the window off-screen instead of hiding it. A hidden window
+    /// never paints, so the UI would stop handling tray picks, profile
+    /// switches and watcher updates until it was shown again.
+    fn park_window(&mut self, ctx: &egui::Context) {
+        if self.parked_at.is_some() {
+            return;
+        }
+        // Before the first frame there's no rect yet; the window opens at
+        // `last_pos`.
+        let origin = ctx
+            .input(|i| i.viewport().outer_rect)
+            .map(|rect| rect.min)
+            .or(self.config.last_pos.map(|(x, y)| egui::pos2(x, y)))
+            .unwrap_or(egui::Pos2::ZERO);
+        self.parked_at = Some(origin);
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(PARKED_POS));
+    }
+
+    /// Panel opacity for the show/hide fades: eases in from 0 after a show
+    /// and back out to 0 while hiding. Reduced motion skips the fade-in.
+    fn fade_opacity(&mut self, ctx: &egui::Context) -> f32 {
+        if self.reduce_motion() {
+            self.fade_in_start = None;
+        }
+        if let Some(start) = self.fade_out_start {
+            let t = start.elapsed().as_secs_f32() / self.config.animation.fade_out().as_secs_f32();
+            return 1.0 - ease_out_cubic(t.clamp(0.0, 1.0));
+        }
+        let Some(start) = self.fade_in_start else {
+            return 1.0;
+        };
+        let t = start.elapsed().as_secs_f32() / self.config.animation.fade_in().as_secs_f32();
+        if t >= 1.0 {
+            self.fade_in_start = None;
+            return 1.0;
+        }
+        ctx.request_repaint();
+        ease_out_cubic(t.max(0.0))
     }
 
     fn start_show_transition(&mut self, ctx: &egui::Context) {
-        if !self.is_visible {
-            self.fade_in_start = Some(Instant::now());
+        self.reset_drag_state();
+        self.restore_scroll = true;
+        // Showing again mid-fade turns the fade around instead of finishing the hide.
+        if !self.is_visible || self.hide_after_fade {
+            self.fade_in_start = (!self.reduce_motion()).then(Instant::now);
             self.fade_out_start = None;
             self.hide_after_fade = false;
             self.is_visible = true;
             self.toggle_item.set_text("Hide");
-            let _ = self.tray_icon.set_icon(Some(self.icon_awake.clone()));
+            if let Some(tray) = &self.tray_icon {
+                let _ = tray.set_icon(Some(self.tray_icons.awake.clone()));
+            }
+            self.refresh_tray_tooltip();
+        }
+        self.save_visibility();
+        if self.config.collapsed {
+            self.set_collapsed(false);
+        }
+        let parked_at = self.parked_at.take();
+        if self.config.apply_size_on_show {
+            self.apply_saved_geometry(ctx, parked_at);
+        } else if let Some(pos) = parked_at {
+            let size = ctx
+                .input(|i| i.viewport().inner_rect)
+                .map_or(egui::vec2(WINDOW_WIDTH, WINDOW_HEIGHT), |rect| rect.size());
+            let pos = match self.monitor_size(ctx) {
+                Some(monitor_size) => {
+                    ui::clamp_window_origin(pos, size, ui::desktop_bounds(ctx, monitor_size))
+                }
+                None => pos,
+            };
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
         }
         ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
         ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
-        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        if self.config.focus_on_show {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
+    }
+
+    /// Folds the dock down to its header (or back out), animating `panel_frac`.
+    fn set_collapsed(&mut self, collapsed: bool) {
+        if self.config.collapsed != collapsed {
+            self.config.collapsed = collapsed;
+            self.config.save();
+        }
+        let target = if collapsed { 0.0 } else { 1.0 };
+        if self.reduce_motion() {
+            self.panel_anim = None;
+            self.panel_frac = target;
+        } else if (self.panel_frac - target).abs() > f32::EPSILON {
+            self.panel_anim = Some((self.panel_frac, target, Instant::now()));
+        }
+    }
+
+    /// Whether animations should be flattened, per config or the OS setting.
+    fn reduce_motion(&self) -> bool {
+        self.config
+            .reduce_motion
+            .unwrap_or(self.system_reduce_motion)
+    }
+
+    /// Re-reads the OS high-contrast flag now and then so toggling it flips the theme.
+    fn poll_system_high_contrast(&mut self, ctx: &egui::Context) {
+        if self.config.profile.theme != ThemeMode::System {
+            return;
+        }
+        let (enabled, checked) = self.system_high_contrast;
+        if checked.elapsed() < HIGH_CONTRAST_POLL {
+            ctx.request_repaint_after(HIGH_CONTRAST_POLL);
+            return;
+        }
+        let now = system_high_contrast();
+        self.system_high_contrast = (now, Instant::now());
+        if now != enabled {
+            ctx.request_repaint();
+        }
+    }
+
+    fn theme(&self) -> LauncherTheme {
+        let high_contrast = match self.config.profile.theme {
+            ThemeMode::System => self.system_high_contrast.0,
+            ThemeMode::Standard => false,
+            ThemeMode::HighContrast => true,
+        };
+        let theme = if high_contrast {
+            LauncherTheme::high_contrast()
+        } else {
+            LauncherTheme::default()
+        };
+        theme.with_appearance(self.config.profile.appearance)
+    }
+
+    /// Applies `config.profile.acrylic`/`config.profile.system_shadow` once
+    /// the window exists and whenever they change. Effects the system refuses
+    /// fall back to the flat transparency and painted shadow.
+    fn sync_frame_effects(&mut self, frame: &eframe::Frame) {
+        let wanted = FrameEffects {
+            acrylic: self.config.profile.acrylic,
+            shadow: self.config.profile.system_shadow,
+        };
+        if self.frame_effects.0 == wanted {
+            return;
+        }
+        let granted = crate::system::set_frame_effects(frame, wanted);
+        if granted != wanted {
+            log::info!("compositor effects unavailable, using fallbacks: wanted {wanted:?}, got {granted:?}");
+        }
+        self.frame_effects = (wanted, granted);
+    }
+
+    /// Applies `config.exclude_from_capture` once the window exists and
+    /// whenever it changes.
+    fn sync_capture_exclusion(&mut self, frame: &eframe::Frame) {
+        let wanted = self.config.exclude_from_capture;
+        if self.capture_excluded == wanted {
+            return;
+        }
+        if !crate::system::set_capture_excluded(frame, wanted) && wanted {
+            log::warn!("could not keep the dock out of screen capture");
+        }
+        self.capture_excluded = wanted;
+    }
+
+    /// Clips the window to the panel's rounded outline so the transparent
+    /// corners, and the space below a collapsed panel, don't catch clicks.
+    /// Only re-applied when the outline changes.
+    /// A window region turns off the DWM shadow, so it's dropped while that's on.
+    fn sync_window_region(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let Some((rect, radius)) = self.panel_outline.take() else {
+            return;
+        };
+        if self.frame_effects.1.shadow {
+            if self.window_region.take().is_some() {
+                crate::system::set_window_region(frame, None);
+            }
+            return;
+        }
+        let ppp = ctx.pixels_per_point();
+        let region = RoundedRegion {
+            left: (rect.min.x * ppp).round() as i32,
+            top: (rect.min.y * ppp).round() as i32,
+            right: (rect.max.x * ppp).round() as i32,
+            bottom: (rect.max.y * ppp).round() as i32,
+            radius: (radius * ppp).round() as i32,
+        };
+        if self.window_region != Some(region) {
+            self.window_region = Some(region);
+            crate::system::set_window_region(frame, Some(region));
+        }
+    }
+
+    /// Easing for slide animations: elastic normally, linear under reduced motion.
+    fn motion_ease(&self, t: f32) -> f32 {
+        if self.reduce_motion() {
+            t.clamp(0.0, 1.0)
+        } else {
+            ease_out_elastic(t)
+        }
     }
 
     fn sync_config_pins(&mut self) {
-        self.config.pinned_apps = self
+        self.gather_folders();
+        self.config.profile.pinned_apps = self
             .pinned_apps
             .iter()
             .map(|app| app.path.clone())
             .collect();
-        self.config.pinned_launch_meta = self
+        self.config.profile.pinned_launch_meta = self
             .pinned_apps
             .iter()
             .filter_map(|app| {
                 let args = app.launch_args.clone().and_then(normalize_text_opt);
                 let working_dir = app.working_dir.clone();
+                let shortcut = app.shortcut.clone();
+                let favorite = app.favorite;
+                let opener = app.opener.clone();
+                let command = app.command;
+                let env = app.env.clone();
+                let show_state = app.show_state;
                 let display_name = normalize_text_opt(app.name.clone())
                     .filter(|name| Some(name) != default_display_name(&app.path).as_ref());
-                if args.is_none() && working_dir.is_none() && display_name.is_none() {
+                if args.is_none()
+                    && working_dir.is_none()
+                    && display_name.is_none()
+                    && shortcut.is_none()
+                    && !favorite
+                    && opener.is_none()
+                    && !command
+                    && env.is_empty()
+                    && show_state == ShowState::Normal
+                {
                     None
                 } else {
                     Some(PinnedLaunchMeta {
@@ -189,25 +616,478 @@ impl MyApp {
                         display_name,
                         args,
                         working_dir,
+                        shortcut,
+                        favorite,
+                        opener,
+                        command,
+                        env,
+                        show_state,
                     })
                 }
             })
             .collect();
         self.config.save();
+        self.refresh_tray_pins();
+        self.refresh_watched_paths();
+    }
+
+    /// Hands the current pin targets to the mtime watcher. Command pins
+    /// have no file to watch.
+    fn refresh_watched_paths(&self) {
+        let paths = self
+            .pinned_apps
+            .iter()
+            .filter(|app| !app.command)
+            .map(|app| app.path.clone())
+            .collect();
+        if let Ok(mut watched) = self.watched_paths.lock() {
+            *watched = paths;
+        }
+    }
+
+    /// Starts the tray's launch animation, unless it's turned off.
+    fn start_tray_spin(&mut self) {
+        if self.config.animate_tray_on_launch && !self.tray_icons.launching.is_empty() {
+            self.tray_spin = Some((Instant::now(), None));
+        }
+    }
+
+    /// Steps the launch animation, then puts back the awake or sleep icon.
+    fn update_tray_spin(&mut self, ctx: &egui::Context) {
+        let Some((start, shown)) = self.tray_spin else {
+            return;
+        };
+        let Some(tray) = &self.tray_icon else {
+            self.tray_spin = None;
+            return;
+        };
+        let elapsed = start.elapsed();
+        if elapsed >= TRAY_SPIN_DURATION {
+            self.tray_spin = None;
+            let hidden = !self.is_visible || self.hide_after_fade;
+            let icon = if hidden {
+                &self.tray_icons.sleep
+            } else {
+                &self.tray_icons.awake
+            };
+            let _ = tray.set_icon(Some(icon.clone()));
+            return;
+        }
+        let frames = &self.tray_icons.launching;
+        let frame = (elapsed.as_millis() / TRAY_SPIN_FRAME.as_millis()) as usize % frames.len();
+        if shown != Some(frame) {
+            let _ = tray.set_icon(Some(frames[frame].clone()));
+            self.tray_spin = Some((start, Some(frame)));
+        }
+        ctx.request_repaint_after(TRAY_SPIN_FRAME);
+    }
+
+    /// Puts the pin count and whether the dock is hidden in the tray tooltip.
+    fn refresh_tray_tooltip(&self) {
+        let hidden = !self.is_visible || self.hide_after_fade;
+        if let Some(tray) = &self.tray_icon {
+            let _ = tray.set_tooltip(Some(tray_tooltip(self.pinned_apps.len(), hidden)));
+        }
+    }
+
+    /// Rebuilds the tray menu's pin entries when the first `MAX_TRAY_PINS`
+    /// pins (or their names) changed.
+    fn refresh_tray_pins(&mut self) {
+        self.refresh_tray_tooltip();
+        let pins: Vec<(String, String)> = self
+            .pinned_apps
+            .iter()
+            .take(MAX_TRAY_PINS)
+            .map(|app| (pin_launch_key(app), app.name.clone()))
+            .collect();
+        if pins == self.tray_pins {
+            return;
+        }
+
+        for item in self.tray_pin_items.drain(..) {
+            let _ = self.tray_menu.remove(&item);
+        }
+        if !self.tray_pins.is_empty() {
+            let _ = self.tray_menu.remove(&self.tray_pin_separator);
+        }
+        for (position, (key, label)) in pins.iter().enumerate() {
+            let id = format!("{}{}", runtime::TRAY_PIN_ID_PREFIX, key);
+            let item = MenuItem::with_id(id, label, true, None);
+            let _ = self.tray_menu.insert(&item, position);
+            self.tray_pin_items.push(item);
+        }
+        if !pins.is_empty() {
+            let _ = self.tray_menu.insert(&self.tray_pin_separator, pins.len());
+        }
+        self.tray_pins = pins;
+    }
+
+    /// Rebuilds the tray's "Profile" submenu when profiles were added, removed
+    /// or renamed, and moves the check to the active one.
+    fn refresh_tray_profiles(&mut self) {
+        let labels = if self.config.profile_count() > 1 {
+            self.config.profile_labels()
+        } else {
+            Vec::new()
+        };
+        if labels != self.tray_profiles {
+            if let Some((submenu, _)) = self.tray_profile_menu.take() {
+                let _ = self.tray_menu.remove(&submenu);
+            }
+            if !labels.is_empty() {
+                let submenu = Submenu::new("Profile", true);
+                let items: Vec<CheckMenuItem> = labels
+                    .iter()
+                    .enumerate()
+                    .map(|(index, label)| {
+                        let id = format!("{}{index}", runtime::TRAY_PROFILE_ID_PREFIX);
+                        let item = CheckMenuItem::with_id(id, label, true, false, None);
+                        let _ = submenu.append(&item);
+                        item
+                    })
+                    .collect();
+                // Between the pin entries and the fixed ones.
+                let position =
+                    self.tray_pin_items.len() + usize::from(!self.tray_pin_items.is_empty());
+                let _ = self.tray_menu.insert(&submenu, position);
+                self.tray_profile_menu = Some((submenu, items));
+            }
+            self.tray_profiles = labels;
+        }
+        // Set every time: the menu flips a check item's state when it's clicked.
+        if let Some((_, items)) = &self.tray_profile_menu {
+            for (index, item) in items.iter().enumerate() {
+                item.set_checked(index == self.config.active_profile);
+            }
+        }
+    }
+
+    /// Moves on to the profile after the active one, wrapping around.
+    fn next_profile(&mut self, ctx: &egui::Context) {
+        let count = self.config.profile_count();
+        if count < 2 {
+            self.show_toast(Severity::Info, "No other profile to switch to");
+            return;
+        }
+        self.switch_profile(ctx, (self.config.active_profile + 1) % count);
+    }
+
+    fn switch_profile(&mut self, ctx: &egui::Context, index: usize) {
+        self.change_profile(ctx, |config| config.switch_profile(index));
+    }
+
+    /// Runs `change` on the profile list and, when it says the active profile
+    /// changed, swaps in the new one's pins, layout and look, growing the
+    /// window if its grid needs more room. Selections and other state
+    /// pointing at the old pins by index are dropped.
+    fn change_profile(&mut self, ctx: &egui::Context, change: impl FnOnce(&mut AppConfig) -> bool) {
+        self.reset_drag_state();
+        self.config.profile.scroll_offset = self.list_scroll_offset;
+        if !change(&mut self.config) {
+            self.refresh_tray_profiles();
+            return;
+        }
+        let repairs = self.config.validate_and_repair();
+        self.config.save();
+        self.pinned_apps = pins_from_config(&self.config);
+        self.clear_selection();
+        self.launch_bounce = None;
+        self.pending_favorite = None;
+        self.list_scroll_offset = self.config.profile.scroll_offset;
+        self.restore_scroll = true;
+        self.gather_folders();
+        self.refresh_tray_pins();
+        self.refresh_tray_profiles();
+        self.refresh_watched_paths();
+        self.fit_window_to_layout(ctx);
+        let label = self.config.profile.label(self.config.active_profile);
+        self.show_toast(Severity::Info, format!("Profile: {label}"));
+        if let Some(summary) = repairs.summary() {
+            self.show_toast(Severity::Warning, summary);
+        }
+    }
+
+    /// Pin matching a tray entry's launch key, if it is still pinned.
+    fn pin_index_for_key(&self, key: &str) -> Option<usize> {
+        self.pinned_apps
+            .iter()
+            .position(|app| pin_launch_key(app) == key)
+    }
+
+    fn is_selected(&self, idx: usize) -> bool {
+        self.selected_apps.contains(&idx)
+    }
+
+    fn select_only(&mut self, idx: usize) {
+        self.selected_apps.clear();
+        self.selected_apps.insert(idx);
+        self.selected_app = Some(idx);
+    }
+
+    fn toggle_selection(&mut self, idx: usize) {
+        if self.selected_apps.remove(&idx) {
+            if self.selected_app == Some(idx) {
+                self.selected_app = self.selected_apps.iter().copied().min();
+            }
+        } else {
+            self.selected_apps.insert(idx);
+            self.selected_app = Some(idx);
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        self.selected_apps.clear();
+        self.selected_app = None;
+    }
+
+    fn selected_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.selected_apps.iter().copied().collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// The selection to offer for "Remove selected" when `idx` is part of a
+    /// multi-row selection.
+    fn batch_selection_for(&self, idx: usize) -> Option<Vec<usize>> {
+        if self.selected_apps.len() > 1 && self.is_selected(idx) {
+            Some(self.selected_indices())
+        } else {
+            None
+        }
+    }
+
+    /// Exits right away, or opens the confirmation prompt when `confirm_quit` is set.
+    fn request_quit(&mut self, ctx: &egui::Context) {
+        if self.config.confirm_quit {
+            self.quit_prompt_open = true;
+        } else {
+            self.shutdown(ctx);
+        }
+    }
+
+    /// Clean exit: flush config, release hotkeys and remove the tray icon
+    /// before closing the viewport, instead of `process::exit` skipping drops.
+    fn shutdown(&mut self, ctx: &egui::Context) {
+        log::info!("Exiting application...");
+        self.quit_prompt_open = false;
+        self.config.profile.scroll_offset = self.list_scroll_offset;
+        self.config.hidden = !self.is_visible || self.hide_after_fade;
+        self.config.save();
+        runtime::stop_hotkey_worker(&self.hotkey_thread);
+        if let Some(tray) = self.tray_icon.take() {
+            let _ = tray.set_visible(false);
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    /// Makes the window a tool window while the dock is hidden, minimized or
+    /// parked, so it stays out of Alt-Tab; showing it makes it a normal
+    /// window again.
+    fn sync_tool_window(&mut self, frame: &eframe::Frame) {
+        let wanted = !self.is_visible;
+        if self.tool_window == wanted {
+            return;
+        }
+        if crate::system::set_tool_window(frame, wanted) || !wanted {
+            self.tool_window = wanted;
+        }
     }
 
-    fn show_warning<S: Into<String>>(&mut self, message: S) {
-        self.warning_message = Some((message.into(), Instant::now()));
+    fn set_window_level(&mut self, ctx: &egui::Context, level: DockLevel) {
+        self.config.window_level = level;
+        self.config.save();
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(window_level(level)));
     }
 
-    fn save_window_geometry(&mut self, pos: egui::Pos2, size: egui::Vec2) {
+    fn set_hide_in_fullscreen(&mut self, enabled: bool) {
+        self.config.hide_in_fullscreen = enabled;
+        self.config.save();
+        self.hide_in_fullscreen.store(enabled, Ordering::Relaxed);
+    }
+
+    fn set_confirm_quit(&mut self, enabled: bool) {
+        self.config.confirm_quit = enabled;
+        self.config.save();
+        self.confirm_quit.store(enabled, Ordering::Relaxed);
+    }
+
+    fn show_toast<S: Into<String>>(&mut self, severity: Severity, message: S) {
+        let message = message.into();
+        if let Some(toast) = self
+            .toasts
+            .iter_mut()
+            .find(|t| t.severity == severity && t.message == message)
+        {
+            toast.count += 1;
+            toast.start = Instant::now();
+            return;
+        }
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            severity,
+            message,
+            count: 1,
+            start: Instant::now(),
+        });
+    }
+
+    /// Drops a toast whose message an action just made untrue.
+    fn dismiss_toast(&mut self, message: &str) {
+        self.toasts.retain(|toast| toast.message != message);
+    }
+
+    /// Zooms the dock to `scale` on top of the monitor's scaling. The window
+    /// keeps its size in points, so it grows or shrinks with the contents.
+    fn set_ui_scale(&mut self, ctx: &egui::Context, scale: f32) {
+        let scale = clamp_ui_scale(scale);
+        let old = self.config.ui_scale();
+        if scale == old {
+            return;
+        }
+        self.config.ui_scale = scale;
+        self.config.save();
+        ctx.set_zoom_factor(scale);
+        let (outer, inner) = ctx.input(|i| (i.viewport().outer_rect, i.viewport().inner_rect));
+        if let (Some(outer), Some(inner)) = (outer, inner) {
+            // A second step before the first landed keeps the first's baseline.
+            let (pos, size, from) = self.ui_rescale.unwrap_or((outer.min, inner.size(), old));
+            self.ui_rescale = Some((pos, size, from));
+        }
+    }
+
+    /// Runs the frame after a zoom change took effect: restores the window's
+    /// size in points (no smaller than the minimum) and saves the origin,
+    /// which stayed put on screen but now measures differently.
+    fn finish_ui_rescale(&mut self, ctx: &egui::Context) {
+        if self.ui_rescale.is_none() || ctx.zoom_factor() != self.config.ui_scale() {
+            return;
+        }
+        let Some((pos, size, from)) = self.ui_rescale.take() else {
+            return;
+        };
+        let size = sanitize_window_size(size);
+        let pos = pos * (from / self.config.ui_scale());
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        self.save_window_geometry(ctx, pos, size);
+    }
+
+    /// Keeps `last_monitor_size` current; runs at the start of every frame.
+    fn remember_monitor_size(&mut self, ctx: &egui::Context) {
+        if let Some(size) = ctx.input(|i| i.viewport().monitor_size) {
+            self.last_monitor_size = Some(size);
+        }
+    }
+
+    /// Moves the window back inside the desktop when the resolution or the
+    /// set of monitors changes, and saves where it ended up. Compares
+    /// physical pixels, so a UI scale change doesn't count as a new display.
+    /// Polls while the dock is visible; a hidden dock is clamped when shown.
+    fn watch_display_changes(&mut self, ctx: &egui::Context) {
+        if self.is_visible {
+            ctx.request_repaint_after(DISPLAY_POLL);
+        }
+        let Some(monitor_size) = self.monitor_size(ctx) else {
+            return;
+        };
+        let pixels = crate::system::virtual_desktop().unwrap_or_else(|| {
+            let scale = ctx
+                .input(|i| i.viewport().native_pixels_per_point)
+                .unwrap_or(1.0);
+            let size = monitor_size * scale;
+            (0, 0, size.x.round() as i32, size.y.round() as i32)
+        });
+        let previous = self.display_pixels.replace(pixels);
+        if previous.is_none()
+            || previous == Some(pixels)
+            || self.is_dragging_window
+            || self.resize_drag.is_some()
+            || self.parked_at.is_some()
+        {
+            return;
+        }
+        let (outer, inner) = ctx.input(|i| (i.viewport().outer_rect, i.viewport().inner_rect));
+        let (Some(outer), Some(inner)) = (outer, inner) else {
+            return;
+        };
+        let size = sanitize_window_size(inner.size());
+        let bounds = ui::desktop_bounds(ctx, monitor_size);
+        let pos = ui::clamp_window_origin(outer.min, size, bounds);
+        log::info!("display changed; keeping the dock at {pos:?}");
+        if (pos - outer.min).abs().max_elem() > 0.5 {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
+        }
+        self.save_window_geometry(ctx, pos, size);
+    }
+
+    /// The viewport's monitor size, or the last one it reported while it
+    /// reports none, so clamping and snapping keep working through the gap.
+    fn monitor_size(&self, ctx: &egui::Context) -> Option<egui::Vec2> {
+        ctx.input(|i| i.viewport().monitor_size)
+            .or(self.last_monitor_size)
+    }
+
+    /// Re-sends the geometry saved for the monitor the window is on (or the
+    /// primary one), clamped so it can't come back out of reach. A parked
+    /// window counts as being where it was parked from.
+    fn apply_saved_geometry(&self, ctx: &egui::Context, parked_at: Option<egui::Pos2>) {
+        let ppp = ctx.pixels_per_point();
+        let monitor = ctx
+            .input(|i| i.viewport().outer_rect)
+            .map(|rect| match parked_at {
+                Some(pos) => rect.translate(pos - rect.min),
+                None => rect,
+            })
+            .and_then(|rect| {
+                let center = rect.center() * ppp;
+                crate::system::monitor_id_at(center.x as i32, center.y as i32)
+            })
+            .or_else(crate::system::primary_monitor_id);
+        let Some(saved) = self.config.geometry_for(monitor.as_deref()) else {
+            return;
+        };
+        let size = sanitize_window_size(egui::Vec2::from(saved.size));
+        let mut pos = egui::Pos2::from(saved.pos);
+        if !(pos.x.is_finite() && pos.y.is_finite()) {
+            return;
+        }
+        if let Some(monitor_size) = self.monitor_size(ctx) {
+            pos = ui::clamp_window_origin(pos, size, ui::desktop_bounds(ctx, monitor_size));
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
+    }
+
+    fn save_window_geometry(&mut self, ctx: &egui::Context, pos: egui::Pos2, size: egui::Vec2) {
         let size = sanitize_window_size(size);
         self.config.last_pos = Some((pos.x, pos.y));
         self.config.last_size = Some((size.x, size.y));
+        let center = (pos + size * 0.5) * ctx.pixels_per_point();
+        if let Some(monitor) = crate::system::monitor_id_at(center.x as i32, center.y as i32) {
+            self.config.monitor_geometry.insert(
+                monitor,
+                WindowGeometry {
+                    pos: (pos.x, pos.y),
+                    size: (size.x, size.y),
+                },
+            );
+        }
         self.config.save();
     }
 }
 
+/// Viewport level for the `window_level` setting, at startup and when changed.
+/// The desktop level is a plain bottom-most window rather than a child of
+/// the wallpaper host, so it keeps getting clicks when the desktop is showing.
+pub fn window_level(level: DockLevel) -> egui::WindowLevel {
+    match level {
+        DockLevel::Normal => egui::WindowLevel::Normal,
+        DockLevel::AlwaysOnTop => egui::WindowLevel::AlwaysOnTop,
+        DockLevel::Desktop => egui::WindowLevel::AlwaysOnBottom,
+    }
+}
+
 pub(super) fn sanitize_window_size(size: egui::Vec2) -> egui::Vec2 {
     let width = if size.x.is_finite() {
         size.x
@@ -222,7 +1102,25 @@ pub(super) fn sanitize_window_size(size: egui::Vec2) -> egui::Vec2 {
     egui::vec2(width.max(MIN_WINDOW_WIDTH), height.max(MIN_WINDOW_HEIGHT))
 }
 
-pub(super) fn ease_out_elastic(t: f32) -> f32 {
+/// Where the window is created: the saved origin, pulled onto the work area
+/// of the monitor nearest to it so a dock last seen on a disconnected
+/// display doesn't open out of reach.
+pub fn startup_window_pos(saved: egui::Pos2, size: egui::Vec2) -> egui::Pos2 {
+    let scale = crate::system::display_scale().unwrap_or(1.0);
+    let Some((left, top, right, bottom)) = crate::system::work_area_at(
+        (saved.x * scale).round() as i32,
+        (saved.y * scale).round() as i32,
+    ) else {
+        return saved;
+    };
+    let area = egui::Rect::from_min_max(
+        egui::pos2(left as f32 / scale, top as f32 / scale),
+        egui::pos2(right as f32 / scale, bottom as f32 / scale),
+    );
+    ui::clamp_window_origin(saved, size, area)
+}
+
+fn ease_out_elastic(t: f32) -> f32 {
     if t <= 0.0 {
         return 0.0;
     }
@@ -233,6 +1131,10 @@ pub(super) fn ease_out_elastic(t: f32) -> f32 {
     (2.0_f32).powf(-10.0 * t) * ((t * 10.0 - 0.75) * c4).sin() + 1.0
 }
 
+fn ease_out_cubic(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
 fn migrate_config_paths(
     paths: &[PathBuf],
     launch_meta: &[PinnedLaunchMeta],
@@ -258,12 +1160,29 @@ fn migrate_config_paths(
         let mut working_dir = launch_meta_by_path
             .get(&key_before)
             .and_then(|m| m.working_dir.clone());
+        let mut shortcut_path = launch_meta_by_path
+            .get(&key_before)
+            .and_then(|m| m.shortcut.clone());
+        let favorite = launch_meta_by_path
+            .get(&key_before)
+            .is_some_and(|m| m.favorite);
+        let opener = launch_meta_by_path
+            .get(&key_before)
+            .and_then(|m| m.opener.clone());
+        let command = launch_meta_by_path
+            .get(&key_before)
+            .is_some_and(|m| m.command);
+        let env = launch_meta_by_path
+            .get(&key_before)
+            .map(|m| m.env.clone())
+            .unwrap_or_default();
+        let show_state = launch_meta_by_path
+            .get(&key_before)
+            .map(|m| m.show_state)
+            .unwrap_or_default();
 
-        let is_shortcut = path
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("lnk"))
-            .unwrap_or(false);
+        // A command line ending in `.lnk` is still a command, not a shortcut.
+        let is_shortcut = !command && crate::system::is_shortcut_path(path);
         if is_shortcut {
             display_name = path
                 .file_stem()
@@ -271,9 +1190,13 @@ fn migrate_config_paths(
                 .and_then(normalize_text_opt);
         }
 
-        if let Some(shortcut) = crate::system::resolve_shortcut(path) {
+        if let Some(shortcut) = (!command)
+            .then(|| crate::system::resolve_shortcut(path))
+            .flatten()
+        {
             if shortcut.target_path.exists() {
                 resolved_path = shortcut.target_path;
+                shortcut_path = Some(path.clone());
                 if let Some(v) = shortcut.arguments.and_then(normalize_text_opt) {
                     args = Some(v);
                 }
@@ -290,12 +1213,27 @@ fn migrate_config_paths(
                     display_name = None;
                 }
             }
-            if args.is_some() || working_dir.is_some() || display_name.is_some() {
+            if args.is_some()
+                || working_dir.is_some()
+                || display_name.is_some()
+                || shortcut_path.is_some()
+                || favorite
+                || opener.is_some()
+                || command
+                || !env.is_empty()
+                || show_state != ShowState::Normal
+            {
                 migrated_meta.push(PinnedLaunchMeta {
                     path: resolved_path.clone(),
                     display_name,
                     args,
                     working_dir,
+                    shortcut: shortcut_path,
+                    favorite,
+                    opener,
+                    command,
+                    env,
+                    show_state,
                 });
             }
             migrated.push(resolved_path);
@@ -305,8 +1243,59 @@ fn migrate_config_paths(
     (migrated, dedupe_launch_meta(migrated_meta))
 }
 
-fn normalize_path_key(path: &Path) -> String {
-    path.to_string_lossy().to_ascii_lowercase()
+/// The active profile's pins with their saved launch settings.
+fn pins_from_config(config: &AppConfig) -> Vec<PinnedApp> {
+    let launch_meta_by_path: HashMap<String, &PinnedLaunchMeta> = config
+        .profile
+        .pinned_launch_meta
+        .iter()
+        .map(|meta| (meta.key(), meta))
+        .collect();
+    config
+        .profile
+        .pinned_apps
+        .iter()
+        .cloned()
+        .map(|path| {
+            let meta = launch_meta_by_path.get(&normalize_path_key(&path)).copied();
+            pinned_app_from_meta(path, meta)
+        })
+        .collect()
+}
+
+/// The pin for `path`, with its saved launch settings when it has any.
+fn pinned_app_from_meta(path: PathBuf, meta: Option<&PinnedLaunchMeta>) -> PinnedApp {
+    let Some(meta) = meta else {
+        return PinnedApp::from_path(path);
+    };
+    let mut app = PinnedApp::new(
+        path,
+        meta.display_name.clone(),
+        meta.args.clone(),
+        meta.working_dir.clone(),
+    );
+    app.shortcut = meta.shortcut.clone();
+    app.favorite = meta.favorite;
+    app.opener = meta.opener.clone();
+    app.command = meta.command;
+    app.env = meta.env.clone();
+    app.show_state = meta.show_state;
+    app
+}
+
+/// "Float Dock — 12 apps", with "(hidden)" while the panel is in the tray.
+fn tray_tooltip(pins: usize, hidden: bool) -> String {
+    let apps = if pins == 1 { "app" } else { "apps" };
+    let state = if hidden { " (hidden)" } else { "" };
+    format!("{APP_DISPLAY_NAME} — {pins} {apps}{state}")
+}
+
+fn pin_launch_key(app: &PinnedApp) -> String {
+    normalize_launch_key(
+        &app.path,
+        app.launch_args.as_deref(),
+        app.working_dir.as_deref(),
+    )
 }
 
 fn normalize_text_opt(text: String) -> Option<String> {
@@ -318,17 +1307,6 @@ fn normalize_text_opt(text: String) -> Option<String> {
     }
 }
 
-fn dedupe_launch_meta(items: Vec<PinnedLaunchMeta>) -> Vec<PinnedLaunchMeta> {
-    let mut out = Vec::with_capacity(items.len());
-    let mut seen = HashSet::with_capacity(items.len());
-    for item in items {
-        if seen.insert(item.key()) {
-            out.push(item);
-        }
-    }
-    out
-}
-
 fn default_display_name(path: &Path) -> Option<String> {
     path.file_stem()
         .map(|stem| stem.to_string_lossy().to_string())
@@ -338,62 +1316,78 @@ fn default_display_name(path: &Path) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::process::Command;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn ps_quote(path: &Path) -> String {
-        path.to_string_lossy().replace('\'', "''")
-    }
-
-    fn norm(path: &Path) -> String {
-        path.to_string_lossy()
-            .replace('/', "\\")
-            .to_ascii_lowercase()
-    }
 
     #[test]
-    fn migrate_shortcut_to_target_with_meta() {
-        let uniq = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time error")
-            .as_nanos();
-        let base = std::env::temp_dir().join(format!("float_launcher_migrate_test_{uniq}"));
-        std::fs::create_dir_all(&base).expect("create temp dir");
-
-        let target = base.join("dummy.exe");
-        std::fs::write(&target, b"MZ").expect("write exe");
-        let shortcut = base.join("dummy.lnk");
-
-        let script = format!(
-            "$w=New-Object -ComObject WScript.Shell; \
-             $s=$w.CreateShortcut('{shortcut}'); \
-             $s.TargetPath='{target}'; \
-             $s.Arguments='--migrated'; \
-             $s.WorkingDirectory='{workdir}'; \
-             $s.Save()",
-            shortcut = ps_quote(&shortcut),
-            target = ps_quote(&target),
-            workdir = ps_quote(&base)
+    fn tray_tooltip_counts_pins_and_notes_hidden() {
+        assert_eq!(
+            tray_tooltip(12, true),
+            format!("{APP_DISPLAY_NAME} — 12 apps (hidden)")
         );
-        let status = Command::new("powershell")
-            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
-            .status()
-            .expect("run powershell");
-        assert!(status.success(), "powershell failed to create shortcut");
-
-        let (paths, meta) = migrate_config_paths(&[shortcut.clone()], &[]);
-        assert_eq!(paths.len(), 1);
-        assert_eq!(norm(&paths[0]), norm(&target));
-        assert_eq!(meta.len(), 1);
-        assert_eq!(norm(&meta[0].path), norm(&target));
-        assert_eq!(meta[0].args.as_deref(), Some("--migrated"));
         assert_eq!(
-            meta[0].working_dir.as_ref().map(|p| norm(p)),
-            Some(norm(&base))
+            tray_tooltip(1, false),
+            format!("{APP_DISPLAY_NAME} — 1 app")
         );
+    }
+
+    // Builds a real `.lnk` through PowerShell and the WScript COM object.
+    #[cfg(windows)]
+    mod shortcuts {
+        use super::*;
+        use std::process::Command;
+        use std::time::{SystemTime, UNIX_EPOCH};
 
-        let _ = std::fs::remove_file(&shortcut);
-        let _ = std::fs::remove_file(&target);
-        let _ = std::fs::remove_dir_all(&base);
+        fn ps_quote(path: &Path) -> String {
+            path.to_string_lossy().replace('\'', "''")
+        }
+
+        #[test]
+        fn migrate_shortcut_to_target_with_meta() {
+            let uniq = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("time error")
+                .as_nanos();
+            let base = std::env::temp_dir().join(format!("float_launcher_migrate_test_{uniq}"));
+            std::fs::create_dir_all(&base).expect("create temp dir");
+
+            let target = base.join("dummy.exe");
+            std::fs::write(&target, b"MZ").expect("write exe");
+            let shortcut = base.join("dummy.lnk");
+
+            let script = format!(
+                "$w=New-Object -ComObject WScript.Shell; \
+                 $s=$w.CreateShortcut('{shortcut}'); \
+                 $s.TargetPath='{target}'; \
+                 $s.Arguments='--migrated'; \
+                 $s.WorkingDirectory='{workdir}'; \
+                 $s.Save()",
+                shortcut = ps_quote(&shortcut),
+                target = ps_quote(&target),
+                workdir = ps_quote(&base)
+            );
+            let status = Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+                .status()
+                .expect("run powershell");
+            assert!(status.success(), "powershell failed to create shortcut");
+
+            let (paths, meta) = migrate_config_paths(std::slice::from_ref(&shortcut), &[]);
+            assert_eq!(paths.len(), 1);
+            assert_eq!(normalize_path_key(&paths[0]), normalize_path_key(&target));
+            assert_eq!(meta.len(), 1);
+            assert_eq!(
+                normalize_path_key(&meta[0].path),
+                normalize_path_key(&target)
+            );
+            assert_eq!(meta[0].args.as_deref(), Some("--migrated"));
+            assert_eq!(meta[0].shortcut.as_deref(), Some(shortcut.as_path()));
+            assert_eq!(
+                meta[0].working_dir.as_deref().map(normalize_path_key),
+                Some(normalize_path_key(&base))
+            );
+
+            let _ = std::fs::remove_file(&shortcut);
+            let _ = std::fs::remove_file(&target);
+            let _ = std::fs::remove_dir_all(&base);
+        }
     }
 }
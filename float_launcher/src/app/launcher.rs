@@ -0,0 +1,134 @@
+use crate::system::{LaunchError, ShowState};
+use std::path::Path;
+#[cfg(test)]
+use {std::path::PathBuf, std::sync::Mutex};
+
+/// Where pin launches go: the desktop shell in the app, a recorder in tests.
+pub trait Launcher: Send + Sync {
+    /// Opens `path` with `env` added to the environment it starts with,
+    /// its window coming up as `show` says.
+    fn open_with_env(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+        env: &[(String, String)],
+        show: ShowState,
+    ) -> Result<(), LaunchError>;
+
+    fn open_with(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+    ) -> Result<(), LaunchError> {
+        self.open_with_env(path, args, working_dir, &[], ShowState::Normal)
+    }
+
+    fn open(&self, path: &Path) -> Result<(), LaunchError> {
+        self.open_with(path, None, None)
+    }
+
+    /// Runs `path` as administrator, once the UAC prompt is accepted.
+    fn open_elevated(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+    ) -> Result<(), LaunchError>;
+}
+
+/// Launches through the shell, or as a plain process when there's an
+/// environment to pass, which the shell can't. A plain process always
+/// comes up normally.
+pub struct ShellLauncher;
+
+impl Launcher for ShellLauncher {
+    fn open_with_env(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+        env: &[(String, String)],
+        show: ShowState,
+    ) -> Result<(), LaunchError> {
+        if env.is_empty() {
+            crate::system::shell_open_with(path, args, working_dir, show)
+        } else {
+            crate::system::spawn_with_env(path, args, working_dir, env)
+        }
+    }
+
+    fn open_elevated(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+    ) -> Result<(), LaunchError> {
+        crate::system::shell_run_as_admin(path, args, working_dir)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Launch {
+    pub path: PathBuf,
+    pub args: Option<String>,
+    pub working_dir: Option<PathBuf>,
+    pub env: Vec<(String, String)>,
+    pub show: ShowState,
+    pub elevated: bool,
+}
+
+/// Keeps every launch instead of running it, then reports `fail_with` if set.
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingLauncher {
+    pub launches: Mutex<Vec<Launch>>,
+    pub fail_with: Option<LaunchError>,
+}
+
+#[cfg(test)]
+impl RecordingLauncher {
+    fn record(&self, launch: Launch) -> Result<(), LaunchError> {
+        self.launches.lock().unwrap().push(launch);
+        self.fail_with.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+impl Launcher for RecordingLauncher {
+    fn open_with_env(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+        env: &[(String, String)],
+        show: ShowState,
+    ) -> Result<(), LaunchError> {
+        self.record(Launch {
+            path: path.to_path_buf(),
+            args: args.map(str::to_string),
+            working_dir: working_dir.map(Path::to_path_buf),
+            env: env.to_vec(),
+            show,
+            elevated: false,
+        })
+    }
+
+    fn open_elevated(
+        &self,
+        path: &Path,
+        args: Option<&str>,
+        working_dir: Option<&Path>,
+    ) -> Result<(), LaunchError> {
+        self.record(Launch {
+            path: path.to_path_buf(),
+            args: args.map(str::to_string),
+            working_dir: working_dir.map(Path::to_path_buf),
+            env: Vec::new(),
+            show: ShowState::Normal,
+            elevated: true,
+        })
+    }
+}
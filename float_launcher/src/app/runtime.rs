@@ -1,42 +1,101 @@
 use crate::branding::APP_DISPLAY_NAME;
 use crate::events::{IconRequest, IconResult, UserEvent};
 use crate::icons::{
-    extract_icon_with_cache, generate_colored_icon, load_tray_icon_for_app, resize_to_square,
+    extract_command_icon_with_cache, extract_icon_with_cache, load_tray_icons_for_app,
+    resize_to_square, TrayIcons,
 };
+use crate::system::ComGuard;
 use crossbeam_channel::TryRecvError;
 use eframe::egui;
-use log::{error, info};
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem},
-    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuId, MenuItem},
+    MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
 };
-use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+#[cfg(windows)]
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+#[cfg(windows)]
+use windows::Win32::System::Threading::GetCurrentThreadId;
+#[cfg(windows)]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     GetAsyncKeyState, RegisterHotKey, UnregisterHotKey, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT,
-    MOD_SHIFT, VIRTUAL_KEY, VK_CONTROL, VK_F10, VK_F11, VK_F9, VK_MENU, VK_OEM_4, VK_OEM_5,
-    VK_OEM_6, VK_SHIFT,
+    MOD_SHIFT, VIRTUAL_KEY, VK_CONTROL, VK_F10, VK_F11, VK_F8, VK_F9, VK_MENU, VK_OEM_4, VK_OEM_5,
+    VK_OEM_6, VK_SHIFT, VK_SPACE,
 };
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetMessageW, PeekMessageW, MSG, PM_NOREMOVE, WM_HOTKEY,
+    GetMessageW, PeekMessageW, PostThreadMessageW, MSG, PM_NOREMOVE, WM_HOTKEY, WM_QUIT,
 };
 
+#[cfg(windows)]
 pub const HOTKEY_SHOW: &str = "Ctrl+Alt+Shift+[";
+#[cfg(windows)]
 pub const HOTKEY_HIDE: &str = "Ctrl+Alt+Shift+]";
+#[cfg(windows)]
 pub const HOTKEY_QUIT: &str = "Ctrl+Alt+Shift+\\";
+#[cfg(windows)]
+pub const HOTKEY_SEARCH: &str = "Ctrl+Alt+Shift+Space";
 
+#[cfg(windows)]
 const HOTKEY_SHOW_FALLBACK: &str = "Ctrl+Alt+Shift+F9";
+#[cfg(windows)]
 const HOTKEY_HIDE_FALLBACK: &str = "Ctrl+Alt+Shift+F10";
+#[cfg(windows)]
 const HOTKEY_QUIT_FALLBACK: &str = "Ctrl+Alt+Shift+F11";
+#[cfg(windows)]
+const HOTKEY_SEARCH_FALLBACK: &str = "Ctrl+Alt+Shift+F8";
 
+#[cfg(windows)]
 const HOTKEY_ID_SHOW: i32 = 1001;
+#[cfg(windows)]
 const HOTKEY_ID_HIDE: i32 = 1002;
+#[cfg(windows)]
 const HOTKEY_ID_QUIT: i32 = 1003;
+#[cfg(windows)]
+const HOTKEY_ID_SEARCH: i32 = 1004;
+#[cfg(windows)]
+const HOTKEY_ID_NEXT_PROFILE: i32 = 1005;
+#[cfg(windows)]
 const HOTKEY_ID_SHOW_FALLBACK: i32 = 1101;
+#[cfg(windows)]
 const HOTKEY_ID_HIDE_FALLBACK: i32 = 1102;
+#[cfg(windows)]
 const HOTKEY_ID_QUIT_FALLBACK: i32 = 1103;
+#[cfg(windows)]
+const HOTKEY_ID_SEARCH_FALLBACK: i32 = 1104;
+
+/// `(action, hotkey, fallback hotkey)` for the global hotkeys, as shown in
+/// the context menu; empty where none are registered.
+pub fn hotkey_help() -> &'static [(&'static str, &'static str, &'static str)] {
+    #[cfg(windows)]
+    {
+        &[
+            ("Show", HOTKEY_SHOW, HOTKEY_SHOW_FALLBACK),
+            ("Hide", HOTKEY_HIDE, HOTKEY_HIDE_FALLBACK),
+            ("Search", HOTKEY_SEARCH, HOTKEY_SEARCH_FALLBACK),
+            ("Quit", HOTKEY_QUIT, HOTKEY_QUIT_FALLBACK),
+        ]
+    }
+    #[cfg(not(windows))]
+    {
+        &[]
+    }
+}
+
+/// Tray menu ids for pin entries are this prefix followed by the pin's launch key.
+pub const TRAY_PIN_ID_PREFIX: &str = "pin:";
+/// Tray menu ids for profile entries are this prefix followed by the profile's index.
+pub const TRAY_PROFILE_ID_PREFIX: &str = "profile:";
+
+/// How long a tray/hotkey quit waits for the UI to shut down cleanly before forcing exit.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RuntimeAction {
@@ -44,8 +103,25 @@ enum RuntimeAction {
     Hide,
     Toggle,
     Quit,
+    /// Show the dock with the search box focused.
+    Search,
+    /// The foreground window went fullscreen (true) or stopped being so.
+    Fullscreen(bool),
+    /// Switch to the profile after the active one; only the native hotkey sends it.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    NextProfile,
 }
 
+/// The runtime's view of the window, owned by the event-loop thread.
+#[derive(Default)]
+struct Visibility {
+    shown: bool,
+    /// Hidden by the fullscreen watcher rather than the user, so it comes
+    /// back when the fullscreen window goes away.
+    by_fullscreen: bool,
+}
+
+#[cfg(windows)]
 #[derive(Clone, Copy)]
 struct HotkeyBinding {
     id: i32,
@@ -54,7 +130,8 @@ struct HotkeyBinding {
     label: &'static str,
 }
 
-const HOTKEY_BINDINGS: [HotkeyBinding; 6] = [
+#[cfg(windows)]
+const HOTKEY_BINDINGS: [HotkeyBinding; 8] = [
     HotkeyBinding {
         id: HOTKEY_ID_SHOW,
         vk: VK_OEM_4.0 as u32,
@@ -73,6 +150,12 @@ const HOTKEY_BINDINGS: [HotkeyBinding; 6] = [
         action: RuntimeAction::Quit,
         label: HOTKEY_QUIT,
     },
+    HotkeyBinding {
+        id: HOTKEY_ID_SEARCH,
+        vk: VK_SPACE.0 as u32,
+        action: RuntimeAction::Search,
+        label: HOTKEY_SEARCH,
+    },
     HotkeyBinding {
         id: HOTKEY_ID_SHOW_FALLBACK,
         vk: VK_F9.0 as u32,
@@ -91,57 +174,100 @@ const HOTKEY_BINDINGS: [HotkeyBinding; 6] = [
         action: RuntimeAction::Quit,
         label: HOTKEY_QUIT_FALLBACK,
     },
+    HotkeyBinding {
+        id: HOTKEY_ID_SEARCH_FALLBACK,
+        vk: VK_F8.0 as u32,
+        action: RuntimeAction::Search,
+        label: HOTKEY_SEARCH_FALLBACK,
+    },
 ];
 
 pub struct RuntimeHandles {
     pub tray_icon: TrayIcon,
+    /// Kept so the UI can swap the pin entries in and out as pins change.
+    pub tray_menu: Menu,
     pub rx: Receiver<UserEvent>,
+    /// For UI-side workers that report back through the event queue.
+    pub ui_tx: Sender<UserEvent>,
     pub icon_req_tx: Sender<IconRequest>,
     pub toggle_item: MenuItem,
-    pub icon_awake: Icon,
-    pub icon_sleep: Icon,
+    pub icons: TrayIcons,
+    pub hotkey_thread: Arc<AtomicU32>,
+    pub watched_paths: WatchedPaths,
 }
 
-pub fn build_runtime(ctx: &egui::Context) -> RuntimeHandles {
+pub fn build_runtime(
+    ctx: &egui::Context,
+    start_visible: bool,
+    confirm_quit: Arc<AtomicBool>,
+    hide_in_fullscreen: Arc<AtomicBool>,
+    next_profile_vk: Option<u32>,
+) -> RuntimeHandles {
     let (icon_req_tx, icon_req_rx) = mpsc::channel::<IconRequest>();
     let (ui_tx, ui_rx) = mpsc::channel::<UserEvent>();
     let (action_tx, action_rx) = mpsc::channel::<RuntimeAction>();
 
     spawn_icon_worker(icon_req_rx, ui_tx.clone(), ctx.clone());
+    let watched_paths = WatchedPaths::default();
+    spawn_mtime_watcher(watched_paths.clone(), ui_tx.clone(), ctx.clone());
 
-    let base_icon =
-        load_tray_icon_for_app(32).unwrap_or_else(|| generate_colored_icon([45, 190, 150, 255]));
-    let icon_awake = base_icon.clone();
-    let icon_sleep = base_icon;
+    let icons = load_tray_icons_for_app(32);
 
     let tray_menu = Menu::new();
     let toggle_item = MenuItem::new("Hide", true, None);
+    let search_item = MenuItem::new("Search…", true, None);
     let quit_item = MenuItem::new("Quit", true, None);
     tray_menu
-        .append_items(&[&toggle_item, &quit_item])
+        .append_items(&[&toggle_item, &search_item, &quit_item])
         .expect("failed to append tray menu");
 
     let tray_icon = TrayIconBuilder::new()
-        .with_menu(Box::new(tray_menu))
+        .with_menu(Box::new(tray_menu.clone()))
         .with_tooltip(APP_DISPLAY_NAME)
-        .with_icon(icon_awake.clone())
+        .with_icon(icons.awake.clone())
         .build()
         .expect("failed to create tray icon");
 
-    let toggle_id = toggle_item.id().clone();
-    let quit_id = quit_item.id().clone();
+    let menu_ids = TrayMenuIds {
+        toggle: toggle_item.id().clone(),
+        search: search_item.id().clone(),
+        quit: quit_item.id().clone(),
+    };
 
-    spawn_native_hotkey_worker(action_tx.clone());
-    spawn_hotkey_polling_fallback(action_tx);
-    spawn_runtime_event_loop(ui_tx, action_rx, ctx.clone(), toggle_id, quit_id);
+    let hotkey_thread = Arc::new(AtomicU32::new(0));
+    spawn_native_hotkey_worker(action_tx.clone(), hotkey_thread.clone(), next_profile_vk);
+    spawn_hotkey_polling_fallback(action_tx.clone());
+    spawn_fullscreen_watcher(action_tx, hide_in_fullscreen);
+    spawn_runtime_event_loop(
+        ui_tx.clone(),
+        action_rx,
+        ctx.clone(),
+        menu_ids,
+        start_visible,
+        confirm_quit,
+    );
 
     RuntimeHandles {
         tray_icon,
+        tray_menu,
         rx: ui_rx,
+        ui_tx,
         icon_req_tx,
         toggle_item,
-        icon_awake,
-        icon_sleep,
+        icons,
+        hotkey_thread,
+        watched_paths,
+    }
+}
+
+/// Ends the native hotkey worker's message loop so it unregisters its hotkeys.
+pub fn stop_hotkey_worker(thread_id: &AtomicU32) {
+    let id = thread_id.swap(0, Ordering::SeqCst);
+    if id != 0 {
+        #[cfg(windows)]
+        unsafe {
+            let _ = PostThreadMessageW(id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
     }
 }
 
@@ -151,27 +277,41 @@ fn spawn_icon_worker(
     ctx: egui::Context,
 ) {
     thread::spawn(move || {
-        let com_initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+        let _com = ComGuard::init();
         while let Ok(req) = icon_req_rx.recv() {
             let side = req.size.clamp(16, 256) as usize;
-            let image = extract_icon_with_cache(&req.path, req.name_hint.as_deref())
-                .map(|img| resize_to_square(&img, side));
+            let image = if req.command {
+                extract_command_icon_with_cache(&req.path, req.name_hint.as_deref())
+            } else {
+                extract_icon_with_cache(&req.path, req.name_hint.as_deref())
+            }
+            .map(|img| resize_to_square(&img, side));
+            if image.is_none() {
+                warn!("no icon could be extracted for {}", req.path.display());
+            }
             let _ = tx.send(UserEvent::IconReady(IconResult {
                 path: req.path,
+                size: req.size,
                 image,
             }));
             ctx.request_repaint();
         }
-        if com_initialized {
-            unsafe { CoUninitialize() };
-        }
     });
 }
 
-fn spawn_native_hotkey_worker(action_tx: Sender<RuntimeAction>) {
+/// Registers the fixed bindings plus, when set, the next-profile key. That
+/// one has no polling fallback: polling would fire alongside the native
+/// hotkey and skip a profile.
+#[cfg(windows)]
+fn spawn_native_hotkey_worker(
+    action_tx: Sender<RuntimeAction>,
+    thread_id: Arc<AtomicU32>,
+    next_profile_vk: Option<u32>,
+) {
     thread::spawn(move || unsafe {
         let mut init_msg = MSG::default();
         let _ = PeekMessageW(&mut init_msg, None, 0, 0, PM_NOREMOVE);
+        thread_id.store(GetCurrentThreadId(), Ordering::SeqCst);
 
         let mods = MOD_ALT | MOD_CONTROL | MOD_SHIFT | MOD_NOREPEAT;
         let mut registered_count = 0usize;
@@ -189,6 +329,12 @@ fn spawn_native_hotkey_worker(action_tx: Sender<RuntimeAction>) {
         if registered_count == 0 {
             error!("no native hotkeys registered; fallback polling remains active");
         }
+        if let Some(vk) = next_profile_vk {
+            match RegisterHotKey(None, HOTKEY_ID_NEXT_PROFILE, mods, vk) {
+                Ok(()) => info!("registered next-profile hotkey (vk {vk:#x})"),
+                Err(err) => error!("failed to register next-profile hotkey (vk {vk:#x}): {err}"),
+            }
+        }
 
         let mut msg = MSG::default();
         loop {
@@ -202,10 +348,14 @@ fn spawn_native_hotkey_worker(action_tx: Sender<RuntimeAction>) {
             }
             if msg.message == WM_HOTKEY {
                 let hotkey_id = msg.wParam.0 as i32;
-                let action = HOTKEY_BINDINGS
-                    .iter()
-                    .find(|binding| binding.id == hotkey_id)
-                    .map(|binding| binding.action);
+                let action = if hotkey_id == HOTKEY_ID_NEXT_PROFILE {
+                    Some(RuntimeAction::NextProfile)
+                } else {
+                    HOTKEY_BINDINGS
+                        .iter()
+                        .find(|binding| binding.id == hotkey_id)
+                        .map(|binding| binding.action)
+                };
                 if let Some(action) = action {
                     let _ = action_tx.send(action);
                 }
@@ -215,14 +365,29 @@ fn spawn_native_hotkey_worker(action_tx: Sender<RuntimeAction>) {
         for binding in HOTKEY_BINDINGS {
             let _ = UnregisterHotKey(None, binding.id);
         }
+        if next_profile_vk.is_some() {
+            let _ = UnregisterHotKey(None, HOTKEY_ID_NEXT_PROFILE);
+        }
+        info!("native hotkeys unregistered");
     });
 }
 
+#[cfg(not(windows))]
+fn spawn_native_hotkey_worker(
+    _action_tx: Sender<RuntimeAction>,
+    _thread_id: Arc<AtomicU32>,
+    _next_profile_vk: Option<u32>,
+) {
+    info!("global hotkeys are only available on Windows");
+}
+
+#[cfg(windows)]
 fn spawn_hotkey_polling_fallback(action_tx: Sender<RuntimeAction>) {
     thread::spawn(move || unsafe {
         let mut prev_show = false;
         let mut prev_hide = false;
         let mut prev_quit = false;
+        let mut prev_search = false;
 
         loop {
             let key_down = |vk: VIRTUAL_KEY| (GetAsyncKeyState(vk.0 as i32) as u16 & 0x8000) != 0;
@@ -234,6 +399,7 @@ fn spawn_hotkey_polling_fallback(action_tx: Sender<RuntimeAction>) {
             let show_down = chord_down && (key_down(VK_OEM_4) || key_down(VK_F9));
             let hide_down = chord_down && (key_down(VK_OEM_6) || key_down(VK_F10));
             let quit_down = chord_down && (key_down(VK_OEM_5) || key_down(VK_F11));
+            let search_down = chord_down && (key_down(VK_SPACE) || key_down(VK_F8));
 
             if show_down && !prev_show {
                 let _ = action_tx.send(RuntimeAction::Show);
@@ -244,36 +410,193 @@ fn spawn_hotkey_polling_fallback(action_tx: Sender<RuntimeAction>) {
             if quit_down && !prev_quit {
                 let _ = action_tx.send(RuntimeAction::Quit);
             }
+            if search_down && !prev_search {
+                let _ = action_tx.send(RuntimeAction::Search);
+            }
 
             prev_show = show_down;
             prev_hide = hide_down;
             prev_quit = quit_down;
+            prev_search = search_down;
 
             thread::sleep(Duration::from_millis(20));
         }
     });
 }
 
+#[cfg(not(windows))]
+fn spawn_hotkey_polling_fallback(_action_tx: Sender<RuntimeAction>) {}
+
+/// How often the foreground window is checked for fullscreen.
+const FULLSCREEN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reports the foreground window entering and leaving fullscreen while
+/// `enabled` is set; turning it off mid-fullscreen counts as leaving.
+fn spawn_fullscreen_watcher(action_tx: Sender<RuntimeAction>, enabled: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let mut was_fullscreen = false;
+        loop {
+            thread::sleep(FULLSCREEN_POLL_INTERVAL);
+            let fullscreen =
+                enabled.load(Ordering::Relaxed) && crate::system::foreground_is_fullscreen();
+            if fullscreen == was_fullscreen {
+                continue;
+            }
+            was_fullscreen = fullscreen;
+            if action_tx
+                .send(RuntimeAction::Fullscreen(fullscreen))
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+}
+
+/// Pinned targets the mtime watcher polls; the UI replaces the list as pins change.
+pub type WatchedPaths = Arc<Mutex<Vec<PathBuf>>>;
+
+/// How often pinned targets are checked for updates and for being reachable.
+const MTIME_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Modification times of watched files. A change is only reported once the
+/// time has held still for a whole poll, so an installer rewriting the file
+/// several times triggers a single icon refresh.
+#[derive(Default)]
+struct MtimeWatch {
+    seen: HashMap<PathBuf, SystemTime>,
+    /// Changed since `seen`, with the time observed on the last poll.
+    pending: HashMap<PathBuf, SystemTime>,
+    /// Paths that couldn't be read on the last poll.
+    unavailable: HashSet<PathBuf>,
+}
+
+impl MtimeWatch {
+    /// Records `mtime` for `path`; true once a change has settled.
+    fn observe(&mut self, path: &Path, mtime: SystemTime) -> bool {
+        let Some(&seen) = self.seen.get(path) else {
+            self.seen.insert(path.to_path_buf(), mtime);
+            return false;
+        };
+        if mtime == seen {
+            self.pending.remove(path);
+            return false;
+        }
+        match self.pending.insert(path.to_path_buf(), mtime) {
+            Some(last) if last == mtime => {
+                self.pending.remove(path);
+                self.seen.insert(path.to_path_buf(), mtime);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Forgets paths that are no longer watched.
+    fn retain(&mut self, watched: &[PathBuf]) {
+        self.seen.retain(|path, _| watched.contains(path));
+        self.pending.retain(|path, _| watched.contains(path));
+    }
+}
+
+fn spawn_mtime_watcher(watched: WatchedPaths, tx: Sender<UserEvent>, ctx: egui::Context) {
+    thread::spawn(move || {
+        let mut watch = MtimeWatch::default();
+        loop {
+            thread::sleep(MTIME_POLL_INTERVAL);
+            let paths = match watched.lock() {
+                Ok(paths) => paths.clone(),
+                Err(_) => break,
+            };
+            watch.retain(&paths);
+            let mut changed = Vec::new();
+            let mut unavailable = HashSet::new();
+            for path in paths {
+                let Ok(meta) = std::fs::metadata(&path) else {
+                    unavailable.insert(path);
+                    continue;
+                };
+                // Folders change with their contents; only files get new icons.
+                if meta.is_file()
+                    && meta
+                        .modified()
+                        .is_ok_and(|mtime| watch.observe(&path, mtime))
+                {
+                    changed.push(path);
+                }
+            }
+            if unavailable != watch.unavailable {
+                info!("unreachable pinned targets: {}", unavailable.len());
+                watch.unavailable = unavailable.clone();
+                let event = UserEvent::PinsUnavailable(unavailable.into_iter().collect());
+                if tx.send(event).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+            if changed.is_empty() {
+                continue;
+            }
+            info!("pinned targets changed on disk: {}", changed.len());
+            if tx.send(UserEvent::IconsStale(changed)).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+}
+
+/// The fixed tray menu entries; pin entries are matched by id prefix instead.
+struct TrayMenuIds {
+    toggle: MenuId,
+    search: MenuId,
+    quit: MenuId,
+}
+
 fn spawn_runtime_event_loop(
     ui_tx: Sender<UserEvent>,
     action_rx: Receiver<RuntimeAction>,
     ctx: egui::Context,
-    toggle_menu_id: tray_icon::menu::MenuId,
-    quit_menu_id: tray_icon::menu::MenuId,
+    menu_ids: TrayMenuIds,
+    start_visible: bool,
+    confirm_quit: Arc<AtomicBool>,
 ) {
     thread::spawn(move || {
-        let mut is_visible = true;
+        let mut visibility = Visibility {
+            shown: start_visible,
+            ..Default::default()
+        };
         loop {
             while let Ok(action) = action_rx.try_recv() {
-                apply_runtime_action(action, &ui_tx, &ctx, &mut is_visible);
+                apply_runtime_action(action, &ui_tx, &ctx, &mut visibility, &confirm_quit);
             }
 
             match MenuEvent::receiver().try_recv() {
                 Ok(event) => {
-                    if event.id == toggle_menu_id {
-                        apply_runtime_action(RuntimeAction::Toggle, &ui_tx, &ctx, &mut is_visible);
-                    } else if event.id == quit_menu_id {
-                        apply_runtime_action(RuntimeAction::Quit, &ui_tx, &ctx, &mut is_visible);
+                    let action = if event.id == menu_ids.toggle {
+                        Some(RuntimeAction::Toggle)
+                    } else if event.id == menu_ids.search {
+                        Some(RuntimeAction::Search)
+                    } else if event.id == menu_ids.quit {
+                        Some(RuntimeAction::Quit)
+                    } else {
+                        None
+                    };
+                    if let Some(action) = action {
+                        apply_runtime_action(action, &ui_tx, &ctx, &mut visibility, &confirm_quit);
+                    } else if let Some(key) = event.id.0.strip_prefix(TRAY_PIN_ID_PREFIX) {
+                        // Launched by the UI thread, which runs even while hidden.
+                        let _ = ui_tx.send(UserEvent::LaunchPin(key.to_string()));
+                        ctx.request_repaint();
+                    } else if let Some(index) = event
+                        .id
+                        .0
+                        .strip_prefix(TRAY_PROFILE_ID_PREFIX)
+                        .and_then(|index| index.parse().ok())
+                    {
+                        // Switched by the UI thread too, shown or hidden.
+                        let _ = ui_tx.send(UserEvent::SwitchProfile(index));
+                        ctx.request_repaint();
                     }
                 }
                 Err(err) => {
@@ -291,7 +614,13 @@ fn spawn_runtime_event_loop(
                         ..
                     } = event
                     {
-                        apply_runtime_action(RuntimeAction::Toggle, &ui_tx, &ctx, &mut is_visible);
+                        apply_runtime_action(
+                            RuntimeAction::Toggle,
+                            &ui_tx,
+                            &ctx,
+                            &mut visibility,
+                            &confirm_quit,
+                        );
                     }
                 }
                 Err(err) => {
@@ -310,35 +639,101 @@ fn apply_runtime_action(
     action: RuntimeAction,
     ui_tx: &Sender<UserEvent>,
     ctx: &egui::Context,
-    is_visible: &mut bool,
+    visibility: &mut Visibility,
+    confirm_quit: &AtomicBool,
 ) {
     match action {
         RuntimeAction::Show => {
-            *is_visible = true;
+            visibility.shown = true;
+            visibility.by_fullscreen = false;
+            // Focus is the UI's call, per `focus_on_show`.
             ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
             ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
-            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
             let _ = ui_tx.send(UserEvent::Show);
             ctx.request_repaint();
         }
         RuntimeAction::Hide => {
-            if *is_visible {
-                *is_visible = false;
-                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            visibility.by_fullscreen = false;
+            if visibility.shown {
+                visibility.shown = false;
+                // The UI fades the panel out and hides the window when it's done.
                 let _ = ui_tx.send(UserEvent::Hide);
                 ctx.request_repaint();
             }
         }
         RuntimeAction::Toggle => {
-            if *is_visible {
-                apply_runtime_action(RuntimeAction::Hide, ui_tx, ctx, is_visible);
+            if visibility.shown {
+                apply_runtime_action(RuntimeAction::Hide, ui_tx, ctx, visibility, confirm_quit);
             } else {
-                apply_runtime_action(RuntimeAction::Show, ui_tx, ctx, is_visible);
+                apply_runtime_action(RuntimeAction::Show, ui_tx, ctx, visibility, confirm_quit);
             }
         }
+        RuntimeAction::NextProfile => {
+            // Switched by the UI thread, which runs even while hidden, so the
+            // dock stays where it is, as with the tray's profile picks.
+            let _ = ui_tx.send(UserEvent::NextProfile);
+            ctx.request_repaint();
+        }
+        RuntimeAction::Search => {
+            apply_runtime_action(RuntimeAction::Show, ui_tx, ctx, visibility, confirm_quit);
+            let _ = ui_tx.send(UserEvent::ShowSearch);
+            ctx.request_repaint();
+        }
         RuntimeAction::Quit => {
+            if confirm_quit.load(Ordering::Relaxed) {
+                // Bring the panel up so the prompt is visible; the UI owns the exit.
+                apply_runtime_action(RuntimeAction::Show, ui_tx, ctx, visibility, confirm_quit);
+                let _ = ui_tx.send(UserEvent::QuitRequested);
+                ctx.request_repaint();
+                return;
+            }
+            // The UI saves config and drops the tray icon; only force the exit
+            // if it never gets to run (e.g. the window can't repaint). The
+            // watchdog waits on its own thread so tray and hotkey events keep
+            // flowing meanwhile.
             let _ = ui_tx.send(UserEvent::Quit);
-            std::process::exit(0);
+            ctx.request_repaint();
+            thread::spawn(|| {
+                thread::sleep(SHUTDOWN_GRACE);
+                error!("UI did not shut down in time; forcing exit");
+                std::process::exit(0);
+            });
         }
+        RuntimeAction::Fullscreen(true) => {
+            if visibility.shown {
+                apply_runtime_action(RuntimeAction::Hide, ui_tx, ctx, visibility, confirm_quit);
+                visibility.by_fullscreen = true;
+            }
+        }
+        RuntimeAction::Fullscreen(false) => {
+            if visibility.by_fullscreen {
+                apply_runtime_action(RuntimeAction::Show, ui_tx, ctx, visibility, confirm_quit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mtime_change_reports_once_after_it_settles() {
+        let path = Path::new("app.exe");
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let mut watch = MtimeWatch::default();
+
+        assert!(!watch.observe(path, at(10)));
+        assert!(!watch.observe(path, at(10)));
+        // An update in progress keeps rewriting the file.
+        assert!(!watch.observe(path, at(20)));
+        assert!(!watch.observe(path, at(25)));
+        assert!(watch.observe(path, at(25)));
+        assert!(!watch.observe(path, at(25)));
+
+        // Forgotten paths start over without reporting.
+        watch.retain(&[]);
+        assert!(!watch.observe(path, at(30)));
+        assert!(!watch.observe(path, at(30)));
     }
 }
@@ -0,0 +1,187 @@
+use eframe::egui;
+
+const MATCH_SCORE: i32 = 16;
+/// First letter of a word, after a separator or a lower→upper case change.
+const WORD_START_BONUS: i32 = 24;
+const CONSECUTIVE_BONUS: i32 = 16;
+/// Per character skipped between two matched characters.
+const GAP_PENALTY: i32 = 3;
+/// Per character before the first match, capped so long prefixes still rank.
+const LEADING_GAP_PENALTY: i32 = 1;
+const LEADING_GAP_CAP: i32 = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Char (not byte) indices of the matched characters in the name.
+    pub positions: Vec<usize>,
+}
+
+/// Matches `query` as a case-insensitive subsequence of `name`, picking the
+/// alignment that favours word starts and runs, so "vsc" lands on the
+/// initials of "Visual Studio Code". Whitespace in the query is ignored.
+pub fn fuzzy_match(query: &str, name: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(fold_case)
+        .collect();
+    let chars: Vec<char> = name.chars().collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    if query.len() > chars.len() {
+        return None;
+    }
+    let folded: Vec<char> = chars.iter().copied().map(fold_case).collect();
+    let bonus: Vec<i32> = (0..chars.len())
+        .map(|j| {
+            let word_start = j == 0 || {
+                let prev = chars[j - 1];
+                !prev.is_alphanumeric() || (prev.is_lowercase() && chars[j].is_uppercase())
+            };
+            MATCH_SCORE + if word_start { WORD_START_BONUS } else { 0 }
+        })
+        .collect();
+
+    // best[i][j]: top score with query[i] matched at name[j]; from[i][j] is
+    // where query[i - 1] sat on that path.
+    let mut best = vec![vec![None::<i32>; chars.len()]; query.len()];
+    let mut from = vec![vec![0usize; chars.len()]; query.len()];
+    for (j, c) in folded.iter().enumerate() {
+        if *c == query[0] {
+            let leading = (j as i32 * LEADING_GAP_PENALTY).min(LEADING_GAP_CAP);
+            best[0][j] = Some(bonus[j] - leading);
+        }
+    }
+    for i in 1..query.len() {
+        for j in i..chars.len() {
+            if folded[j] != query[i] {
+                continue;
+            }
+            for k in (i - 1)..j {
+                let Some(prev) = best[i - 1][k] else {
+                    continue;
+                };
+                let link = if k + 1 == j {
+                    CONSECUTIVE_BONUS
+                } else {
+                    -((j - k - 1) as i32) * GAP_PENALTY
+                };
+                let score = prev + link + bonus[j];
+                let better = match best[i][j] {
+                    Some(current) => score > current,
+                    None => true,
+                };
+                if better {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|s| (j, s)))
+        .fold(None, |top: Option<(usize, i32)>, (j, s)| match top {
+            Some((_, t)) if t >= s => top,
+            _ => Some((j, s)),
+        })?;
+    let mut positions = vec![0; query.len()];
+    for i in (0..query.len()).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Indices of the names that match, best first; equal scores keep their
+/// original order.
+pub fn rank<'a>(query: &str, names: impl IntoIterator<Item = &'a str>) -> Vec<(usize, FuzzyMatch)> {
+    let mut hits: Vec<(usize, FuzzyMatch)> = names
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, name)| fuzzy_match(query, name).map(|m| (idx, m)))
+        .collect();
+    hits.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    hits
+}
+
+/// `name` laid out with the matched characters in `highlight`.
+pub fn highlighted_name(
+    name: &str,
+    positions: &[usize],
+    font: egui::FontId,
+    color: egui::Color32,
+    highlight: egui::Color32,
+) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (idx, c) in name.chars().enumerate() {
+        let matched = positions.contains(&idx);
+        if matched != run_matched && !run.is_empty() {
+            let run_color = if run_matched { highlight } else { color };
+            job.append(
+                &std::mem::take(&mut run),
+                0.0,
+                egui::TextFormat::simple(font.clone(), run_color),
+            );
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        let run_color = if run_matched { highlight } else { color };
+        job.append(&run, 0.0, egui::TextFormat::simple(font, run_color));
+    }
+    job
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, name: &str) -> i32 {
+        fuzzy_match(query, name).expect("should match").score
+    }
+
+    #[test]
+    fn acronym_lands_on_word_starts() {
+        let m = fuzzy_match("vsc", "Visual Studio Code").expect("should match");
+        assert_eq!(m.positions, [0, 7, 14]);
+        let m = fuzzy_match("VSC", "visualStudioCode").expect("should match");
+        assert_eq!(m.positions, [0, 6, 12]);
+        assert!(score("vsc", "Visual Studio Code") > score("vsc", "Visual Basic Script Console"));
+    }
+
+    #[test]
+    fn gaps_cost_and_order_matters() {
+        assert!(score("abc", "abxc") > score("abc", "axxxbxxxc"));
+        assert!(score("note", "Notepad") > score("note", "Nothing To Edit"));
+        assert_eq!(
+            fuzzy_match("ntpd", "Notepad").map(|m| m.positions),
+            Some(vec![0, 2, 4, 6])
+        );
+        assert_eq!(fuzzy_match("dap", "Notepad"), None);
+        assert_eq!(fuzzy_match("notepad++", "Notepad"), None);
+    }
+
+    #[test]
+    fn rank_sorts_by_score_and_keeps_order_on_ties() {
+        let names = ["Paint", "Code", "Notepad", "Code", "Calculator"];
+        let order: Vec<usize> = rank("co", names).into_iter().map(|(idx, _)| idx).collect();
+        assert_eq!(order, [1, 3, 4]);
+        assert_eq!(rank(" ", names).len(), names.len());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::system::ShowState;
 use eframe::egui;
 use std::path::PathBuf;
 use std::time::Instant;
@@ -7,8 +8,27 @@ pub struct PinnedApp {
     pub path: PathBuf,
     pub launch_args: Option<String>,
     pub working_dir: Option<PathBuf>,
+    /// Shortcut the pin was resolved from, if it came from one.
+    pub shortcut: Option<PathBuf>,
+    pub favorite: bool,
+    /// Program the pin is opened with instead of the system default.
+    pub opener: Option<PathBuf>,
+    /// `path` holds a command line (program and arguments) instead of a file.
+    pub command: bool,
+    /// Extra environment variables for the launch.
+    pub env: Vec<(String, String)>,
+    /// How the app's window comes up when launched.
+    pub show_state: ShowState,
+    /// The target couldn't be reached on the last check, e.g. on a network
+    /// drive that isn't mounted; the pin is drawn dimmed until it's back.
+    pub unavailable: bool,
     pub texture: Option<egui::TextureHandle>,
     pub icon_requested: bool,
+    /// The icon worker answered without an image; show the flat placeholder.
+    pub icon_failed: bool,
+    /// When the single retry after a failed extraction is due.
+    pub icon_retry_at: Option<Instant>,
+    pub icon_retried: bool,
 }
 
 impl PinnedApp {
@@ -36,16 +56,94 @@ impl PinnedApp {
             path,
             launch_args,
             working_dir,
+            shortcut: None,
+            favorite: false,
+            opener: None,
+            command: false,
+            env: Vec::new(),
+            show_state: ShowState::Normal,
+            unavailable: false,
             texture: None,
             icon_requested: false,
+            icon_failed: false,
+            icon_retry_at: None,
+            icon_retried: false,
         }
     }
 }
 
 pub struct DropAnim {
     pub item: PinnedApp,
+    /// Where the pin goes back into `pinned_apps`.
     pub insert_at: usize,
+    /// The list slot held open for it until it lands, in drawing order.
+    pub slot: usize,
     pub start: Instant,
     pub start_y: f32,
     pub end_y: f32,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// One message in the toast stack; repeats of a live toast bump `count`.
+pub struct Toast {
+    pub severity: Severity,
+    pub message: String,
+    pub count: usize,
+    pub start: Instant,
+}
+
+/// Row under the pointer and when it was first hovered, for the delayed hover card.
+pub struct HoverCard {
+    pub index: usize,
+    pub since: Instant,
+    pub anchor: egui::Rect,
+    /// Cleared after each frame; a row that is still hovered sets it again.
+    pub seen: bool,
+}
+
+/// A launch waiting on the confirmation prompt; the pin is looked up again by
+/// key when confirmed, in case the list changed meanwhile.
+pub struct PendingLaunch {
+    pub key: String,
+    pub name: String,
+    pub elevated: bool,
+    /// Set after the prompt's first frame; keys only answer it from then on.
+    pub shown: bool,
+}
+
+/// Fields of the "Launch options" dialog while it is open; the pin is
+/// found again by `key` when saved.
+pub struct LaunchOptionsDraft {
+    pub key: String,
+    pub name: String,
+    pub args: String,
+    pub working_dir: String,
+    /// One `NAME=value` per line.
+    pub env: String,
+    /// Only programs take an environment; folders and documents open
+    /// through the shell, which can't pass one.
+    pub takes_env: bool,
+    pub show_state: ShowState,
+}
+
+/// Fields of the "New command pin" dialog while it is open.
+#[derive(Default)]
+pub struct CommandDraft {
+    pub command: String,
+    pub working_dir: String,
+    pub name: String,
+}
+
+pub struct ImportCandidate {
+    pub name: String,
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub already_pinned: bool,
+    pub checked: bool,
+}
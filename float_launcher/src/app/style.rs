@@ -1,9 +1,12 @@
-use crate::config::WindowShape;
-use eframe::egui::Color32;
+use super::state::Severity;
+use crate::config::{Appearance, WindowShape};
+use eframe::egui::{Color32, Stroke, Vec2};
 
 pub const HEADER_HEIGHT: f32 = 28.0;
 pub const ROW_HEIGHT: f32 = 46.0;
 pub const CONTENT_PADDING: f32 = 9.0;
+/// `CONTENT_PADDING` in the minimal appearance.
+pub const MINIMAL_CONTENT_PADDING: f32 = 4.0;
 pub const ICON_SIDE: f32 = 20.0;
 pub const DROP_SHADOW: f32 = 8.0;
 
@@ -22,6 +25,15 @@ pub struct LauncherTheme {
     pub drop_hint: Color32,
     pub toast_bg: Color32,
     pub toast_text: Color32,
+    pub toast_warning: Color32,
+    pub toast_error: Color32,
+    pub snap_guide: Color32,
+    /// Stroke width of drop hints; snap guides are drawn twice as thick.
+    pub hint_width: f32,
+    /// Space between the panel edge and the rows.
+    pub content_padding: f32,
+    /// Whether the soft glows behind the header are painted.
+    pub glow: bool,
 }
 
 impl Default for LauncherTheme {
@@ -40,14 +52,88 @@ impl Default for LauncherTheme {
             drop_hint: Color32::from_rgba_premultiplied(93, 214, 189, 186),
             toast_bg: Color32::from_rgba_premultiplied(8, 12, 18, 236),
             toast_text: Color32::from_rgb(245, 250, 255),
+            toast_warning: Color32::from_rgb(236, 178, 72),
+            toast_error: Color32::from_rgb(232, 86, 86),
+            snap_guide: Color32::from_rgba_premultiplied(75, 197, 165, 160),
+            hint_width: 1.0,
+            content_padding: CONTENT_PADDING,
+            glow: true,
         }
     }
 }
 
-pub fn rounding(shape: WindowShape) -> f32 {
+impl LauncherTheme {
+    /// Opaque fills, strong borders and bright text for the OS high-contrast mode.
+    pub fn high_contrast() -> Self {
+        Self {
+            panel_bg_bottom: Color32::BLACK,
+            panel_border: Color32::WHITE,
+            panel_shadow: Color32::TRANSPARENT,
+            header_bg_bottom: Color32::from_rgb(16, 16, 16),
+            title_color: Color32::WHITE,
+            row_bg: Color32::BLACK,
+            row_hover: Color32::from_rgb(0, 0, 140),
+            row_selected: Color32::from_rgb(0, 110, 220),
+            row_border: Color32::WHITE,
+            icon_placeholder: Color32::from_rgb(200, 200, 200),
+            drop_hint: Color32::YELLOW,
+            toast_bg: Color32::BLACK,
+            toast_text: Color32::WHITE,
+            toast_warning: Color32::YELLOW,
+            toast_error: Color32::from_rgb(255, 64, 64),
+            snap_guide: Color32::YELLOW,
+            hint_width: 2.5,
+            content_padding: CONTENT_PADDING,
+            glow: true,
+        }
+    }
+
+    /// Drops the glows and tightens the padding for `Appearance::Minimal`.
+    pub fn with_appearance(mut self, appearance: Appearance) -> Self {
+        if appearance == Appearance::Minimal {
+            self.content_padding = MINIMAL_CONTENT_PADDING;
+            self.glow = false;
+        }
+        self
+    }
+
+    /// Toast outline: neutral for info, amber for warnings, red for errors.
+    pub fn toast_stroke(&self, severity: Severity) -> Stroke {
+        match severity {
+            Severity::Info => Stroke::new(1.0, self.row_border),
+            Severity::Warning => Stroke::new(1.5, self.toast_warning),
+            Severity::Error => Stroke::new(2.0, self.toast_error),
+        }
+    }
+
+    pub fn drop_hint_stroke(&self) -> Stroke {
+        Stroke::new(self.hint_width, self.drop_hint)
+    }
+
+    pub fn snap_guide_stroke(&self) -> Stroke {
+        Stroke::new(self.hint_width * 2.0, self.snap_guide)
+    }
+}
+
+pub fn rounding(shape: WindowShape, size: Vec2) -> f32 {
+    let max_radius = (size.min_elem() * 0.5).max(0.0);
     match shape {
-        WindowShape::Circle => 210.0,
-        WindowShape::Square => 6.0,
-        WindowShape::RoundedRect => 20.0,
+        WindowShape::Pill => max_radius,
+        WindowShape::Square => 6.0_f32.min(max_radius),
+        WindowShape::RoundedRect => 20.0_f32.min(max_radius),
     }
 }
+
+/// How far a corner arc of `radius` sits inside the rect edge at `depth` from the other edge.
+pub fn arc_inset(radius: f32, depth: f32) -> f32 {
+    if depth >= radius {
+        return 0.0;
+    }
+    let dy = radius - depth.max(0.0);
+    radius - (radius * radius - dy * dy).max(0.0).sqrt()
+}
+
+/// Inset that keeps a rect's corners on or inside the arc (45° point).
+pub fn corner_inset(radius: f32) -> f32 {
+    radius * (1.0 - std::f32::consts::FRAC_1_SQRT_2)
+}
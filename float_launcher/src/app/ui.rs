@@ -1,36 +1,81 @@
-use super::state::{DropAnim, PinnedApp};
+use super::launcher::Launcher;
+use super::search;
+use super::state::{
+    CommandDraft, DropAnim, HoverCard, ImportCandidate, LaunchOptionsDraft, PendingLaunch,
+    PinnedApp, Severity,
+};
 use super::style::{
-    rounding, LauncherTheme, CONTENT_PADDING, DROP_SHADOW, HEADER_HEIGHT, ICON_SIDE, ROW_HEIGHT,
+    arc_inset, corner_inset, rounding, LauncherTheme, DROP_SHADOW, HEADER_HEIGHT, ICON_SIDE,
+    ROW_HEIGHT,
 };
 use super::{
-    ease_out_elastic, sanitize_window_size, MyApp, ResizeDragState, ResizeEdge, MAX_PINNED_APPS,
+    pin_launch_key, pinned_app_from_meta, sanitize_window_size, MyApp, ResizeDragState, ResizeEdge,
     MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH,
 };
 use crate::branding::APP_DISPLAY_NAME;
-use crate::config::{TwoColumnEntry, TwoColumnLayout};
-use crate::events::{IconRequest, UserEvent};
-use crate::system::set_auto_start;
+use crate::config::{
+    normalize_launch_key, ActivationMode, AnimationConfig, AppConfig, Appearance, DockLevel,
+    GridLayout, HideMethod, MissingPins, PinGroup, PinnedLaunchMeta, ThemeMode, TwoColumnEntry,
+    WindowShape, MAX_GRID_COLS, MAX_GRID_ROWS, MAX_PIN_LIMIT, MAX_UI_SCALE, MIN_UI_SCALE,
+};
+use crate::events::{FilePick, IconRequest, UserEvent};
+use crate::system::{set_auto_start, LaunchError, ShowState};
 use eframe::egui;
-use log::info;
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-const REORDER_HOLD_MS: u64 = 260;
-const REORDER_MOVE_TOLERANCE: f32 = 18.0;
 const RESIZE_EDGE_THICKNESS: f32 = 6.0;
 const RESIZE_CORNER_SIZE: f32 = 14.0;
+/// The top edge shares its space with the header's drag area, so its zone
+/// is only the outermost few pixels.
+const RESIZE_TOP_THICKNESS: f32 = 4.0;
+/// Geometry changes smaller than this, in points, aren't sent while resizing.
+const RESIZE_SEND_THRESHOLD: f32 = 0.5;
+const COLUMN_SWITCH_HYSTERESIS: f32 = 12.0;
+const GRID_MIN_CELL_WIDTH: f32 = 110.0;
+const GRID_COL_GAP: f32 = 8.0;
+const GRID_ROW_GAP: f32 = 6.0;
+const REORDER_GRIP_WIDTH: f32 = 14.0;
+/// How far folder pins sit in from the folder row above them.
+const FOLDER_INDENT: f32 = 16.0;
+/// Middle share of a row's height where dropping a pin merges it into a folder.
+const MERGE_ZONE: f32 = 0.5;
+/// Wheel travel (points) that moves the selection by one pin.
+const WHEEL_CYCLE_STEP: f32 = 40.0;
+const WHEEL_PREVIEW_MS: u64 = 900;
+const ICON_RETRY_DELAY: Duration = Duration::from_secs(5);
 const MIN_VISIBLE_WIDTH: f32 = 72.0;
+/// Header plus two rows stay above the bottom of the screen.
+const MIN_VISIBLE_HEIGHT: f32 = HEADER_HEIGHT + 2.0 * ROW_HEIGHT;
+const DRAG_SCROLL_EDGE: f32 = 32.0;
+const DRAG_SCROLL_MAX_STEP: f32 = 14.0;
+const SEARCH_BAR_HEIGHT: f32 = 26.0;
+const FAVORITE_STAR_WIDTH: f32 = 18.0;
+/// Dropped file refused while only programs, shortcuts and folders pin.
+const UNSUPPORTED_PIN_TOAST: &str = "Only .exe/.lnk/folder is supported";
 
 impl eframe::App for MyApp {
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
         [0.0, 0.0, 0.0, 0.0]
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.sync_frame_effects(frame);
+        self.sync_capture_exclusion(frame);
+        self.sync_tool_window(frame);
+        self.remember_monitor_size(ctx);
+        self.watch_display_changes(ctx);
         self.handle_runtime_events(ctx);
+        self.finish_ui_rescale(ctx);
+        self.update_tray_spin(ctx);
         self.update_panel_animation(ctx);
         self.update_drop_animation(ctx);
         self.handle_dropped_files(ctx);
+        self.handle_reorder_keys(ctx);
+        self.handle_search_keys(ctx);
+        self.handle_launch_key(ctx);
 
         if self.handle_fade_out(ctx) {
             return;
@@ -38,395 +83,1896 @@ impl eframe::App for MyApp {
         if !self.is_visible {
             return;
         }
+        self.poll_system_high_contrast(ctx);
 
-        let app_to_remove = self.draw_main_panel(ctx);
-
-        if let Some(index) = app_to_remove {
-            if index < self.pinned_apps.len() {
-                self.pinned_apps.remove(index);
-                if self.config.two_column_mode {
-                    self.sync_two_column_layout_from_current();
-                }
-                self.sync_config_pins();
-
-                if let Some(sel) = self.selected_app {
-                    self.selected_app = if sel == index {
-                        None
-                    } else if sel > index {
-                        Some(sel - 1)
-                    } else {
-                        Some(sel)
-                    };
-                }
-            }
+        let mut apps_to_remove = self.draw_main_panel(ctx);
+        self.sync_window_region(ctx, frame);
+        if let Some(idx) = self.pending_favorite.take() {
+            self.toggle_favorite(idx);
+        }
+        if apps_to_remove.is_empty()
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_pressed(egui::Key::Delete))
+        {
+            apps_to_remove = self.selected_indices();
         }
+        self.remove_pins(apps_to_remove);
+        // Pins launched from this frame's clicks start the tray spin; keep it going.
+        self.update_tray_spin(ctx);
     }
 }
 
 impl MyApp {
-    fn handle_runtime_events(&mut self, ctx: &egui::Context) {
-        while let Ok(event) = self.rx.try_recv() {
-            match event {
-                UserEvent::Show => self.start_show_transition(ctx),
-                UserEvent::Hide => self.start_hide_transition(),
-                UserEvent::Quit => {
-                    info!("Exiting application...");
-                    std::process::exit(0);
-                }
-                UserEvent::IconReady(result) => {
-                    for app in &mut self.pinned_apps {
-                        if app.path == result.path {
-                            if let Some(img) = &result.image {
-                                let tex_name = format!("icon:{}", app.path.to_string_lossy());
-                                app.texture = Some(ctx.load_texture(
-                                    tex_name,
-                                    img.clone(),
-                                    egui::TextureOptions::LINEAR,
-                                ));
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
+    fn remove_pins(&mut self, mut indices: Vec<usize>) {
+        indices.retain(|&index| index < self.pinned_apps.len());
+        if indices.is_empty() {
+            return;
         }
-    }
-
-    fn update_panel_animation(&mut self, ctx: &egui::Context) {
-        if let Some((from, to, start)) = self.panel_anim {
-            let elapsed = start.elapsed();
-            let duration = Duration::from_millis(220);
-            let t = (elapsed.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
-            let eased = ease_out_elastic(t);
-            self.panel_frac = from + (to - from) * eased;
-            if t >= 1.0 {
-                self.panel_anim = None;
-                self.panel_frac = to;
-            } else {
-                ctx.request_repaint();
+        // Remove from the back so earlier indices stay valid.
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        indices.dedup();
+        let mut quick_launch_removed = false;
+        for &index in &indices {
+            let removed = self.pinned_apps.remove(index);
+            if let Some(quick) = &self.config.profile.quick_launch_app {
+                quick_launch_removed |= same_launch_path(quick, &removed.path);
             }
         }
+        if quick_launch_removed {
+            // The quick-launch target followed the pin out; sync_config_pins saves below.
+            self.config.profile.quick_launch_app = None;
+        }
+        self.sync_grid_layout_from_current();
+        self.sync_config_pins();
+
+        self.selected_apps = self
+            .selected_apps
+            .iter()
+            .filter_map(|&sel| shift_index_after_removal(sel, &indices))
+            .collect();
+        self.selected_app = self
+            .selected_app
+            .and_then(|sel| shift_index_after_removal(sel, &indices));
     }
 
-    fn update_drop_animation(&mut self, ctx: &egui::Context) {
-        if let Some(anim) = &self.drop_anim {
-            let elapsed = anim.start.elapsed();
-            let duration = Duration::from_millis(200);
-            let t = (elapsed.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
-            if t >= 1.0 {
-                if let Some(done) = self.drop_anim.take() {
-                    let insert_at = done.insert_at.min(self.pinned_apps.len());
-                    self.pinned_apps.insert(insert_at, done.item);
-                    self.sync_config_pins();
-                    self.selected_app = Some(insert_at);
-                }
-            } else {
-                ctx.request_repaint();
-            }
+    fn open_terminal_in(&mut self, idx: usize) {
+        let Some(dir) = self.pinned_apps.get(idx).map(|app| app.path.clone()) else {
+            return;
+        };
+        let terminal = PathBuf::from(&self.config.terminal);
+        if let Err(err) = self.launcher.open_with(&terminal, None, Some(&dir)) {
+            self.show_toast(Severity::Error, format!("Failed to open terminal: {err}"));
         }
     }
 
-    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
-        if ctx.input(|i| i.raw.dropped_files.is_empty()) {
+    fn fire_quick_launch(&mut self) {
+        let Some(path) = self.config.profile.quick_launch_app.clone() else {
+            return;
+        };
+        if !path.exists() {
+            warn!("Quick-launch target is gone: {}", path.display());
+            self.config.profile.quick_launch_app = None;
+            self.config.save();
+            self.show_toast(Severity::Warning, "Quick-launch app missing; cleared");
             return;
         }
-
-        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
-        let mut changed = false;
-
-        for file in dropped_files {
-            if let Some(path) = file.path {
-                match self.try_add_pin(path) {
-                    AddPinResult::Added => changed = true,
-                    AddPinResult::Duplicate => self.show_warning("Already pinned"),
-                    AddPinResult::Unsupported => {
-                        self.show_warning("Only .exe/.lnk/folder is supported")
-                    }
-                    AddPinResult::ShortcutUnresolved => {
-                        self.show_warning("Shortcut target not found")
-                    }
-                    AddPinResult::Missing => self.show_warning("File not found"),
-                    AddPinResult::LimitReached => {
-                        self.show_warning(format!("Max {} apps", MAX_PINNED_APPS));
-                        break;
-                    }
-                }
+        if self.launcher.open(&path).is_ok() {
+            self.start_tray_spin();
+            if !self.reduce_motion() {
+                self.flash_start_time = Some(Instant::now());
             }
         }
+    }
 
-        if changed {
-            if self.config.two_column_mode {
-                self.sync_two_column_layout_from_current();
-            }
-            self.sync_config_pins();
-        }
+    fn set_quick_launch(&mut self, path: Option<PathBuf>) {
+        self.config.profile.quick_launch_app = path;
+        self.config.save();
     }
 
-    fn try_add_pin(&mut self, path: PathBuf) -> AddPinResult {
-        if self.pinned_apps.len() >= MAX_PINNED_APPS {
-            return AddPinResult::LimitReached;
+    /// Pick, rename, add and delete profiles.
+    fn draw_profile_menu(&mut self, ui: &mut egui::Ui) {
+        let labels = self.config.profile_labels();
+        let mut picked = None;
+        for (index, label) in labels.iter().enumerate() {
+            if ui
+                .radio(index == self.config.active_profile, label)
+                .clicked()
+            {
+                picked = Some(index);
+                ui.close_menu();
+            }
         }
-        if !path.exists() {
-            return AddPinResult::Missing;
+        if let Some(index) = picked {
+            self.switch_profile(ui.ctx(), index);
         }
+        ui.separator();
+        let hint = self.config.profile.label(self.config.active_profile);
+        if ui
+            .add(egui::TextEdit::singleline(&mut self.config.profile.name).hint_text(hint))
+            .changed()
+        {
+            self.config.save();
+            self.refresh_tray_profiles();
+        }
+        if ui.button("New profile").clicked() {
+            let name = format!("Profile {}", self.config.profile_count() + 1);
+            self.change_profile(ui.ctx(), |config| {
+                config.add_profile(name);
+                true
+            });
+            ui.close_menu();
+        }
+        if ui
+            .add_enabled(
+                self.config.profile_count() > 1,
+                egui::Button::new("Delete this profile"),
+            )
+            .clicked()
+        {
+            self.change_profile(ui.ctx(), AppConfig::remove_active_profile);
+            ui.close_menu();
+        }
+    }
 
-        let is_shortcut = path
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("lnk"))
-            .unwrap_or(false);
-
-        let mut display_name = None;
-        let mut launch_args = None;
-        let mut working_dir = None;
-        let resolved_path = if is_shortcut {
-            let source_name = path.file_stem().map(|s| s.to_string_lossy().to_string());
-            match crate::system::resolve_shortcut(&path) {
-                Some(shortcut) if shortcut.target_path.exists() => {
-                    display_name = source_name;
-                    launch_args = shortcut.arguments;
-                    working_dir = shortcut.working_dir;
-                    shortcut.target_path
-                }
-                _ => return AddPinResult::ShortcutUnresolved,
+    fn draw_quick_launch_menu(&mut self, ui: &mut egui::Ui) {
+        let current = self.config.profile.quick_launch_app.clone();
+        let mark = |selected: bool, label: &str| {
+            if selected {
+                format!("✔ {label}")
+            } else {
+                format!("   {label}")
             }
-        } else {
-            path
         };
 
-        if !is_supported_app_path(&resolved_path) {
-            return AddPinResult::Unsupported;
+        let mut choice: Option<Option<PathBuf>> = None;
+        if ui.button(mark(current.is_none(), "None")).clicked() {
+            choice = Some(None);
+        }
+        let mut matched_pin = false;
+        for app in &self.pinned_apps {
+            let selected = current
+                .as_deref()
+                .is_some_and(|quick| same_launch_path(quick, &app.path));
+            matched_pin |= selected;
+            if ui
+                .button(mark(selected, &app.name))
+                .on_hover_text(app.path.display().to_string())
+                .clicked()
+            {
+                choice = Some(Some(app.path.clone()));
+            }
+        }
+        if let Some(path) = current.as_deref().filter(|_| !matched_pin) {
+            let label = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            let _ = ui
+                .button(mark(true, &label))
+                .on_hover_text(path.display().to_string());
         }
 
-        let key = normalize_launch_key(
-            &resolved_path,
-            launch_args.as_deref(),
-            working_dir.as_deref(),
-        );
-        let exists = self.pinned_apps.iter().any(|app| {
-            normalize_launch_key(
-                &app.path,
-                app.launch_args.as_deref(),
-                app.working_dir.as_deref(),
-            ) == key
-        });
-        if exists {
-            return AddPinResult::Duplicate;
+        ui.separator();
+        if ui.button("Browse…").clicked() {
+            ui.close_menu();
+            self.pick_file(ui.ctx(), "Choose quick-launch app", FilePick::QuickLaunch);
         }
 
-        self.pinned_apps.push(PinnedApp::new(
-            resolved_path,
-            display_name,
-            launch_args,
-            working_dir,
-        ));
-        AddPinResult::Added
+        if let Some(path) = choice {
+            self.set_quick_launch(path);
+            ui.close_menu();
+        }
     }
 
-    fn set_two_column_mode(&mut self, enabled: bool) {
-        if self.config.two_column_mode == enabled {
+    fn handle_reorder_keys(&mut self, ctx: &egui::Context) {
+        if !self.is_visible
+            || self.dragging_app.is_some()
+            || self.drop_anim.is_some()
+            || ctx.wants_keyboard_input()
+            || !ctx.input(|i| i.focused)
+        {
             return;
         }
+        let Some(idx) = self
+            .selected_app
+            .filter(|idx| *idx < self.pinned_apps.len())
+        else {
+            return;
+        };
 
-        self.dragging_app = None;
-        self.drag_target = None;
-        self.press_candidate = None;
-        self.drop_anim = None;
-        self.grid_drag_target = None;
+        let (up, down) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::ArrowDown),
+            )
+        });
+        if !up && !down {
+            return;
+        }
+        // Moves follow the order on screen; a pin can't step past the edge of
+        // the favorites group, as it would go straight back.
+        let order = self.render_order();
+        let flags = self.favorite_flags();
+        let Some(target) = order
+            .iter()
+            .position(|i| *i == idx)
+            .and_then(|pos| key_move_target(pos, down, order.len()))
+            .map(|pos| order[pos])
+            .filter(|target| flags[*target] == flags[idx])
+        else {
+            return;
+        };
 
-        if enabled {
-            let (left, right) = resolve_two_column_indices(
-                &self.pinned_apps,
-                self.config.two_column_layout.as_ref(),
-            );
-            reorder_pinned_apps_by_columns(&mut self.pinned_apps, &left, &right);
-            self.config.two_column_mode = true;
-            self.config.two_column_layout =
-                Some(two_column_layout_from_split(&self.pinned_apps, left.len()));
-        } else {
-            let (left, right) = resolve_two_column_indices(
+        let column_count = self.config.column_count();
+        let lengths = (column_count > 1).then(|| {
+            column_lengths(&resolve_column_indices(
                 &self.pinned_apps,
-                self.config.two_column_layout.as_ref(),
-            );
-            reorder_pinned_apps_by_columns(&mut self.pinned_apps, &left, &right);
-            self.config.two_column_layout =
-                Some(two_column_layout_from_split(&self.pinned_apps, left.len()));
-            self.config.two_column_mode = false;
+                self.config.profile.grid_layout.as_ref(),
+                column_count,
+            ))
+        });
+        self.pinned_apps.swap(idx, target);
+        if let Some(lengths) = lengths {
+            self.config.profile.grid_layout =
+                Some(grid_layout_from_lengths(&self.pinned_apps, &lengths));
+            self.sync_grid_layout_from_current();
         }
-
+        self.select_only(target);
         self.sync_config_pins();
+        ctx.request_repaint();
     }
 
-    fn sync_two_column_layout_from_current(&mut self) {
-        if !self.config.two_column_mode {
+    fn toggle_favorite(&mut self, idx: usize) {
+        let Some(app) = self.pinned_apps.get_mut(idx) else {
             return;
-        }
-
-        let (left, right) =
-            resolve_two_column_indices(&self.pinned_apps, self.config.two_column_layout.as_ref());
-        reorder_pinned_apps_by_columns(&mut self.pinned_apps, &left, &right);
-        self.config.two_column_layout =
-            Some(two_column_layout_from_split(&self.pinned_apps, left.len()));
+        };
+        app.favorite = !app.favorite;
+        self.sync_config_pins();
     }
 
-    fn handle_fade_out(&mut self, ctx: &egui::Context) -> bool {
-        if let Some(start) = self.fade_out_start {
-            let elapsed = start.elapsed();
-            let duration = Duration::from_millis(150);
-            if elapsed >= duration {
-                self.fade_out_start = None;
-                if self.hide_after_fade {
-                    self.is_visible = false;
-                    self.hide_after_fade = false;
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
-                    return true;
+    /// Per pin, whether it's drawn in the favorites group: never unless
+    /// `favorites_first` is on. In the list a folder goes up with any
+    /// favorite in it, so its pins stay together.
+    fn favorite_flags(&self) -> Vec<bool> {
+        let mut flags: Vec<bool> = self
+            .pinned_apps
+            .iter()
+            .map(|app| app.favorite && self.config.profile.favorites_first)
+            .collect();
+        if self.config.column_count() <= 1 {
+            let folders = pin_folders(&self.pinned_apps, &self.config.profile.pin_groups);
+            let favorite_folders: HashSet<usize> = folders
+                .iter()
+                .zip(&flags)
+                .filter_map(|(folder, favorite)| folder.filter(|_| *favorite))
+                .collect();
+            for (flag, folder) in flags.iter_mut().zip(&folders) {
+                if let Some(group) = folder {
+                    *flag = favorite_folders.contains(group);
                 }
-                return true;
             }
-            ctx.request_repaint();
         }
-        false
+        flags
     }
 
-    fn draw_main_panel(&mut self, ctx: &egui::Context) -> Option<usize> {
-        let theme = LauncherTheme::default();
-        let panel_rounding = rounding(self.config.shape);
-        let panel_frame = egui::Frame::none()
-            .fill(egui::Color32::TRANSPARENT)
-            .stroke(egui::Stroke::NONE);
-        let is_dragging_file = ctx.input(|i| !i.raw.hovered_files.is_empty());
-        if is_dragging_file {
-            ctx.request_repaint();
-        }
-
-        egui::CentralPanel::default()
-            .frame(panel_frame)
-            .show(ctx, |ui| {
-                let response =
-                    ui.allocate_response(ui.available_size(), egui::Sense::click_and_drag());
-
-                let window_rect = ctx
-                    .input(|i| i.viewport().outer_rect)
-                    .unwrap_or(egui::Rect::ZERO);
-
-                ui.painter().rect_filled(
-                    response.rect.expand(10.0),
-                    panel_rounding + 10.0,
-                    theme.panel_shadow,
-                );
-                ui.painter()
-                    .rect_filled(response.rect, panel_rounding, theme.panel_bg_bottom);
-                paint_glow_blob(
-                    ui.painter(),
-                    egui::pos2(response.rect.right() - 28.0, response.rect.top() + 12.0),
-                    62.0,
-                    egui::Color32::from_rgba_premultiplied(75, 197, 165, 6),
-                );
-                paint_glow_blob(
-                    ui.painter(),
-                    egui::pos2(response.rect.left() + 50.0, response.rect.top() + 40.0),
-                    44.0,
-                    egui::Color32::from_rgba_premultiplied(120, 175, 240, 5),
-                );
-                ui.painter().rect_stroke(
-                    response.rect,
-                    panel_rounding,
-                    egui::Stroke::new(1.0, theme.panel_border),
-                );
-
-                let header_rect = egui::Rect::from_min_size(
-                    response.rect.min,
-                    egui::vec2(response.rect.width(), HEADER_HEIGHT),
-                );
-                ui.painter()
-                    .rect_filled(header_rect, panel_rounding, theme.header_bg_bottom);
-
-                self.draw_header(ui, header_rect, &theme);
-                let handle_resp = ui.allocate_rect(header_rect, egui::Sense::click_and_drag());
-                let panel_size = response.rect.size();
-                self.ensure_window_visible(ctx, window_rect, panel_size);
-                self.handle_window_drag(ctx, ui, &handle_resp, window_rect, panel_size);
-                self.draw_resize_handles(ui, ctx, response.rect, window_rect, panel_size);
-                self.update_resize_drag(ctx, window_rect, panel_size);
-
-                if response.double_clicked() {
-                    if let Some(path) = &self.config.quick_launch_app {
-                        if crate::system::shell_open(path) {
-                            self.flash_start_time = Some(Instant::now());
-                        }
-                    }
-                }
-
-                response.context_menu(|ui| self.draw_context_menu(ui));
-
-                let mut remove_idx = None;
-                let content_h = (response.rect.height() - HEADER_HEIGHT).max(0.0);
-                let visible_h = (self.panel_frac * content_h).clamp(0.0, content_h);
-                let content_rect = egui::Rect::from_min_max(
-                    egui::pos2(response.rect.min.x, response.rect.min.y + HEADER_HEIGHT),
-                    egui::pos2(
-                        response.rect.max.x,
-                        response.rect.min.y + HEADER_HEIGHT + visible_h,
-                    ),
-                );
-
-                if visible_h > 0.0 {
-                    ui.allocate_new_ui(egui::UiBuilder::new().max_rect(content_rect), |ui| {
-                        remove_idx =
-                            self.draw_pinned_list(ui, ctx, content_rect, &theme, is_dragging_file);
-                    });
-                }
+    /// The grid's columns as drawn: the saved layout, with favorites moved
+    /// into the leading slots while `favorites_first` is on. The stored
+    /// order is left alone.
+    fn render_columns(&self) -> Vec<Vec<usize>> {
+        let columns = resolve_column_indices(
+            &self.pinned_apps,
+            self.config.profile.grid_layout.as_ref(),
+            self.config.column_count(),
+        );
+        favorites_first_columns(&columns, &self.favorite_flags())
+    }
 
-                self.draw_flash_overlay(ui);
-                self.draw_warning_overlay(ui, &theme);
-                self.draw_fade_in_overlay(ui, panel_rounding);
+    /// Pin indices in the order they're drawn, down the list or down each
+    /// grid column in turn.
+    fn render_order(&self) -> Vec<usize> {
+        if self.config.column_count() > 1 {
+            self.render_columns().concat()
+        } else {
+            favorites_first_order(&self.favorite_flags())
+        }
+    }
 
-                remove_idx
-            })
-            .inner
+    /// Puts the pins in `order` (old indices by new position), keeping the
+    /// selection on the pins it was on; `false` when nothing moved.
+    fn reorder_pins(&mut self, order: Vec<usize>) -> bool {
+        let Some(new_pos) = reorder_pin_list(&mut self.pinned_apps, order) else {
+            return false;
+        };
+        self.selected_apps = self.selected_apps.iter().map(|idx| new_pos[*idx]).collect();
+        self.selected_app = self.selected_app.map(|idx| new_pos[idx]);
+        self.launch_bounce = None;
+        self.hover_card = None;
+        true
     }
 
-    fn draw_header(&self, ui: &egui::Ui, header_rect: egui::Rect, theme: &LauncherTheme) {
-        ui.painter().text(
-            egui::pos2(header_rect.min.x + 12.0, header_rect.center().y),
-            egui::Align2::LEFT_CENTER,
-            APP_DISPLAY_NAME,
-            egui::FontId::proportional(15.0),
-            theme.title_color,
-        );
+    /// Keeps each folder's pins together, in member order, where its first
+    /// member sits, and dissolves folders left with fewer than two pins. The
+    /// grid shows folder pins loose, so its order is left alone.
+    pub(super) fn gather_folders(&mut self) {
+        let groups = std::mem::take(&mut self.config.profile.pin_groups);
+        self.config.profile.pin_groups = prune_folders(&self.pinned_apps, groups);
+        if self.config.column_count() > 1 {
+            return;
+        }
+        let order = folder_order(&self.pinned_apps, &self.config.profile.pin_groups);
+        self.reorder_pins(order);
     }
 
-    fn ensure_window_visible(
-        &mut self,
-        ctx: &egui::Context,
-        window_rect: egui::Rect,
-        panel_size: egui::Vec2,
-    ) {
-        if self.is_dragging_window || self.resize_drag.is_some() {
+    /// Drops pin `from` onto pin `onto`: into `onto`'s folder, or into a new
+    /// folder holding both.
+    fn merge_into_folder(&mut self, from: usize, onto: usize) {
+        if from == onto || from.max(onto) >= self.pinned_apps.len() {
             return;
         }
+        let dragged = grid_entry_from_app(&self.pinned_apps[from]);
+        let target = grid_entry_from_app(&self.pinned_apps[onto]);
+        let groups = &mut self.config.profile.pin_groups;
+        leave_folders(groups, &dragged);
+        if let Some(group) = groups
+            .iter_mut()
+            .find(|group| group.members.contains(&target))
+        {
+            group.members.push(dragged);
+            group.expanded = true;
+        } else {
+            let name = next_folder_name(groups);
+            groups.push(PinGroup {
+                name,
+                expanded: true,
+                members: vec![target, dragged],
+                launch_delay_ms: None,
+            });
+        }
+        self.select_only(from);
+        self.sync_config_pins();
+    }
 
-        let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) else {
+    /// Takes the dragged pin out of its folder, if it was in one; it lands
+    /// wherever it was dropped.
+    fn leave_folder(&mut self, idx: usize) {
+        let Some(app) = self.pinned_apps.get(idx) else {
             return;
         };
-        let window_size = sanitize_window_size(panel_size);
-        let clamped = clamp_window_origin(window_rect.min, window_size, monitor_size);
+        let entry = grid_entry_from_app(app);
+        leave_folders(&mut self.config.profile.pin_groups, &entry);
+    }
 
-        if (clamped.x - window_rect.min.x).abs() > 0.5
-            || (clamped.y - window_rect.min.y).abs() > 0.5
-        {
-            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(clamped));
-            self.save_window_geometry(clamped, window_size);
+    /// Puts `entry` into folder `group` where pin slot `slot` falls among the
+    /// folder's pins.
+    fn join_folder(&mut self, group: usize, entry: TwoColumnEntry, slot: usize) {
+        if let Some(folder) = self.config.profile.pin_groups.get_mut(group) {
+            insert_folder_member(folder, entry, &self.pinned_apps, slot);
         }
     }
 
-    fn handle_window_drag(
-        &mut self,
-        ctx: &egui::Context,
-        ui: &egui::Ui,
-        handle_resp: &egui::Response,
+    fn apply_folder_action(&mut self, ctx: &egui::Context, group: usize, action: FolderAction) {
+        if group >= self.config.profile.pin_groups.len() {
+            return;
+        }
+        match action {
+            FolderAction::Toggle => {
+                let folder = &mut self.config.profile.pin_groups[group];
+                folder.expanded = !folder.expanded;
+                self.config.save();
+            }
+            FolderAction::Rename(name) => {
+                self.config.profile.pin_groups[group].name = name;
+                self.config.save();
+            }
+            FolderAction::LaunchAll => {
+                let folders = pin_folders(&self.pinned_apps, &self.config.profile.pin_groups);
+                let members: Vec<usize> = (0..folders.len())
+                    .filter(|idx| folders[*idx] == Some(group))
+                    .collect();
+                let delay =
+                    self.config.profile.pin_groups[group].launch_delay(self.config.launch_delay_ms);
+                self.launch_group(ctx, &members, delay);
+            }
+            FolderAction::Ungroup => {
+                self.config.profile.pin_groups.remove(group);
+                self.sync_config_pins();
+            }
+        }
+    }
+
+    /// The row heading a folder's pins: a click folds it, right-click for the
+    /// folder actions. `merging` lights it up as the drop target.
+    fn draw_folder_row(
+        &self,
+        ui: &mut egui::Ui,
+        group: usize,
+        count: usize,
+        merging: bool,
+        width: f32,
+        theme: &LauncherTheme,
+    ) -> (egui::Rect, Option<FolderAction>) {
+        let folder = &self.config.profile.pin_groups[group];
+        let (rect, resp) =
+            ui.allocate_exact_size(egui::vec2(width, ROW_HEIGHT), egui::Sense::click());
+        let painter = ui.painter();
+        if merging {
+            painter.rect_filled(rect, 8.0, theme.drop_hint.gamma_multiply(0.25));
+            painter.rect_stroke(rect, 8.0, theme.drop_hint_stroke());
+        } else {
+            let fill = if resp.hovered() {
+                theme.row_hover
+            } else {
+                theme.row_bg
+            };
+            painter.rect_filled(rect, 8.0, fill);
+            if resp.hovered() {
+                painter.rect_stroke(rect, 8.0, egui::Stroke::new(1.0, theme.row_border));
+            }
+        }
+
+        let icon_rect = egui::Rect::from_center_size(
+            egui::pos2(rect.min.x + 14.0 + ICON_SIDE * 0.5, rect.center().y),
+            egui::vec2(ICON_SIDE, ICON_SIDE),
+        );
+        paint_folder_glyph(painter, icon_rect.shrink(2.0), theme.icon_placeholder);
+        painter.text(
+            egui::pos2(icon_rect.max.x + 9.0, rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            format!("{} ({count})", folder.name),
+            egui::FontId::proportional(14.0),
+            theme.title_color,
+        );
+        let chevron = egui::Rect::from_center_size(
+            egui::pos2(rect.max.x - 16.0, rect.center().y),
+            egui::vec2(8.0, 8.0),
+        );
+        paint_chevron(painter, chevron, folder.expanded, theme.title_color);
+
+        let mut action = resp.clicked().then_some(FolderAction::Toggle);
+        let mut name = folder.name.clone();
+        resp.context_menu(|ui| {
+            if ui.text_edit_singleline(&mut name).changed() && !name.trim().is_empty() {
+                action = Some(FolderAction::Rename(name.trim().to_string()));
+            }
+            if ui.button(format!("Launch all ({count})")).clicked() {
+                action = Some(FolderAction::LaunchAll);
+                ui.close_menu();
+            }
+            if ui.button("Ungroup").clicked() {
+                action = Some(FolderAction::Ungroup);
+                ui.close_menu();
+            }
+        });
+        (rect, action)
+    }
+
+    /// Star at the right end of a row: always shown on favorites, hollow on
+    /// hover for the rest. A click toggles the flag once drawing is done.
+    fn draw_favorite_star(
+        &mut self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        row: egui::Rect,
+        row_hovered: bool,
+        theme: &LauncherTheme,
+    ) {
+        let favorite = self.pinned_apps[idx].favorite;
+        if !favorite && !row_hovered {
+            return;
+        }
+        let star_rect = egui::Rect::from_center_size(
+            egui::pos2(row.max.x - FAVORITE_STAR_WIDTH * 0.5 - 4.0, row.center().y),
+            egui::vec2(FAVORITE_STAR_WIDTH, FAVORITE_STAR_WIDTH),
+        );
+        let resp = ui
+            .interact(
+                star_rect,
+                ui.id().with(("favorite_star", idx)),
+                egui::Sense::click(),
+            )
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+        let color = if favorite || resp.hovered() {
+            theme.toast_warning
+        } else {
+            theme.icon_placeholder
+        };
+        ui.painter().text(
+            star_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            if favorite { "★" } else { "☆" },
+            egui::FontId::proportional(15.0),
+            color,
+        );
+        if resp.clicked() {
+            self.pending_favorite = Some(idx);
+        }
+    }
+
+    /// Ctrl+F opens the search box, or moves focus back into it.
+    fn handle_search_keys(&mut self, ctx: &egui::Context) {
+        if !self.is_visible || self.config.collapsed {
+            return;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::F)) {
+            self.open_search(ctx);
+        }
+    }
+
+    /// Enter launches the selected pin, however it was selected: by click,
+    /// the mouse wheel or a Ctrl+arrow move.
+    fn handle_launch_key(&mut self, ctx: &egui::Context) {
+        if !self.is_visible
+            || ctx.wants_keyboard_input()
+            || self.quit_prompt_open
+            || self.pending_launch.is_some()
+            || !ctx.input(|i| i.focused && i.key_pressed(egui::Key::Enter))
+        {
+            return;
+        }
+        if let Some(idx) = self
+            .selected_app
+            .filter(|idx| *idx < self.pinned_apps.len())
+        {
+            self.request_launch(idx, false);
+        }
+    }
+
+    fn open_search(&mut self, ctx: &egui::Context) {
+        self.search.get_or_insert_with(String::new);
+        self.search_focus = true;
+        // Typing goes nowhere without it, whatever `focus_on_show` says.
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        ctx.request_repaint();
+    }
+
+    /// Launches a pin from a click or key, first asking when
+    /// `confirm_risky_launch` is on and the launch is elevated or the pin lives
+    /// on a network share.
+    fn request_launch(&mut self, idx: usize, elevated: bool) {
+        let app = &self.pinned_apps[idx];
+        let risky = elevated || crate::system::is_network_path(&app.path);
+        if self.config.confirm_risky_launch && risky {
+            self.pending_launch = Some(PendingLaunch {
+                key: pin_launch_key(app),
+                name: app.name.clone(),
+                elevated,
+                shown: false,
+            });
+        } else {
+            self.launch_pin(idx, elevated);
+        }
+    }
+
+    fn launch_pin(&mut self, idx: usize, elevated: bool) {
+        let app = &self.pinned_apps[idx];
+        match launch_app(self.launcher.as_ref(), app, elevated) {
+            Ok(()) => {
+                if !self.reduce_motion() {
+                    self.launch_bounce = Some((idx, Instant::now()));
+                }
+                self.start_tray_spin();
+            }
+            Err(err) => {
+                warn!("couldn't launch {}: {err}", app.path.display());
+                let (severity, message) = launch_failure_toast(&app.name, elevated, err);
+                self.show_toast(severity, message);
+            }
+        }
+    }
+
+    /// Launches `indices` in order on a worker thread, `delay` apart, and
+    /// reports the outcome as one toast.
+    fn launch_group(&mut self, ctx: &egui::Context, indices: &[usize], delay: Duration) {
+        let batch: Vec<_> = indices
+            .iter()
+            .filter_map(|&idx| self.pinned_apps.get(idx))
+            .map(|app| {
+                let (program, args) = launch_command(app);
+                let launch = (
+                    program,
+                    args,
+                    app.working_dir.clone(),
+                    app.env.clone(),
+                    app.show_state,
+                );
+                (app.name.clone(), launch)
+            })
+            .collect();
+        let ui_tx = self.ui_tx.clone();
+        let launcher = self.launcher.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _com = crate::system::ComGuard::init();
+            let mut launched = 0;
+            let mut failed = Vec::new();
+            for (i, (name, (path, args, working_dir, env, show))) in batch.into_iter().enumerate() {
+                if i > 0 && !delay.is_zero() {
+                    std::thread::sleep(delay);
+                }
+                match launcher.open_with_env(
+                    &path,
+                    args.as_deref(),
+                    working_dir.as_deref(),
+                    &env,
+                    show,
+                ) {
+                    Ok(()) => launched += 1,
+                    Err(err) => {
+                        warn!("couldn't launch {}: {err}", path.display());
+                        failed.push(name);
+                    }
+                }
+            }
+            let _ = ui_tx.send(UserEvent::GroupLaunched { launched, failed });
+            ctx.request_repaint();
+        });
+    }
+
+    fn apply_open_with(&mut self, ctx: &egui::Context, idx: usize, choice: OpenWithChoice) {
+        let Some(app) = self.pinned_apps.get(idx) else {
+            return;
+        };
+        let opener = match choice {
+            OpenWithChoice::SystemPicker => {
+                if !crate::system::shell_open_as(&app.path) {
+                    let message = format!("Couldn't open {}", app.name);
+                    self.show_toast(Severity::Error, message);
+                }
+                return;
+            }
+            OpenWithChoice::Default => None,
+            OpenWithChoice::Browse => {
+                let key = pin_launch_key(app);
+                self.pick_file(ctx, "Open with", FilePick::Opener(key));
+                return;
+            }
+        };
+        self.pinned_apps[idx].opener = opener;
+        self.sync_config_pins();
+    }
+
+    /// Shows the "Open" dialog on its own thread, so the dock keeps drawing;
+    /// the choice comes back as `UserEvent::FilePicked`. Only one at a time.
+    fn pick_file(&mut self, ctx: &egui::Context, title: &'static str, purpose: FilePick) {
+        if self.picking_file {
+            return;
+        }
+        self.picking_file = true;
+        let ui_tx = self.ui_tx.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _com = crate::system::ComGuard::init();
+            let path = crate::system::pick_file(title);
+            let _ = ui_tx.send(UserEvent::FilePicked(purpose, path));
+            ctx.request_repaint();
+        });
+    }
+
+    fn apply_picked_file(&mut self, purpose: FilePick, path: Option<PathBuf>) {
+        self.picking_file = false;
+        let Some(path) = path else {
+            return;
+        };
+        match purpose {
+            FilePick::QuickLaunch => self.set_quick_launch(Some(path)),
+            FilePick::Opener(key) => match self.pin_index_for_key(&key) {
+                Some(idx) => {
+                    self.pinned_apps[idx].opener = Some(path);
+                    self.sync_config_pins();
+                }
+                None => self.show_toast(Severity::Warning, "That pin was removed"),
+            },
+        }
+    }
+
+    fn report_group_launch(&mut self, launched: usize, failed: Vec<String>) {
+        if launched > 0 {
+            self.start_tray_spin();
+        }
+        if failed.is_empty() {
+            self.show_toast(Severity::Info, format!("Launched {launched} apps"));
+        } else {
+            let message = format!(
+                "Launched {launched} of {}; couldn't launch {}",
+                launched + failed.len(),
+                failed.join(", ")
+            );
+            self.show_toast(Severity::Warning, message);
+        }
+    }
+
+    fn reveal_pin_path(&mut self, path: &Path) {
+        if !crate::system::reveal_in_folder(path) {
+            self.show_toast(Severity::Warning, "Couldn't open the file location");
+        }
+    }
+
+    fn show_pin_properties(&mut self, path: &Path) {
+        if !crate::system::show_properties(path) {
+            self.show_toast(Severity::Warning, "Couldn't show the properties");
+        }
+    }
+
+    /// Launch/select handling for a row, per `activate_on`. Never fires while a
+    /// reorder drag or its drop animation is in flight.
+    fn handle_row_activation(&mut self, ctx: &egui::Context, idx: usize, resp: &egui::Response) {
+        if self.dragging_app.is_some() || self.drop_anim.is_some() {
+            return;
+        }
+        // Middle-click always starts another instance; Shift-click elevates.
+        if resp.middle_clicked() {
+            self.request_launch(idx, false);
+            return;
+        }
+        if resp.clicked() && ctx.input(|i| i.modifiers.shift_only()) {
+            self.request_launch(idx, true);
+            return;
+        }
+        match self.config.activate_on {
+            ActivationMode::DoubleClick => {
+                if resp.double_clicked() {
+                    self.request_launch(idx, false);
+                } else if resp.clicked() {
+                    self.handle_row_click(ctx, idx);
+                }
+            }
+            ActivationMode::SingleClick => {
+                // Selection follows the pointer unless a multi-selection is being built.
+                if resp.hovered() && self.selected_apps.len() <= 1 && !self.is_selected(idx) {
+                    self.select_only(idx);
+                }
+                if resp.clicked() {
+                    if ctx.input(|i| i.modifiers.command) {
+                        self.toggle_selection(idx);
+                    } else {
+                        self.request_launch(idx, false);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_row_click(&mut self, ctx: &egui::Context, idx: usize) {
+        if ctx.input(|i| i.modifiers.command) {
+            self.toggle_selection(idx);
+        } else {
+            self.select_only(idx);
+        }
+    }
+
+    fn handle_runtime_events(&mut self, ctx: &egui::Context) {
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                UserEvent::Show => self.start_show_transition(ctx),
+                UserEvent::Hide => self.start_hide_transition(ctx),
+                UserEvent::ShowSearch => {
+                    self.set_collapsed(false);
+                    self.open_search(ctx);
+                }
+                UserEvent::Quit => self.shutdown(ctx),
+                UserEvent::QuitRequested => {
+                    self.start_show_transition(ctx);
+                    self.request_quit(ctx);
+                }
+                UserEvent::GroupLaunched { launched, failed } => {
+                    self.report_group_launch(launched, failed)
+                }
+                UserEvent::SwitchProfile(index) => self.switch_profile(ctx, index),
+                UserEvent::NextProfile => self.next_profile(ctx),
+                UserEvent::LaunchPin(key) => match self.pin_index_for_key(&key) {
+                    Some(idx) => self.launch_pin(idx, false),
+                    None => self.show_toast(Severity::Warning, "That pin was removed"),
+                },
+                // Requested before a profile switch changed the icon size; the
+                // new profile's pins asked again at the right one.
+                UserEvent::IconReady(result) if result.size != self.config.profile.icon_size => {}
+                UserEvent::IconReady(result) => {
+                    for app in &mut self.pinned_apps {
+                        if app.path == result.path {
+                            app.icon_failed = result.image.is_none();
+                            // A transient COM/shell failure gets one more try.
+                            if app.icon_failed && !app.icon_retried {
+                                app.icon_retry_at = Some(Instant::now() + ICON_RETRY_DELAY);
+                                ctx.request_repaint_after(ICON_RETRY_DELAY);
+                            }
+                            if let Some(img) = &result.image {
+                                let tex_name = format!("icon:{}", app.path.to_string_lossy());
+                                app.texture = Some(ctx.load_texture(
+                                    tex_name,
+                                    img.clone(),
+                                    egui::TextureOptions::LINEAR,
+                                ));
+                            }
+                            break;
+                        }
+                    }
+                }
+                UserEvent::IconsStale(paths) => {
+                    for idx in 0..self.pinned_apps.len() {
+                        if paths.contains(&self.pinned_apps[idx].path) {
+                            self.refresh_icon(idx);
+                        }
+                    }
+                }
+                UserEvent::PinsUnavailable(paths) => {
+                    for idx in 0..self.pinned_apps.len() {
+                        let app = &mut self.pinned_apps[idx];
+                        let back = app.unavailable && !paths.contains(&app.path);
+                        app.unavailable = paths.contains(&app.path);
+                        // Its icon likely failed while the target was away.
+                        if back && app.icon_failed {
+                            self.refresh_icon(idx);
+                        }
+                    }
+                }
+                UserEvent::FilePicked(purpose, path) => self.apply_picked_file(purpose, path),
+            }
+        }
+    }
+
+    fn update_panel_animation(&mut self, ctx: &egui::Context) {
+        if let Some((from, to, start)) = self.panel_anim {
+            let elapsed = start.elapsed();
+            let duration = self.config.animation.panel();
+            let t = (elapsed.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
+            let eased = self.motion_ease(t);
+            self.panel_frac = from + (to - from) * eased;
+            if t >= 1.0 {
+                self.panel_anim = None;
+                self.panel_frac = to;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    fn update_drop_animation(&mut self, ctx: &egui::Context) {
+        if let Some(anim) = &self.drop_anim {
+            let elapsed = anim.start.elapsed();
+            let duration = self.config.animation.drop();
+            let t = (elapsed.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
+            if t >= 1.0 {
+                self.land_drop_anim();
+            } else {
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.raw.dropped_files.is_empty()) {
+            return;
+        }
+
+        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+        let paths = dropped_files
+            .into_iter()
+            .filter_map(|file| file.path)
+            .collect();
+        self.add_pins_from_paths(paths);
+    }
+
+    fn add_pins_from_paths(&mut self, paths: Vec<PathBuf>) {
+        let mut changed = false;
+        let mut limit_hit = false;
+
+        for path in paths {
+            match self.try_add_pin(path) {
+                AddPinResult::Added => changed = true,
+                AddPinResult::Duplicate => self.show_toast(Severity::Info, "Already pinned"),
+                AddPinResult::Unsupported => {
+                    self.show_toast(Severity::Warning, UNSUPPORTED_PIN_TOAST)
+                }
+                AddPinResult::ShortcutUnresolved => {
+                    self.show_toast(Severity::Warning, "Shortcut target not found")
+                }
+                AddPinResult::Missing => self.show_toast(Severity::Warning, "File not found"),
+                AddPinResult::LimitReached => {
+                    let limit = self.config.pin_limit();
+                    self.show_toast(Severity::Warning, format!("Max {} apps", limit));
+                    limit_hit = true;
+                    break;
+                }
+            }
+        }
+
+        if changed {
+            self.sync_grid_layout_from_current();
+            self.sync_config_pins();
+            let limit = self.config.pin_limit();
+            let free = limit.saturating_sub(self.pinned_apps.len());
+            if !limit_hit && free <= self.config.pin_warning_slots {
+                let used = self.pinned_apps.len();
+                self.show_toast(Severity::Info, format!("{used} of {limit} pins used"));
+            }
+        }
+    }
+
+    fn open_taskbar_import(&mut self) {
+        let pinned: HashSet<String> = self
+            .pinned_apps
+            .iter()
+            .map(|app| {
+                normalize_launch_key(
+                    &app.path,
+                    app.launch_args.as_deref(),
+                    app.working_dir.as_deref(),
+                )
+            })
+            .collect();
+        let allow_any_file = self.config.pin_any_file;
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+
+        for shortcut in crate::system::list_taskbar_pinned_shortcuts() {
+            let Some(resolved) = crate::system::resolve_shortcut(&shortcut) else {
+                continue;
+            };
+            if !resolved.target_path.exists()
+                || !is_supported_app_path(&resolved.target_path, allow_any_file)
+            {
+                continue;
+            }
+            let key = normalize_launch_key(
+                &resolved.target_path,
+                resolved.arguments.as_deref(),
+                resolved.working_dir.as_deref(),
+            );
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let name = shortcut
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| resolved.target_path.to_string_lossy().to_string());
+            let already_pinned = pinned.contains(&key);
+            candidates.push(ImportCandidate {
+                name,
+                source: shortcut,
+                target: resolved.target_path,
+                already_pinned,
+                checked: !already_pinned,
+            });
+        }
+
+        self.import_candidates = Some(candidates);
+    }
+
+    fn draw_import_dialog(&mut self, ctx: &egui::Context, theme: &LauncherTheme) {
+        let Some(candidates) = self.import_candidates.as_mut() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("Import taskbar pins")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .fill(theme.toast_bg)
+                    .stroke(egui::Stroke::new(1.0, theme.row_border)),
+            )
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if candidates.is_empty() {
+                    ui.label("No taskbar pins found");
+                } else {
+                    egui::ScrollArea::vertical()
+                        .max_height(260.0)
+                        .show(ui, |ui| {
+                            for candidate in candidates.iter_mut() {
+                                let label = if candidate.already_pinned {
+                                    format!("{} (pinned)", candidate.name)
+                                } else {
+                                    candidate.name.clone()
+                                };
+                                ui.add_enabled(
+                                    !candidate.already_pinned,
+                                    egui::Checkbox::new(&mut candidate.checked, label),
+                                )
+                                .on_hover_text(candidate.target.to_string_lossy());
+                            }
+                        });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let any_checked = candidates.iter().any(|c| c.checked && !c.already_pinned);
+                    if ui
+                        .add_enabled(any_checked, egui::Button::new("Pin selected"))
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            let paths = candidates
+                .iter()
+                .filter(|c| c.checked && !c.already_pinned)
+                .map(|c| c.source.clone())
+                .collect();
+            self.import_candidates = None;
+            self.add_pins_from_paths(paths);
+        } else if cancelled || !open {
+            self.import_candidates = None;
+        }
+    }
+
+    fn draw_command_dialog(&mut self, ctx: &egui::Context, theme: &LauncherTheme) {
+        let Some(draft) = self.command_draft.as_mut() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new("New command pin")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .fill(theme.toast_bg)
+                    .stroke(egui::Stroke::new(1.0, theme.row_border)),
+            )
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("command_pin_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Command");
+                        ui.text_edit_singleline(&mut draft.command)
+                            .on_hover_text("e.g. cmd /c backup.bat");
+                        ui.end_row();
+                        ui.label("Start in");
+                        ui.text_edit_singleline(&mut draft.working_dir);
+                        ui.end_row();
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut draft.name);
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let has_command = !draft.command.trim().is_empty();
+                    if ui
+                        .add_enabled(has_command, egui::Button::new("Pin"))
+                        .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            if let Some(draft) = self.command_draft.take() {
+                if !self.add_command_pin(&draft) {
+                    self.command_draft = Some(draft);
+                }
+            }
+        } else if cancelled || !open {
+            self.command_draft = None;
+        }
+    }
+
+    /// Pins the drafted command; on a problem shows why and returns `false`
+    /// so the dialog stays open for a fix.
+    fn add_command_pin(&mut self, draft: &CommandDraft) -> bool {
+        let command = draft.command.trim();
+        let Some((program, _)) = crate::system::split_program(command) else {
+            self.show_toast(Severity::Warning, "Enter a command to run");
+            return false;
+        };
+        if self.pinned_apps.len() >= self.config.pin_limit() {
+            let limit = self.config.pin_limit();
+            self.show_toast(Severity::Warning, format!("Max {} apps", limit));
+            return false;
+        }
+        let working_dir = Some(draft.working_dir.trim())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        if working_dir.as_ref().is_some_and(|dir| !dir.is_dir()) {
+            self.show_toast(Severity::Warning, "Start-in folder not found");
+            return false;
+        }
+
+        let path = PathBuf::from(command);
+        let key = normalize_launch_key(&path, None, working_dir.as_deref());
+        if self
+            .pinned_apps
+            .iter()
+            .any(|app| pin_launch_key(app) == key)
+        {
+            self.show_toast(Severity::Info, "Already pinned");
+            return false;
+        }
+
+        let name = Some(draft.name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| Some(program.file_stem()?.to_string_lossy().into_owned()));
+        let mut app = PinnedApp::new(path, name, None, working_dir);
+        app.command = true;
+        self.pinned_apps.push(app);
+        self.sync_grid_layout_from_current();
+        self.sync_config_pins();
+        true
+    }
+
+    fn open_launch_options(&mut self, idx: usize) {
+        let Some(app) = self.pinned_apps.get(idx) else {
+            return;
+        };
+        self.launch_options = Some(LaunchOptionsDraft {
+            key: pin_launch_key(app),
+            name: app.name.clone(),
+            args: app.launch_args.clone().unwrap_or_default(),
+            working_dir: app
+                .working_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+            env: format_env_lines(&app.env),
+            takes_env: !opens_as_document(&app.path),
+            show_state: app.show_state,
+        });
+    }
+
+    fn draw_launch_options_dialog(&mut self, ctx: &egui::Context, theme: &LauncherTheme) {
+        let Some(draft) = self.launch_options.as_mut() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(format!("Launch options: {}", draft.name))
+            .id(egui::Id::new("launch_options_dialog"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .fill(theme.toast_bg)
+                    .stroke(egui::Stroke::new(1.0, theme.row_border)),
+            )
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("launch_options_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Arguments");
+                        ui.text_edit_singleline(&mut draft.args);
+                        ui.end_row();
+                        ui.label("Start in");
+                        ui.text_edit_singleline(&mut draft.working_dir);
+                        ui.end_row();
+                        ui.label("Environment");
+                        ui.add_enabled(
+                            draft.takes_env,
+                            egui::TextEdit::multiline(&mut draft.env)
+                                .desired_rows(3)
+                                .hint_text("NAME=value, one per line"),
+                        )
+                        .on_disabled_hover_text("Only programs take an environment");
+                        ui.end_row();
+                        ui.label("Window");
+                        ui.horizontal(|ui| {
+                            for (state, label) in [
+                                (ShowState::Normal, "Normal"),
+                                (ShowState::Minimized, "Minimized"),
+                                (ShowState::Maximized, "Maximized"),
+                            ] {
+                                ui.radio_value(&mut draft.show_state, state, label);
+                            }
+                        });
+                        ui.end_row();
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            if let Some(draft) = self.launch_options.take() {
+                if !self.apply_launch_options(&draft) {
+                    self.launch_options = Some(draft);
+                }
+            }
+        } else if cancelled || !open {
+            self.launch_options = None;
+        }
+    }
+
+    /// Saves the dialog's fields to its pin; on a problem shows why and
+    /// returns `false` so the dialog stays open for a fix.
+    fn apply_launch_options(&mut self, draft: &LaunchOptionsDraft) -> bool {
+        let Some(idx) = self.pin_index_for_key(&draft.key) else {
+            self.show_toast(Severity::Warning, "That pin was removed");
+            return true;
+        };
+        let LaunchOptions {
+            args,
+            working_dir,
+            env,
+        } = match launch_options_from_draft(draft) {
+            Ok(options) => options,
+            Err(message) => {
+                self.show_toast(Severity::Warning, message);
+                return false;
+            }
+        };
+        let key = normalize_launch_key(
+            &self.pinned_apps[idx].path,
+            args.as_deref(),
+            working_dir.as_deref(),
+        );
+        let duplicate = self
+            .pinned_apps
+            .iter()
+            .enumerate()
+            .any(|(other, app)| other != idx && pin_launch_key(app) == key);
+        if duplicate {
+            self.show_toast(Severity::Warning, "Another pin already launches this");
+            return false;
+        }
+
+        let old_entry = grid_entry_from_app(&self.pinned_apps[idx]);
+        let app = &mut self.pinned_apps[idx];
+        app.launch_args = args;
+        app.working_dir = working_dir;
+        app.env = env;
+        app.show_state = draft.show_state;
+        let new_entry = grid_entry_from_app(app);
+        for member in self
+            .config
+            .profile
+            .pin_groups
+            .iter_mut()
+            .flat_map(|g| &mut g.members)
+        {
+            if *member == old_entry {
+                *member = new_entry.clone();
+            }
+        }
+        self.sync_grid_layout_from_current();
+        self.sync_config_pins();
+        true
+    }
+
+    fn try_add_pin(&mut self, path: PathBuf) -> AddPinResult {
+        if self.pinned_apps.len() >= self.config.pin_limit() {
+            return AddPinResult::LimitReached;
+        }
+        if !path.exists() {
+            return AddPinResult::Missing;
+        }
+
+        let is_shortcut = crate::system::is_shortcut_path(&path);
+
+        let mut display_name = None;
+        let mut launch_args = None;
+        let mut working_dir = None;
+        let shortcut = is_shortcut.then(|| path.clone());
+        let resolved_path = if is_shortcut {
+            let source_name = path.file_stem().map(|s| s.to_string_lossy().to_string());
+            match crate::system::resolve_shortcut(&path) {
+                Some(shortcut) if shortcut.target_path.exists() => {
+                    display_name = source_name;
+                    launch_args = shortcut.arguments;
+                    working_dir = shortcut.working_dir;
+                    shortcut.target_path
+                }
+                _ => return AddPinResult::ShortcutUnresolved,
+            }
+        } else {
+            path
+        };
+        let resolved_path = crate::system::canonical_path(&resolved_path);
+
+        if !is_supported_app_path(&resolved_path, self.config.pin_any_file) {
+            return AddPinResult::Unsupported;
+        }
+
+        let key = normalize_launch_key(
+            &resolved_path,
+            launch_args.as_deref(),
+            working_dir.as_deref(),
+        );
+        let exists = self.pinned_apps.iter().any(|app| {
+            normalize_launch_key(
+                &app.path,
+                app.launch_args.as_deref(),
+                app.working_dir.as_deref(),
+            ) == key
+        });
+        if exists {
+            return AddPinResult::Duplicate;
+        }
+
+        let mut app = PinnedApp::new(resolved_path, display_name, launch_args, working_dir);
+        app.shortcut = shortcut;
+        self.pinned_apps.push(app);
+        AddPinResult::Added
+    }
+
+    fn set_grid_mode(&mut self, enabled: bool) {
+        if self.config.profile.grid_mode == enabled {
+            return;
+        }
+
+        self.reset_drag_state();
+        self.clear_selection();
+
+        // Flatten the pins into column order either way, so the list order
+        // matches what the grid showed and the grid can be restored later.
+        self.config.profile.grid_mode = true;
+        let columns = resolve_column_indices(
+            &self.pinned_apps,
+            self.config.profile.grid_layout.as_ref(),
+            self.config.column_count(),
+        );
+        reorder_pinned_apps_by_columns(&mut self.pinned_apps, &columns);
+        self.config.profile.grid_layout = Some(grid_layout_from_lengths(
+            &self.pinned_apps,
+            &column_lengths(&columns),
+        ));
+        self.config.profile.grid_mode = enabled;
+
+        self.sync_config_pins();
+    }
+
+    fn set_grid_cols(&mut self, cols: u32) {
+        let cols = cols.clamp(1, MAX_GRID_COLS as u32);
+        if self.config.profile.grid_cols == cols {
+            return;
+        }
+
+        self.reset_drag_state();
+
+        // Resolving against the new count folds surplus columns into the last
+        // one, or leaves the added columns empty.
+        self.config.profile.grid_cols = cols;
+        self.sync_grid_layout_from_current();
+        self.sync_config_pins();
+    }
+
+    fn set_grid_rows(&mut self, rows: u32) {
+        let rows = rows.min(MAX_GRID_ROWS as u32);
+        if self.config.profile.grid_rows != rows {
+            self.config.profile.grid_rows = rows;
+            self.config.save();
+        }
+    }
+
+    /// Fits the window to the grid after a profile switch brings other
+    /// columns or rows, growing or shrinking it between the minimum window
+    /// size and the monitor's. Without a row count the height is kept.
+    pub(super) fn fit_window_to_layout(&mut self, ctx: &egui::Context) {
+        let (outer, inner) = ctx.input(|i| (i.viewport().outer_rect, i.viewport().inner_rect));
+        let (Some(outer), Some(inner)) = (outer, inner) else {
+            return;
+        };
+        let rows = if self.config.profile.grid_mode {
+            self.config.visible_grid_rows()
+        } else {
+            None
+        };
+        let needed = grid_window_size(
+            self.config.column_count(),
+            rows,
+            self.theme().content_padding,
+        );
+        let height = if rows.is_some() {
+            needed.y
+        } else {
+            inner.height()
+        };
+        let mut size = sanitize_window_size(egui::vec2(needed.x, height));
+        let monitor_size = self.monitor_size(ctx);
+        if let Some(monitor_size) = monitor_size {
+            size = sanitize_window_size(size.min(monitor_size - egui::vec2(8.0, 8.0)));
+        }
+        if (size - inner.size()).abs().max_elem() <= RESIZE_SEND_THRESHOLD {
+            return;
+        }
+        let pos = match monitor_size {
+            Some(monitor_size) => {
+                clamp_window_origin(outer.min, size, desktop_bounds(ctx, monitor_size))
+            }
+            None => outer.min,
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        if (pos - outer.min).abs().max_elem() > RESIZE_SEND_THRESHOLD {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
+        }
+        self.save_window_geometry(ctx, pos, size);
+    }
+
+    fn draw_grid_size_controls(&mut self, ui: &mut egui::Ui) {
+        let cols = self.config.profile.grid_cols.clamp(1, MAX_GRID_COLS as u32);
+        ui.horizontal(|ui| {
+            ui.label(format!("Columns: {}", cols));
+            if ui.add_enabled(cols > 1, egui::Button::new("−")).clicked() {
+                self.set_grid_cols(cols - 1);
+            }
+            if ui
+                .add_enabled(cols < MAX_GRID_COLS as u32, egui::Button::new("+"))
+                .clicked()
+            {
+                self.set_grid_cols(cols + 1);
+            }
+        });
+
+        let rows = self.config.profile.grid_rows.min(MAX_GRID_ROWS as u32);
+        ui.horizontal(|ui| {
+            if rows == 0 {
+                ui.label("Rows: fit");
+            } else {
+                ui.label(format!("Rows: {}", rows));
+            }
+            // Stepping below one row goes back to filling the window.
+            if ui.add_enabled(rows > 0, egui::Button::new("−")).clicked() {
+                self.set_grid_rows(rows - 1);
+            }
+            if ui
+                .add_enabled(rows < MAX_GRID_ROWS as u32, egui::Button::new("+"))
+                .clicked()
+            {
+                self.set_grid_rows(rows + 1);
+            }
+        });
+    }
+
+    fn draw_ui_scale_control(&mut self, ui: &mut egui::Ui) {
+        const STEP: f32 = 0.1;
+        let scale = self.config.ui_scale();
+        ui.horizontal(|ui| {
+            ui.label(format!("Scale: {:.0}%", scale * 100.0))
+                .on_hover_text("Ctrl+wheel over the title bar also zooms");
+            if ui
+                .add_enabled(scale > MIN_UI_SCALE, egui::Button::new("−"))
+                .clicked()
+            {
+                self.set_ui_scale(ui.ctx(), scale - STEP);
+            }
+            if ui
+                .add_enabled(scale < MAX_UI_SCALE, egui::Button::new("+"))
+                .clicked()
+            {
+                self.set_ui_scale(ui.ctx(), scale + STEP);
+            }
+            if scale != 1.0 && ui.button("Reset").clicked() {
+                self.set_ui_scale(ui.ctx(), 1.0);
+            }
+        });
+    }
+
+    fn draw_pin_limit_control(&mut self, ui: &mut egui::Ui) {
+        const STEP: usize = 10;
+        let limit = self.config.pin_limit();
+        ui.horizontal(|ui| {
+            ui.label(format!("Pin limit: {}", limit));
+            // Lowering below the current count keeps existing pins; it only blocks new ones.
+            if ui.add_enabled(limit > 1, egui::Button::new("−")).clicked() {
+                self.config.pin_limit = limit.saturating_sub(STEP).max(1);
+                self.config.save();
+            }
+            if ui
+                .add_enabled(limit < MAX_PIN_LIMIT, egui::Button::new("+"))
+                .clicked()
+            {
+                self.config.pin_limit = (limit + STEP).min(MAX_PIN_LIMIT);
+                self.config.save();
+            }
+        });
+    }
+
+    fn sync_grid_layout_from_current(&mut self) {
+        let column_count = self.config.column_count();
+        if column_count <= 1 {
+            return;
+        }
+
+        let columns = resolve_column_indices(
+            &self.pinned_apps,
+            self.config.profile.grid_layout.as_ref(),
+            column_count,
+        );
+        reorder_pinned_apps_by_columns(&mut self.pinned_apps, &columns);
+        self.config.profile.grid_layout = Some(grid_layout_from_lengths(
+            &self.pinned_apps,
+            &column_lengths(&columns),
+        ));
+    }
+
+    /// Finishes a hide once the fade-out has run, or at once if reduced
+    /// motion came on mid-fade; `true` when the panel just went away.
+    fn handle_fade_out(&mut self, ctx: &egui::Context) -> bool {
+        let Some(start) = self.fade_out_start else {
+            return false;
+        };
+        if self.reduce_motion() || start.elapsed() >= self.config.animation.fade_out() {
+            self.hide_now(ctx);
+            return true;
+        }
+        ctx.request_repaint();
+        false
+    }
+
+    fn draw_main_panel(&mut self, ctx: &egui::Context) -> Vec<usize> {
+        let theme = self.theme();
+        let panel_frame = egui::Frame::none()
+            .fill(egui::Color32::TRANSPARENT)
+            .stroke(egui::Stroke::NONE);
+        let is_dragging_file = ctx.input(|i| !i.raw.hovered_files.is_empty());
+        if is_dragging_file {
+            ctx.request_repaint();
+        }
+
+        egui::CentralPanel::default()
+            .frame(panel_frame)
+            .show(ctx, |ui| {
+                ui.multiply_opacity(self.fade_opacity(ctx));
+                let response =
+                    ui.allocate_response(ui.available_size(), egui::Sense::click_and_drag());
+                let layout_rounding = rounding(self.config.profile.shape, response.rect.size());
+
+                // Keep rows inside the rounded corners; small radii fit in the padding.
+                let shape_inset = (corner_inset(layout_rounding) - theme.content_padding).max(0.0);
+                let body_rect = egui::Rect::from_min_max(
+                    egui::pos2(
+                        response.rect.min.x + shape_inset,
+                        response.rect.min.y + HEADER_HEIGHT.max(shape_inset),
+                    ),
+                    response.rect.max - egui::vec2(shape_inset, shape_inset),
+                );
+                let content_h = body_rect.height().max(0.0);
+                let visible_h = (self.panel_frac * content_h).clamp(0.0, content_h);
+                let content_rect = egui::Rect::from_min_max(
+                    body_rect.min,
+                    egui::pos2(body_rect.max.x, body_rect.min.y + visible_h),
+                );
+
+                // The painted panel follows `panel_frac`, folding up to the header
+                // when collapsed.
+                let open_frac = (visible_h / content_h.max(1.0)).clamp(0.0, 1.0);
+                let mut panel_rect = response.rect;
+                panel_rect.max.y = panel_rect.min.y
+                    + HEADER_HEIGHT
+                    + (response.rect.height() - HEADER_HEIGHT).max(0.0) * open_frac;
+                let panel_rounding = rounding(self.config.profile.shape, panel_rect.size());
+                self.panel_outline = Some((panel_rect, panel_rounding));
+
+                let window_rect = ctx
+                    .input(|i| i.viewport().outer_rect)
+                    .unwrap_or(egui::Rect::ZERO);
+
+                if !self.frame_effects.1.shadow {
+                    ui.painter().rect_filled(
+                        panel_rect.expand(10.0),
+                        panel_rounding + 10.0,
+                        theme.panel_shadow,
+                    );
+                }
+                ui.painter()
+                    .rect_filled(panel_rect, panel_rounding, theme.panel_bg_bottom);
+                if theme.glow {
+                    paint_glow_blob(
+                        ui.painter(),
+                        egui::pos2(panel_rect.right() - 28.0, panel_rect.top() + 12.0),
+                        62.0,
+                        egui::Color32::from_rgba_premultiplied(75, 197, 165, 6),
+                    );
+                    paint_glow_blob(
+                        ui.painter(),
+                        egui::pos2(panel_rect.left() + 50.0, panel_rect.top() + 40.0),
+                        44.0,
+                        egui::Color32::from_rgba_premultiplied(120, 175, 240, 5),
+                    );
+                }
+                ui.painter().rect_stroke(
+                    panel_rect,
+                    panel_rounding,
+                    egui::Stroke::new(1.0, theme.panel_border),
+                );
+
+                let header_rect = egui::Rect::from_min_size(
+                    panel_rect.min,
+                    egui::vec2(panel_rect.width(), HEADER_HEIGHT),
+                );
+                // Paint the header as the top slice of the panel shape so heavy
+                // rounding doesn't poke its corners outside the panel.
+                ui.painter().with_clip_rect(header_rect).rect_filled(
+                    panel_rect,
+                    panel_rounding,
+                    theme.header_bg_bottom,
+                );
+
+                self.draw_header(ui, header_rect, panel_rounding, &theme);
+                let handle_resp = ui.allocate_rect(header_rect, egui::Sense::click_and_drag());
+                let panel_size = response.rect.size();
+                self.ensure_window_visible(ctx, window_rect, panel_size);
+                self.handle_window_drag(ctx, ui, &handle_resp, window_rect, panel_size, &theme);
+                if !self.config.collapsed && self.panel_anim.is_none() {
+                    self.draw_resize_handles(
+                        ui,
+                        ctx,
+                        response.rect,
+                        panel_rounding,
+                        window_rect,
+                        panel_size,
+                    );
+                }
+                self.update_resize_drag(ctx, window_rect, panel_size);
+
+                if self.config.collapsed {
+                    if handle_resp.clicked() && !self.is_dragging_window {
+                        self.set_collapsed(false);
+                    }
+                } else if handle_resp.double_clicked() && self.panel_anim.is_none() {
+                    self.set_collapsed(true);
+                } else if response.double_clicked() {
+                    self.fire_quick_launch();
+                }
+
+                if handle_resp.hovered() {
+                    // Ctrl+wheel over the header zooms the dock.
+                    let zoom = ctx.input(|i| i.zoom_delta());
+                    if zoom != 1.0 {
+                        self.set_ui_scale(ctx, self.config.ui_scale() * zoom);
+                    }
+                }
+                self.handle_wheel_cycle(ctx);
+
+                handle_resp.context_menu(|ui| self.draw_context_menu(ui));
+                response.context_menu(|ui| self.draw_context_menu(ui));
+
+                let mut to_remove = Vec::new();
+
+                if visible_h > 0.0 {
+                    let list_rect = self.draw_search_bar(ui, content_rect, &theme);
+                    let searching = self.search.as_deref().is_some_and(|q| !q.trim().is_empty());
+                    ui.allocate_new_ui(egui::UiBuilder::new().max_rect(list_rect), |ui| {
+                        if searching {
+                            self.draw_search_results(ui, ctx, list_rect, &theme);
+                        } else {
+                            to_remove =
+                                self.draw_pinned_list(ui, ctx, list_rect, &theme, is_dragging_file);
+                        }
+                    });
+                }
+
+                self.draw_flash_overlay(ui);
+                self.draw_hover_card(ui, &theme);
+                self.draw_wheel_preview(ui, &theme);
+                self.draw_warning_overlay(ui, &theme);
+                self.draw_quit_prompt(ui, &theme);
+                self.draw_launch_prompt(ui, &theme);
+                self.draw_import_dialog(ctx, &theme);
+                self.draw_command_dialog(ctx, &theme);
+                self.draw_launch_options_dialog(ctx, &theme);
+
+                to_remove
+            })
+            .inner
+    }
+
+    /// The search box along the top of the list while it is open; returns
+    /// the area left for the rows. Enter launches the best match and Escape
+    /// closes the box.
+    fn draw_search_bar(
+        &mut self,
+        ui: &mut egui::Ui,
+        content_rect: egui::Rect,
+        theme: &LauncherTheme,
+    ) -> egui::Rect {
+        let Some(query) = self.search.as_mut() else {
+            return content_rect;
+        };
+        let bar_rect = egui::Rect::from_min_size(
+            content_rect.min + egui::vec2(theme.content_padding, theme.content_padding),
+            egui::vec2(
+                (content_rect.width() - theme.content_padding * 2.0).max(0.0),
+                SEARCH_BAR_HEIGHT,
+            ),
+        );
+        let resp = ui.put(
+            bar_rect,
+            egui::TextEdit::singleline(query)
+                .hint_text("Search pins")
+                .desired_width(bar_rect.width()),
+        );
+        if std::mem::take(&mut self.search_focus) {
+            resp.request_focus();
+        }
+        if resp.lost_focus() {
+            let (enter, escape) = ui.input(|i| {
+                (
+                    i.key_pressed(egui::Key::Enter),
+                    i.key_pressed(egui::Key::Escape),
+                )
+            });
+            if escape {
+                self.search = None;
+            } else if enter {
+                let query = self.search.take().unwrap_or_default();
+                let best =
+                    search::rank(&query, self.pinned_apps.iter().map(|app| app.name.as_str()))
+                        .first()
+                        .map(|(idx, _)| *idx);
+                if let Some(idx) = best {
+                    self.request_launch(idx, false);
+                } else {
+                    self.search = Some(query);
+                }
+            }
+        }
+
+        let mut rest = content_rect;
+        rest.min.y = bar_rect.max.y.min(rest.max.y);
+        rest
+    }
+
+    /// Pins matching the search query, best first, with the matched letters
+    /// highlighted. Rows launch and select but don't reorder here.
+    fn draw_search_results(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        content_rect: egui::Rect,
+        theme: &LauncherTheme,
+    ) {
+        ui.add_space(theme.content_padding);
+        let list_width = (content_rect.width() - theme.content_padding * 2.0).max(160.0);
+        let query = self.search.clone().unwrap_or_default();
+        let hits = search::rank(&query, self.pinned_apps.iter().map(|app| app.name.as_str()));
+        if hits.is_empty() {
+            ui.painter().text(
+                content_rect.center_top() + egui::vec2(0.0, 30.0),
+                egui::Align2::CENTER_CENTER,
+                "No matching pins",
+                egui::FontId::proportional(14.0),
+                theme.icon_placeholder,
+            );
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("search_results")
+            .max_height(content_rect.height() - theme.content_padding * 2.0)
+            .show(ui, |ui| {
+                for (idx, hit) in hits {
+                    let (rect, resp) = ui.allocate_exact_size(
+                        egui::vec2(list_width, ROW_HEIGHT),
+                        egui::Sense::click(),
+                    );
+                    let icon_rect = self.paint_pin_row(ui, idx, rect, resp.hovered(), false, theme);
+                    let job = search::highlighted_name(
+                        &self.pinned_apps[idx].name,
+                        &hit.positions,
+                        egui::FontId::proportional(14.0),
+                        theme.title_color,
+                        theme.drop_hint,
+                    );
+                    let galley = ui.fonts(|f| f.layout_job(job));
+                    let text_pos = egui::pos2(
+                        icon_rect.max.x + 9.0,
+                        rect.center().y - galley.size().y * 0.5,
+                    );
+                    ui.painter().galley(text_pos, galley, theme.title_color);
+
+                    if resp.hovered() {
+                        self.note_hover(idx, rect);
+                    }
+                    self.handle_row_activation(ctx, idx, &resp);
+                    ui.add_space(5.0);
+                }
+            });
+    }
+
+    fn draw_header(
+        &self,
+        ui: &egui::Ui,
+        header_rect: egui::Rect,
+        panel_rounding: f32,
+        theme: &LauncherTheme,
+    ) {
+        let title_x = 12.0_f32.max(arc_inset(panel_rounding, header_rect.height() * 0.5) + 6.0);
+        ui.painter().text(
+            egui::pos2(header_rect.min.x + title_x, header_rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            APP_DISPLAY_NAME,
+            egui::FontId::proportional(15.0),
+            theme.title_color,
+        );
+
+        // Capacity only shows up once the dock is close to its pin limit.
+        let used = self.pinned_apps.len();
+        let limit = self.config.pin_limit();
+        if limit.saturating_sub(used) <= self.config.pin_warning_slots {
+            let color = if used >= limit {
+                theme.toast_warning
+            } else {
+                theme.icon_placeholder
+            };
+            ui.painter().text(
+                egui::pos2(header_rect.max.x - title_x, header_rect.center().y),
+                egui::Align2::RIGHT_CENTER,
+                format!("{used}/{limit}"),
+                egui::FontId::proportional(12.0),
+                color,
+            );
+        }
+    }
+
+    fn ensure_window_visible(
+        &mut self,
+        ctx: &egui::Context,
         window_rect: egui::Rect,
         panel_size: egui::Vec2,
+    ) {
+        if self.is_dragging_window || self.resize_drag.is_some() {
+            return;
+        }
+
+        let Some(monitor_size) = self.monitor_size(ctx) else {
+            return;
+        };
+        let window_size = sanitize_window_size(panel_size);
+        let clamped = clamp_window_origin(
+            window_rect.min,
+            window_size,
+            desktop_bounds(ctx, monitor_size),
+        );
+
+        if (clamped.x - window_rect.min.x).abs() > 0.5
+            || (clamped.y - window_rect.min.y).abs() > 0.5
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(clamped));
+            self.save_window_geometry(ctx, clamped, window_size);
+        }
+    }
+
+    /// Rect the dock snaps to: the work area (minus the taskbar) by default,
+    /// or the whole monitor when `snap_to_work_area` is off.
+    fn snap_bounds(&self, ctx: &egui::Context, monitor_size: egui::Vec2) -> egui::Rect {
+        let monitor = egui::Rect::from_min_size(egui::Pos2::ZERO, monitor_size);
+        if !self.config.snap_to_work_area {
+            return monitor;
+        }
+        let Some((left, top, right, bottom)) = crate::system::work_area() else {
+            return monitor;
+        };
+        let ppp = ctx.pixels_per_point();
+        egui::Rect::from_min_max(
+            egui::pos2(left as f32 / ppp, top as f32 / ppp),
+            egui::pos2(right as f32 / ppp, bottom as f32 / ppp),
+        )
+    }
+
+    fn handle_window_drag(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &egui::Ui,
+        handle_resp: &egui::Response,
+        window_rect: egui::Rect,
+        panel_size: egui::Vec2,
+        theme: &LauncherTheme,
     ) {
         if handle_resp.drag_started_by(egui::PointerButton::Primary) {
             self.is_dragging_window = true;
@@ -443,28 +1989,25 @@ impl MyApp {
         if ctx.input(|i| i.pointer.button_released(egui::PointerButton::Primary)) {
             self.is_dragging_window = false;
 
-            let snap_threshold = 48.0;
             let mut new_pos = window_rect.min;
             let window_size = sanitize_window_size(panel_size);
 
-            if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
-                if new_pos.x.abs() < snap_threshold {
-                    new_pos.x = 0.0;
-                } else if (new_pos.x + window_size.x - monitor_size.x).abs() < snap_threshold {
-                    new_pos.x = monitor_size.x - window_size.x;
-                }
-
-                if new_pos.y.abs() < snap_threshold {
-                    new_pos.y = 0.0;
-                } else if (new_pos.y + window_size.y - monitor_size.y).abs() < snap_threshold {
-                    new_pos.y = monitor_size.y - window_size.y;
+            if let Some(monitor_size) = self.monitor_size(ctx) {
+                if let Some(threshold) = self.config.snap_threshold() {
+                    let bounds = self.snap_bounds(ctx, monitor_size);
+                    new_pos = egui::pos2(
+                        snap_axis(new_pos.x, window_size.x, bounds.x_range(), threshold)
+                            .unwrap_or(new_pos.x),
+                        snap_axis(new_pos.y, window_size.y, bounds.y_range(), threshold)
+                            .unwrap_or(new_pos.y),
+                    );
                 }
-
-                new_pos = clamp_window_origin(new_pos, window_size, monitor_size);
+                new_pos =
+                    clamp_window_origin(new_pos, window_size, desktop_bounds(ctx, monitor_size));
             }
 
             ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(new_pos));
-            self.save_window_geometry(new_pos, window_size);
+            self.save_window_geometry(ctx, new_pos, window_size);
 
             self.drag_start_window_pos = None;
             self.drag_start_global_mouse = None;
@@ -479,34 +2022,42 @@ impl MyApp {
                 let delta = current_global_mouse - start_global_mouse;
                 let mut new_origin = start_win_pos + delta;
 
-                if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
-                    let snap_threshold = 48.0;
+                if let Some(monitor_size) = self.monitor_size(ctx) {
+                    let window_size = sanitize_window_size(panel_size);
+                    new_origin = clamp_window_origin(
+                        new_origin,
+                        window_size,
+                        desktop_bounds(ctx, monitor_size),
+                    );
+                }
+                if let (Some(monitor_size), Some(threshold)) =
+                    (self.monitor_size(ctx), self.config.snap_threshold())
+                {
                     let window_size = sanitize_window_size(panel_size);
-                    new_origin = clamp_window_origin(new_origin, window_size, monitor_size);
-                    let snap_color = egui::Color32::from_rgba_premultiplied(75, 197, 165, 160);
-                    let stroke = egui::Stroke::new(2.0, snap_color);
+                    let bounds = self.snap_bounds(ctx, monitor_size);
+                    let stroke = theme.snap_guide_stroke();
 
-                    if new_origin.x.abs() < snap_threshold {
+                    if let Some(x) =
+                        snap_axis(new_origin.x, window_size.x, bounds.x_range(), threshold)
+                    {
+                        let guide_x = if x <= bounds.min.x {
+                            0.0
+                        } else {
+                            window_size.x - stroke.width
+                        };
                         ui.painter()
-                            .vline(0.0, egui::Rangef::new(0.0, window_size.y), stroke);
-                    }
-                    if (new_origin.x + window_size.x - monitor_size.x).abs() < snap_threshold {
-                        ui.painter().vline(
-                            window_size.x - 2.0,
-                            egui::Rangef::new(0.0, window_size.y),
-                            stroke,
-                        );
+                            .vline(guide_x, egui::Rangef::new(0.0, window_size.y), stroke);
                     }
-                    if new_origin.y.abs() < snap_threshold {
+                    if let Some(y) =
+                        snap_axis(new_origin.y, window_size.y, bounds.y_range(), threshold)
+                    {
+                        let guide_y = if y <= bounds.min.y {
+                            0.0
+                        } else {
+                            window_size.y - stroke.width
+                        };
                         ui.painter()
-                            .hline(egui::Rangef::new(0.0, window_size.x), 0.0, stroke);
-                    }
-                    if (new_origin.y + window_size.y - monitor_size.y).abs() < snap_threshold {
-                        ui.painter().hline(
-                            egui::Rangef::new(0.0, window_size.x),
-                            window_size.y - 2.0,
-                            stroke,
-                        );
+                            .hline(egui::Rangef::new(0.0, window_size.x), guide_y, stroke);
                     }
                 }
 
@@ -520,29 +2071,65 @@ impl MyApp {
         ui: &mut egui::Ui,
         ctx: &egui::Context,
         panel_rect: egui::Rect,
+        panel_rounding: f32,
         window_rect: egui::Rect,
         panel_size: egui::Vec2,
     ) {
+        // Straight edges only run between the corner arcs; the corner zones
+        // slide in along the diagonal so they sit on the visible outline.
+        let straight = panel_rounding.max(RESIZE_CORNER_SIZE);
+        let corner = corner_inset(panel_rounding);
         let left = egui::Rect::from_min_max(
-            panel_rect.min,
-            egui::pos2(panel_rect.min.x + RESIZE_EDGE_THICKNESS, panel_rect.max.y),
+            egui::pos2(panel_rect.min.x, panel_rect.min.y + panel_rounding),
+            egui::pos2(
+                panel_rect.min.x + RESIZE_EDGE_THICKNESS,
+                panel_rect.max.y - straight,
+            ),
         );
         let right = egui::Rect::from_min_max(
-            egui::pos2(panel_rect.max.x - RESIZE_EDGE_THICKNESS, panel_rect.min.y),
-            panel_rect.max,
+            egui::pos2(
+                panel_rect.max.x - RESIZE_EDGE_THICKNESS,
+                panel_rect.min.y + panel_rounding,
+            ),
+            egui::pos2(panel_rect.max.x, panel_rect.max.y - straight),
+        );
+        let top = egui::Rect::from_min_max(
+            egui::pos2(panel_rect.min.x + straight, panel_rect.min.y),
+            egui::pos2(
+                panel_rect.max.x - straight,
+                panel_rect.min.y + RESIZE_TOP_THICKNESS,
+            ),
         );
         let bottom = egui::Rect::from_min_max(
-            egui::pos2(panel_rect.min.x, panel_rect.max.y - RESIZE_EDGE_THICKNESS),
-            panel_rect.max,
+            egui::pos2(
+                panel_rect.min.x + straight,
+                panel_rect.max.y - RESIZE_EDGE_THICKNESS,
+            ),
+            egui::pos2(panel_rect.max.x - straight, panel_rect.max.y),
+        );
+
+        let top_left = egui::Rect::from_min_size(
+            panel_rect.min + egui::vec2(corner, corner),
+            egui::vec2(RESIZE_CORNER_SIZE, RESIZE_CORNER_SIZE),
+        );
+        let top_right = egui::Rect::from_min_size(
+            egui::pos2(
+                panel_rect.max.x - corner - RESIZE_CORNER_SIZE,
+                panel_rect.min.y + corner,
+            ),
+            egui::vec2(RESIZE_CORNER_SIZE, RESIZE_CORNER_SIZE),
         );
 
-        let bottom_left = egui::Rect::from_min_max(
-            egui::pos2(panel_rect.min.x, panel_rect.max.y - RESIZE_CORNER_SIZE),
-            egui::pos2(panel_rect.min.x + RESIZE_CORNER_SIZE, panel_rect.max.y),
+        let bottom_left = egui::Rect::from_min_size(
+            egui::pos2(
+                panel_rect.min.x + corner,
+                panel_rect.max.y - corner - RESIZE_CORNER_SIZE,
+            ),
+            egui::vec2(RESIZE_CORNER_SIZE, RESIZE_CORNER_SIZE),
         );
-        let bottom_right = egui::Rect::from_min_max(
-            panel_rect.max - egui::vec2(RESIZE_CORNER_SIZE, RESIZE_CORNER_SIZE),
-            panel_rect.max,
+        let bottom_right = egui::Rect::from_min_size(
+            panel_rect.max - egui::vec2(corner + RESIZE_CORNER_SIZE, corner + RESIZE_CORNER_SIZE),
+            egui::vec2(RESIZE_CORNER_SIZE, RESIZE_CORNER_SIZE),
         );
 
         self.interact_resize_zone(
@@ -561,8 +2148,25 @@ impl MyApp {
             window_rect,
             panel_size,
         );
+        self.interact_resize_zone(
+            ui,
+            ctx,
+            ResizeEdge::TopLeft,
+            top_left,
+            window_rect,
+            panel_size,
+        );
+        self.interact_resize_zone(
+            ui,
+            ctx,
+            ResizeEdge::TopRight,
+            top_right,
+            window_rect,
+            panel_size,
+        );
         self.interact_resize_zone(ui, ctx, ResizeEdge::Left, left, window_rect, panel_size);
         self.interact_resize_zone(ui, ctx, ResizeEdge::Right, right, window_rect, panel_size);
+        self.interact_resize_zone(ui, ctx, ResizeEdge::Top, top, window_rect, panel_size);
         self.interact_resize_zone(ui, ctx, ResizeEdge::Bottom, bottom, window_rect, panel_size);
     }
 
@@ -590,11 +2194,14 @@ impl MyApp {
             self.drag_start_global_mouse = None;
 
             if let Some(hover_pos) = ctx.input(|i| i.pointer.hover_pos()) {
+                let start_size = sanitize_window_size(panel_size);
                 self.resize_drag = Some(ResizeDragState {
                     edge,
                     start_window_pos: window_rect.min,
-                    start_window_size: sanitize_window_size(panel_size),
+                    start_window_size: start_size,
                     start_global_mouse: window_rect.min + hover_pos.to_vec2(),
+                    sent_pos: window_rect.min,
+                    sent_size: start_size,
                 });
             }
         }
@@ -620,29 +2227,45 @@ impl MyApp {
                 .input(|i| i.viewport().inner_rect)
                 .map(|r| r.size())
                 .unwrap_or_else(|| sanitize_window_size(panel_size));
-            let saved_pos = if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
-                clamp_window_origin(saved_pos, sanitize_window_size(saved_size), monitor_size)
+            let saved_pos = if let Some(monitor_size) = self.monitor_size(ctx) {
+                clamp_window_origin(
+                    saved_pos,
+                    sanitize_window_size(saved_size),
+                    desktop_bounds(ctx, monitor_size),
+                )
             } else {
                 saved_pos
             };
-            self.save_window_geometry(saved_pos, saved_size);
+            self.save_window_geometry(ctx, saved_pos, saved_size);
             return;
         }
 
         let Some(hover_pos) = ctx.input(|i| i.pointer.hover_pos()) else {
             return;
         };
-        let current_origin = ctx
+        // The pointer is relative to where the window is now, not to what was
+        // last sent, so pair it with the reported origin.
+        let origin = ctx
             .input(|i| i.viewport().outer_rect)
-            .map(|r| r.min)
-            .unwrap_or(window_rect.min);
-        let current_global_mouse = current_origin + hover_pos.to_vec2();
-        let delta = current_global_mouse - state.start_global_mouse;
+            .map_or(window_rect.min, |rect| rect.min);
+        let delta = resize_pointer_delta(origin, hover_pos, state.start_global_mouse);
         let (new_pos, new_size) = self.apply_resize_delta(ctx, state, delta);
 
-        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(new_pos));
-        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(new_size));
-        ctx.request_repaint();
+        let moved = (new_pos - state.sent_pos).abs().max_elem() > RESIZE_SEND_THRESHOLD;
+        let resized = (new_size - state.sent_size).abs().max_elem() > RESIZE_SEND_THRESHOLD;
+        if moved {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(new_pos));
+        }
+        if resized {
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(new_size));
+        }
+        if moved || resized {
+            if let Some(drag) = &mut self.resize_drag {
+                drag.sent_pos = new_pos;
+                drag.sent_size = new_size;
+            }
+            ctx.request_repaint();
+        }
     }
 
     fn apply_resize_delta(
@@ -651,8 +2274,8 @@ impl MyApp {
         state: ResizeDragState,
         delta: egui::Vec2,
     ) -> (egui::Pos2, egui::Vec2) {
-        let max_size = ctx
-            .input(|i| i.viewport().monitor_size)
+        let max_size = self
+            .monitor_size(ctx)
             .map(|size| {
                 egui::vec2(
                     (size.x - 8.0).max(MIN_WINDOW_WIDTH),
@@ -664,65 +2287,515 @@ impl MyApp {
         let clamp_width = |w: f32| w.clamp(MIN_WINDOW_WIDTH, max_size.x);
         let clamp_height = |h: f32| h.clamp(MIN_WINDOW_HEIGHT, max_size.y);
 
-        let mut pos = state.start_window_pos;
         let mut size = state.start_window_size;
 
-        match state.edge {
-            ResizeEdge::Left => {
-                let new_width = clamp_width(state.start_window_size.x - delta.x);
-                pos.x = state.start_window_pos.x + (state.start_window_size.x - new_width);
-                size.x = new_width;
+        let (from_left, from_right, from_top, from_bottom) = resize_edge_sides(state.edge);
+        if from_left {
+            size.x = clamp_width(state.start_window_size.x - delta.x);
+        } else if from_right {
+            size.x = clamp_width(state.start_window_size.x + delta.x);
+        }
+        if from_top {
+            size.y = clamp_height(state.start_window_size.y - delta.y);
+        } else if from_bottom {
+            size.y = clamp_height(state.start_window_size.y + delta.y);
+        }
+
+        // Shift flips the lock for this drag: frees a locked window, locks a free one.
+        let shift = ctx.input(|i| i.modifiers.shift);
+        let ratio = match (self.config.locked_aspect(), shift) {
+            (Some(ratio), false) => Some(ratio),
+            (None, true) => Some(self.config.aspect_ratio()),
+            _ => None,
+        };
+        if let Some(ratio) = ratio {
+            // Corners follow whichever side moved further, relative to its length.
+            let width_drives = match (from_left || from_right, from_top || from_bottom) {
+                (true, true) => {
+                    (size.x / state.start_window_size.x - 1.0).abs()
+                        >= (size.y / state.start_window_size.y - 1.0).abs()
+                }
+                (horizontal, _) => horizontal,
+            };
+            let min_size = egui::vec2(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT);
+            size = lock_aspect(size, ratio, width_drives, min_size, max_size);
+        }
+
+        let mut pos = resized_origin(
+            state.edge,
+            state.start_window_pos,
+            state.start_window_size,
+            size,
+        );
+
+        let size = sanitize_window_size(size);
+        if let Some(monitor_size) = self.monitor_size(ctx) {
+            pos = clamp_window_origin(pos, size, desktop_bounds(ctx, monitor_size));
+        }
+
+        (pos, size)
+    }
+
+    /// The right-click menu of pin `idx`, shared by the list and the grid.
+    fn pin_context_menu(&self, ui: &mut egui::Ui, idx: usize) -> Option<PinMenuAction> {
+        let app = &self.pinned_apps[idx];
+        let batch = self.batch_selection_for(idx);
+        let mut action = None;
+        if app.path.is_dir() && ui.button("Open in terminal here").clicked() {
+            action = Some(PinMenuAction::OpenTerminal);
+        }
+        if let Some(locations) = pin_locations(app) {
+            if let Some(path) = draw_location_menu(ui, "Open file location", &locations) {
+                action = Some(PinMenuAction::Reveal(path));
+            }
+            // Only Explorer can be asked for a Properties dialog.
+            if cfg!(windows) {
+                if let Some(path) = draw_location_menu(ui, "Properties", &locations) {
+                    action = Some(PinMenuAction::Properties(path));
+                }
+            }
+        }
+        if let Some(opener) = document_opener(app) {
+            if let Some(choice) = draw_open_with_menu(ui, opener.as_deref()) {
+                action = Some(PinMenuAction::OpenWith(choice));
+            }
+        }
+        if ui.button("Launch options…").clicked() {
+            action = Some(PinMenuAction::EditOptions);
+        }
+        if ui.button(favorite_menu_label(app.favorite)).clicked() {
+            action = Some(PinMenuAction::ToggleFavorite);
+        }
+        if let Some(batch) = &batch {
+            if ui
+                .button(format!("Launch selected ({})", batch.len()))
+                .clicked()
+            {
+                action = Some(PinMenuAction::LaunchSelected(batch.clone()));
+            }
+        }
+        if ui.button("Remove").clicked() {
+            action = Some(PinMenuAction::Remove(vec![idx]));
+        }
+        if let Some(batch) = batch {
+            if ui
+                .button(format!("Remove selected ({})", batch.len()))
+                .clicked()
+            {
+                action = Some(PinMenuAction::Remove(batch));
+            }
+        }
+        if action.is_some() {
+            ui.close_menu();
+        }
+        action
+    }
+
+    /// Carries out what was picked in pin `idx`'s menu. Removals are handed
+    /// back, since pins can only go once drawing is done.
+    fn apply_pin_menu_action(
+        &mut self,
+        ctx: &egui::Context,
+        idx: usize,
+        action: PinMenuAction,
+    ) -> Option<Vec<usize>> {
+        match action {
+            PinMenuAction::OpenTerminal => self.open_terminal_in(idx),
+            PinMenuAction::Reveal(path) => self.reveal_pin_path(&path),
+            PinMenuAction::Properties(path) => self.show_pin_properties(&path),
+            PinMenuAction::OpenWith(choice) => self.apply_open_with(ctx, idx, choice),
+            PinMenuAction::EditOptions => self.open_launch_options(idx),
+            PinMenuAction::ToggleFavorite => self.pending_favorite = Some(idx),
+            PinMenuAction::LaunchSelected(batch) => {
+                let delay = Duration::from_millis(self.config.launch_delay_ms);
+                self.launch_group(ctx, &batch, delay);
+            }
+            PinMenuAction::Remove(indices) => return Some(indices),
+        }
+        None
+    }
+
+    fn draw_context_menu(&mut self, ui: &mut egui::Ui) {
+        style_compact_menu(ui);
+        if !self.config.collapsed && ui.button("Search pins (Ctrl+F)").clicked() {
+            ui.close_menu();
+            self.open_search(ui.ctx());
+        }
+        ui.menu_button("Profile", |ui| self.draw_profile_menu(ui));
+        if ui
+            .checkbox(&mut self.auto_start_enabled, "Auto-start")
+            .clicked()
+        {
+            if let Err(err) = set_auto_start(self.auto_start_enabled, self.config.start_minimized) {
+                error!("failed to set auto-start: {err}");
+                self.auto_start_enabled = !self.auto_start_enabled;
+                self.show_toast(Severity::Error, "Auto-start failed");
+            }
+        }
+
+        let mut start_minimized = self.config.start_minimized;
+        if ui
+            .checkbox(&mut start_minimized, "Start minimized to tray")
+            .changed()
+        {
+            self.config.start_minimized = start_minimized;
+            self.config.save();
+            // Rewrite the Run entry so the next boot picks up the flag.
+            if self.auto_start_enabled {
+                if let Err(err) = set_auto_start(true, start_minimized) {
+                    error!("failed to update auto-start: {err}");
+                    self.show_toast(Severity::Error, "Auto-start failed");
+                }
+            }
+        }
+
+        if ui
+            .checkbox(&mut self.config.aspect_lock, "Lock aspect ratio")
+            .on_hover_text("Hold Shift while resizing to flip this for one drag")
+            .changed()
+        {
+            self.config.save();
+        }
+        if ui
+            .checkbox(&mut self.config.apply_size_on_show, "Restore size on show")
+            .on_hover_text("Move and resize back to the saved geometry whenever the dock is shown")
+            .changed()
+        {
+            self.config.save();
+        }
+
+        let mut snappy = self.config.animation == AnimationConfig::snappy();
+        if ui.checkbox(&mut snappy, "Snappy animations").changed() {
+            self.config.animation = if snappy {
+                AnimationConfig::snappy()
+            } else {
+                AnimationConfig::default()
+            };
+            self.config.save();
+        }
+
+        let mut reduce_motion = self.reduce_motion();
+        if ui.checkbox(&mut reduce_motion, "Reduce motion").changed() {
+            // Matching the OS choice goes back to following it.
+            self.config.reduce_motion =
+                (reduce_motion != self.system_reduce_motion).then_some(reduce_motion);
+            self.config.save();
+        }
+
+        let mut grid_mode = self.config.profile.grid_mode;
+        if ui.checkbox(&mut grid_mode, "Grid mode").changed() {
+            self.set_grid_mode(grid_mode);
+        }
+
+        if grid_mode {
+            self.draw_grid_size_controls(ui);
+        }
+        self.draw_pin_limit_control(ui);
+        self.draw_ui_scale_control(ui);
+
+        let collapse_label = if self.config.collapsed {
+            "Expand"
+        } else {
+            "Collapse"
+        };
+        if ui.button(collapse_label).clicked() {
+            self.set_collapsed(!self.config.collapsed);
+            ui.close_menu();
+        }
+
+        ui.menu_button("Shape", |ui| {
+            for (shape, label) in [
+                (WindowShape::Pill, "Pill"),
+                (WindowShape::RoundedRect, "Rounded"),
+                (WindowShape::Square, "Square"),
+            ] {
+                if ui
+                    .radio(self.config.profile.shape == shape, label)
+                    .clicked()
+                {
+                    self.config.profile.shape = shape;
+                    self.config.save();
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("Theme", |ui| {
+            for (mode, label) in [
+                (ThemeMode::System, "Follow system"),
+                (ThemeMode::Standard, "Standard"),
+                (ThemeMode::HighContrast, "High contrast"),
+            ] {
+                if ui.radio(self.config.profile.theme == mode, label).clicked() {
+                    self.config.profile.theme = mode;
+                    self.config.save();
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("Appearance", |ui| {
+            for (appearance, label) in [
+                (Appearance::Decorative, "Decorative"),
+                (Appearance::Minimal, "Minimal"),
+            ] {
+                if ui
+                    .radio(self.config.profile.appearance == appearance, label)
+                    .clicked()
+                {
+                    self.config.profile.appearance = appearance;
+                    self.config.save();
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("Launch with", |ui| {
+            for (mode, label) in [
+                (ActivationMode::SingleClick, "Single click"),
+                (ActivationMode::DoubleClick, "Double click"),
+            ] {
+                if ui.radio(self.config.activate_on == mode, label).clicked() {
+                    self.config.activate_on = mode;
+                    self.config.save();
+                    ui.close_menu();
+                }
             }
-            ResizeEdge::Right => {
-                size.x = clamp_width(state.start_window_size.x + delta.x);
+            ui.separator();
+            ui.label("Delay between selected apps");
+            for (delay_ms, label) in [(0, "None"), (500, "0.5 s"), (1000, "1 s"), (2000, "2 s")] {
+                if ui
+                    .radio(self.config.launch_delay_ms == delay_ms, label)
+                    .clicked()
+                {
+                    self.config.launch_delay_ms = delay_ms;
+                    self.config.save();
+                    ui.close_menu();
+                }
             }
-            ResizeEdge::Bottom => {
-                size.y = clamp_height(state.start_window_size.y + delta.y);
+        });
+        ui.menu_button("Quick launch", |ui| self.draw_quick_launch_menu(ui));
+
+        if ui.button("Import taskbar pins…").clicked() {
+            self.open_taskbar_import();
+            ui.close_menu();
+        }
+        if ui.button("New command pin…").clicked() {
+            self.command_draft = Some(CommandDraft::default());
+            ui.close_menu();
+        }
+
+        if ui
+            .checkbox(&mut self.config.profile.acrylic, "Acrylic backdrop")
+            .changed()
+        {
+            self.config.save();
+        }
+
+        if ui
+            .checkbox(&mut self.config.profile.system_shadow, "System shadow")
+            .changed()
+        {
+            self.config.save();
+        }
+
+        if ui
+            .checkbox(&mut self.config.snap_enabled, "Snap to edges")
+            .changed()
+        {
+            self.config.save();
+        }
+        if ui
+            .add_enabled(
+                self.config.snap_enabled,
+                egui::Checkbox::new(&mut self.config.snap_to_work_area, "Snap to work area"),
+            )
+            .changed()
+        {
+            self.config.save();
+        }
+
+        if ui
+            .checkbox(&mut self.config.pin_any_file, "Allow pinning any file")
+            .changed()
+        {
+            // A refusal from before no longer holds.
+            if self.config.pin_any_file {
+                self.dismiss_toast(UNSUPPORTED_PIN_TOAST);
             }
-            ResizeEdge::BottomLeft => {
-                let new_width = clamp_width(state.start_window_size.x - delta.x);
-                pos.x = state.start_window_pos.x + (state.start_window_size.x - new_width);
-                size.x = new_width;
-                size.y = clamp_height(state.start_window_size.y + delta.y);
+            self.config.save();
+        }
+
+        if ui
+            .checkbox(&mut self.config.reorder_handle, "Reorder with drag handle")
+            .changed()
+        {
+            self.press_candidate = None;
+            self.config.save();
+        }
+
+        let mut favorites_first = self.config.profile.favorites_first;
+        if ui
+            .checkbox(&mut favorites_first, "Favorites first")
+            .changed()
+        {
+            self.reset_drag_state();
+            self.config.profile.favorites_first = favorites_first;
+            self.config.save();
+        }
+
+        ui.menu_button("Hide by", |ui| {
+            for (method, label) in [
+                (HideMethod::Minimize, "Minimizing"),
+                (HideMethod::Hide, "Hiding completely"),
+            ] {
+                if ui.radio(self.config.hide_method == method, label).clicked() {
+                    self.config.hide_method = method;
+                    self.config.save();
+                    ui.close_menu();
+                }
             }
-            ResizeEdge::BottomRight => {
-                size.x = clamp_width(state.start_window_size.x + delta.x);
-                size.y = clamp_height(state.start_window_size.y + delta.y);
+        });
+        ui.menu_button("Window level", |ui| {
+            for (level, label) in [
+                (DockLevel::AlwaysOnTop, "Always on top"),
+                (DockLevel::Normal, "Normal"),
+                (DockLevel::Desktop, "On the desktop"),
+            ] {
+                if ui.radio(self.config.window_level == level, label).clicked() {
+                    self.set_window_level(ui.ctx(), level);
+                    ui.close_menu();
+                }
             }
+        });
+
+        if ui
+            .checkbox(&mut self.config.focus_on_show, "Focus when shown")
+            .changed()
+        {
+            self.config.save();
         }
 
-        let size = sanitize_window_size(size);
-        if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
-            pos = clamp_window_origin(pos, size, monitor_size);
+        if ui
+            .checkbox(
+                &mut self.config.animate_tray_on_launch,
+                "Animate tray on launch",
+            )
+            .changed()
+        {
+            self.config.save();
+        }
+
+        if ui
+            .checkbox(
+                &mut self.config.exclude_from_capture,
+                "Hide from screen capture",
+            )
+            .changed()
+        {
+            self.config.save();
         }
 
-        (pos, size)
-    }
-    fn draw_context_menu(&mut self, ui: &mut egui::Ui) {
-        style_compact_menu(ui);
+        let mut hide_in_fullscreen = self.config.hide_in_fullscreen;
         if ui
-            .checkbox(&mut self.auto_start_enabled, "Auto-start")
-            .clicked()
+            .checkbox(&mut hide_in_fullscreen, "Hide over fullscreen apps")
+            .changed()
         {
-            if let Err(err) = set_auto_start(self.auto_start_enabled) {
-                eprintln!("Failed to set auto-start: {}", err);
-                self.auto_start_enabled = !self.auto_start_enabled;
-                self.show_warning("Auto-start failed");
-            }
+            self.set_hide_in_fullscreen(hide_in_fullscreen);
+        }
+
+        let mut confirm_quit = self.config.confirm_quit;
+        if ui.checkbox(&mut confirm_quit, "Confirm on quit").changed() {
+            self.set_confirm_quit(confirm_quit);
         }
 
-        let mut two_column_mode = self.config.two_column_mode;
         if ui
-            .checkbox(&mut two_column_mode, "Two-column mode")
+            .checkbox(
+                &mut self.config.confirm_risky_launch,
+                "Confirm elevated and network launches",
+            )
             .changed()
         {
-            self.set_two_column_mode(two_column_mode);
+            self.config.save();
+        }
+
+        let hotkeys = super::runtime::hotkey_help();
+        if !hotkeys.is_empty() {
+            ui.menu_button("Hotkeys", |ui| {
+                for (action, hotkey, fallback) in hotkeys {
+                    ui.label(format!("{action}: {hotkey} (or {fallback})"));
+                }
+                if self.config.next_profile_vk().is_some() {
+                    let key = self.config.next_profile_key.trim().to_ascii_uppercase();
+                    ui.label(format!("Next profile: Ctrl+Alt+Shift+{key}"));
+                }
+            });
+        }
+
+        ui.menu_button("Missing targets", |ui| {
+            for (policy, label) in [
+                (MissingPins::Keep, "Keep dimmed"),
+                (MissingPins::SetAside, "Set aside on start"),
+            ] {
+                if ui
+                    .radio(self.config.missing_pins == policy, label)
+                    .clicked()
+                {
+                    self.config.missing_pins = policy;
+                    self.config.save();
+                    ui.close_menu();
+                }
+            }
+        });
+        if !self.config.profile.broken_pins.is_empty() {
+            let mut restore = None;
+            let mut forget = false;
+            ui.menu_button(
+                format!("Missing apps ({})", self.config.profile.broken_pins.len()),
+                |ui| {
+                    for (i, meta) in self.config.profile.broken_pins.iter().enumerate() {
+                        let name = broken_pin_name(meta);
+                        if ui
+                            .button(name)
+                            .on_hover_text(meta.path.display().to_string())
+                            .clicked()
+                        {
+                            restore = Some(i);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Forget all").clicked() {
+                        forget = true;
+                        ui.close_menu();
+                    }
+                },
+            );
+            if let Some(i) = restore {
+                self.restore_broken_pin(i);
+            }
+            if forget {
+                self.config.profile.broken_pins.clear();
+                self.config.save();
+            }
+        }
+
+        ui.separator();
+        if ui.button("Open config folder").clicked() {
+            self.open_config_folder();
+            ui.close_menu();
+        }
+        if ui.button("Open log folder").clicked() {
+            self.open_log_folder();
+            ui.close_menu();
+        }
+        if ui.button("Clear icon cache").clicked() {
+            self.clear_icon_cache();
+            ui.close_menu();
         }
 
         ui.separator();
         if ui.button("Quit").clicked() {
-            info!("Exiting via context menu...");
-            std::process::exit(0);
+            info!("Quit requested via context menu...");
+            ui.close_menu();
+            self.request_quit(ui.ctx());
         }
     }
 
@@ -733,11 +2806,11 @@ impl MyApp {
         content_rect: egui::Rect,
         theme: &LauncherTheme,
         is_dragging_file: bool,
-    ) -> Option<usize> {
-        ui.add_space(CONTENT_PADDING);
-        let list_width = (content_rect.width() - CONTENT_PADDING * 2.0).max(160.0);
+    ) -> Vec<usize> {
+        ui.add_space(theme.content_padding);
+        let list_width = (content_rect.width() - theme.content_padding * 2.0).max(160.0);
 
-        if self.config.two_column_mode {
+        if self.config.column_count() > 1 {
             return self.draw_pinned_grid(
                 ui,
                 ctx,
@@ -752,16 +2825,16 @@ impl MyApp {
         if self.pinned_apps.is_empty() {
             let empty_rect = egui::Rect::from_min_max(
                 egui::pos2(
-                    content_rect.min.x + CONTENT_PADDING,
-                    content_rect.min.y + CONTENT_PADDING,
+                    content_rect.min.x + theme.content_padding,
+                    content_rect.min.y + theme.content_padding,
                 ),
                 egui::pos2(
-                    content_rect.max.x - CONTENT_PADDING,
-                    content_rect.max.y - CONTENT_PADDING,
+                    content_rect.max.x - theme.content_padding,
+                    content_rect.max.y - theme.content_padding,
                 ),
             );
             ui.painter()
-                .rect_stroke(empty_rect, 12.0, egui::Stroke::new(1.0, theme.drop_hint));
+                .rect_stroke(empty_rect, 12.0, theme.drop_hint_stroke());
             ui.painter().text(
                 empty_rect.center_top() + egui::vec2(0.0, 42.0),
                 egui::Align2::CENTER_CENTER,
@@ -776,7 +2849,7 @@ impl MyApp {
                     egui::Color32::from_rgba_premultiplied(75, 197, 165, 26),
                 );
             }
-            return None;
+            return Vec::new();
         }
 
         let drag_i = if self.drop_anim.is_some() {
@@ -784,127 +2857,159 @@ impl MyApp {
         } else {
             self.dragging_app
         };
+        let folders = pin_folders(&self.pinned_apps, &self.config.profile.pin_groups);
+        let order = self.render_order();
+        let merge_target = self.merge_target.filter(|_| drag_i.is_some());
+        let merge_folder = merge_target.and_then(|idx| folders.get(idx).copied().flatten());
         let placeholder_slot = self
             .drop_anim
             .as_ref()
-            .map(|anim| anim.insert_at)
-            .or(self.drag_target);
+            .map(|anim| anim.slot)
+            .or(self.drag_target)
+            .filter(|_| merge_target.is_none());
         let pointer_pos = ctx.input(|i| i.pointer.hover_pos());
         let mut rects_for_target: Vec<egui::Rect> = Vec::new();
-        let mut remove_idx = None;
-
-        egui::ScrollArea::vertical()
-            .max_height(content_rect.height() - CONTENT_PADDING * 2.0)
+        // Rows a dragged pin can be dropped onto, with the pin it merges with.
+        let mut merge_rows: Vec<(egui::Rect, usize)> = Vec::new();
+        // Each folder's row and, when open, its pins below it.
+        let mut folder_rows: HashMap<usize, egui::Rect> = HashMap::new();
+        let mut folder_action = None;
+        let mut to_remove = Vec::new();
+
+        let scroll_output = self
+            .reorder_scroll_area(ctx, content_rect, None, drag_i.is_some(), theme)
             .show(ui, |ui| {
                 let mut slot_index = 0usize;
 
-                for idx in 0..self.pinned_apps.len() {
+                for &idx in &order {
                     if drag_i == Some(idx) {
                         continue;
                     }
 
-                    if placeholder_slot == Some(slot_index)
-                        && (self.dragging_app.is_some() || self.drop_anim.is_some())
-                    {
-                        let (r, _) = ui.allocate_exact_size(
-                            egui::vec2(list_width, ROW_HEIGHT),
-                            egui::Sense::hover(),
+                    let folder = folders[idx];
+                    let opens_folder = folder.filter(|group| !folder_rows.contains_key(group));
+                    let placeholder_here = placeholder_slot == Some(slot_index)
+                        && (self.dragging_app.is_some() || self.drop_anim.is_some());
+                    // In front of a folder's first pin, the slot is either just
+                    // above the folder row or just below it, inside the folder.
+                    let placeholder_inside =
+                        opens_folder.is_some() && self.drop_folder == opens_folder;
+                    if placeholder_here && !placeholder_inside {
+                        paint_drop_slot(ui, list_width, self.drop_folder.is_some(), theme);
+                    }
+
+                    if let Some(group) = opens_folder {
+                        let count = folders.iter().filter(|f| **f == Some(group)).count();
+                        let (row, action) = self.draw_folder_row(
+                            ui,
+                            group,
+                            count,
+                            merge_folder == Some(group),
+                            list_width,
+                            theme,
                         );
-                        ui.painter()
-                            .rect_stroke(r, 8.0, egui::Stroke::new(1.0, theme.drop_hint));
+                        if let Some(action) = action {
+                            folder_action = Some((group, action));
+                        }
+                        folder_rows.insert(group, row);
+                        merge_rows.push((row, idx));
                         ui.add_space(5.0);
+                        if placeholder_here && placeholder_inside {
+                            paint_drop_slot(ui, list_width, true, theme);
+                        }
+                    }
+                    if let Some(group) =
+                        folder.filter(|group| !self.config.profile.pin_groups[*group].expanded)
+                    {
+                        // Folded pins take their folder's row as their drop slot.
+                        rects_for_target.push(folder_rows[&group]);
+                        slot_index += 1;
+                        continue;
                     }
 
-                    let (rect, resp) = ui.allocate_exact_size(
+                    let (row, resp) = ui.allocate_exact_size(
                         egui::vec2(list_width, ROW_HEIGHT),
                         egui::Sense::click_and_drag(),
                     );
-                    rects_for_target.push(rect);
+                    rects_for_target.push(row);
+                    merge_rows.push((row, idx));
+                    // Folder pins sit indented under their folder row, which
+                    // grows into the folder's whole span.
+                    let rect = if let Some(group) = folder {
+                        if let Some(span) = folder_rows.get_mut(&group) {
+                            *span = span.union(row);
+                        }
+                        row.with_min_x(row.min.x + FOLDER_INDENT)
+                    } else {
+                        row
+                    };
 
-                    if resp.is_pointer_button_down_on()
-                        && self.drop_anim.is_none()
+                    let grip_rect = egui::Rect::from_min_max(
+                        rect.min,
+                        egui::pos2(rect.min.x + REORDER_GRIP_WIDTH, rect.max.y),
+                    );
+                    let can_lift = self.drop_anim.is_none()
                         && self.dragging_app.is_none()
-                        && self.press_candidate.is_none()
-                    {
+                        && self.press_candidate.is_none();
+                    if self.config.reorder_handle {
+                        // The grip lifts the row on press; the rest of the row only clicks.
+                        let grip_pressed = ctx.input(|i| {
+                            i.pointer.primary_pressed()
+                                && i.pointer
+                                    .interact_pos()
+                                    .is_some_and(|p| grip_rect.contains(p))
+                        });
+                        if can_lift && grip_pressed {
+                            self.dragging_app = Some(idx);
+                            self.drag_target = Some(slot_index);
+                            ctx.request_repaint();
+                        }
+                    } else if resp.is_pointer_button_down_on() && can_lift {
                         if let Some(p) = ctx.input(|i| i.pointer.hover_pos()) {
                             self.press_candidate = Some((idx, Instant::now(), p));
                         }
                     }
 
-                    let is_selected = self.selected_app == Some(idx);
-                    let fill = if is_selected {
-                        theme.row_selected
-                    } else if resp.hovered() {
-                        theme.row_hover
-                    } else {
-                        theme.row_bg
-                    };
-                    ui.painter().rect_filled(rect, 8.0, fill);
-                    if is_selected || resp.hovered() {
-                        ui.painter().rect_stroke(
-                            rect,
-                            8.0,
-                            egui::Stroke::new(1.0, theme.row_border),
-                        );
+                    let merge_hint = merge_target == Some(idx) && merge_folder.is_none();
+                    let icon_rect =
+                        self.paint_pin_row(ui, idx, rect, resp.hovered(), merge_hint, theme);
+                    if self.config.reorder_handle && resp.hovered() {
+                        paint_grip(ui.painter(), grip_rect, theme.icon_placeholder);
                     }
 
-                    let icon_rect = egui::Rect::from_center_size(
-                        egui::pos2(rect.min.x + 14.0 + ICON_SIDE * 0.5, rect.center().y),
-                        egui::vec2(ICON_SIDE, ICON_SIDE),
-                    );
-
-                    if self.pinned_apps[idx].texture.is_none()
-                        && !self.pinned_apps[idx].icon_requested
-                    {
-                        self.pinned_apps[idx].icon_requested = true;
-                        let _ = self.icon_req_tx.send(IconRequest {
-                            path: self.pinned_apps[idx].path.clone(),
-                            name_hint: Some(self.pinned_apps[idx].name.clone()),
-                            size: self.config.icon_size,
-                        });
-                    }
+                    let fade = pin_fade(&self.pinned_apps[idx]);
 
-                    if let Some(tex) = &self.pinned_apps[idx].texture {
-                        ui.painter().image(
-                            tex.id(),
-                            icon_rect,
-                            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                            egui::Color32::WHITE,
-                        );
+                    // Long names stop short of the favorite star.
+                    let star_room = if self.pinned_apps[idx].favorite || resp.hovered() {
+                        FAVORITE_STAR_WIDTH
                     } else {
-                        ui.painter()
-                            .rect_filled(icon_rect, 5.0, theme.icon_placeholder);
-                    }
-
-                    let text_pos = egui::pos2(icon_rect.max.x + 9.0, rect.center().y);
-                    ui.painter().text(
-                        text_pos,
+                        0.0
+                    };
+                    let text_rect = egui::Rect::from_min_max(
+                        egui::pos2(icon_rect.max.x + 9.0, rect.min.y + 2.0),
+                        egui::pos2(rect.max.x - 8.0 - star_room, rect.max.y - 2.0),
+                    );
+                    ui.painter().with_clip_rect(text_rect).text(
+                        egui::pos2(text_rect.min.x, rect.center().y),
                         egui::Align2::LEFT_CENTER,
                         &self.pinned_apps[idx].name,
                         egui::FontId::proportional(14.0),
-                        theme.title_color,
+                        theme.title_color.gamma_multiply(fade),
                     );
+                    self.draw_favorite_star(ui, idx, rect, resp.hovered(), theme);
 
-                    let resp = resp.on_hover_text(self.pinned_apps[idx].path.to_string_lossy());
-                    if self.dragging_app.is_none() {
-                        if resp.double_clicked() {
-                            let app = &self.pinned_apps[idx];
-                            let _ = crate::system::shell_open_with(
-                                &app.path,
-                                app.launch_args.as_deref(),
-                                app.working_dir.as_deref(),
-                            );
-                        } else if resp.clicked() {
-                            self.selected_app = Some(idx);
-                        }
+                    if resp.hovered() {
+                        self.note_hover(idx, rect);
                     }
+                    self.handle_row_activation(ctx, idx, &resp);
 
-                    resp.context_menu(|ui| {
-                        if ui.button("Remove").clicked() {
-                            remove_idx = Some(idx);
-                            ui.close_menu();
+                    let mut menu_action = None;
+                    resp.context_menu(|ui| menu_action = self.pin_context_menu(ui, idx));
+                    if let Some(action) = menu_action {
+                        if let Some(indices) = self.apply_pin_menu_action(ctx, idx, action) {
+                            to_remove = indices;
                         }
-                    });
+                    }
 
                     ui.add_space(5.0);
                     slot_index += 1;
@@ -917,20 +3022,47 @@ impl MyApp {
                         egui::vec2(list_width, ROW_HEIGHT),
                         egui::Sense::hover(),
                     );
-                    ui.painter()
-                        .rect_stroke(r, 12.0, egui::Stroke::new(1.0, theme.drop_hint));
+                    ui.painter().rect_stroke(r, 12.0, theme.drop_hint_stroke());
                 }
             });
+        self.list_scroll_offset = scroll_output.state.offset.y;
+        self.list_overflows = scroll_output.content_size.y > scroll_output.inner_rect.height();
+        if let Some((group, action)) = folder_action {
+            self.apply_folder_action(ctx, group, action);
+            return to_remove;
+        }
 
-        if drag_i.is_some() && pointer_pos.is_some() {
-            let py = pointer_pos.unwrap().y;
-            let mut target = rects_for_target.len();
-            for (pos, rect) in rects_for_target.iter().enumerate() {
-                if py < rect.center().y {
-                    target = pos;
-                    break;
-                }
+        if let (Some(_), Some(pointer)) = (drag_i, pointer_pos) {
+            // Rows scrolled out of view still report their layout rects, so only
+            // hit-test against the visible part of the list.
+            let view = scroll_output.inner_rect;
+            let py = pointer.y.clamp(view.top(), view.bottom());
+            // The middle of a row merges with it; its edges still mean "between".
+            let merge = merge_rows
+                .iter()
+                .find(|(row, _)| {
+                    view.contains(pointer)
+                        && (py - row.center().y).abs() < row.height() * MERGE_ZONE * 0.5
+                })
+                .map(|(_, idx)| *idx);
+            if self.merge_target != merge {
+                self.merge_target = merge;
+                ctx.request_repaint();
+            }
+            // Below an open folder's row and within its pins drops into it.
+            let drop_folder = folder_rows
+                .iter()
+                .find(|(group, span)| {
+                    self.config.profile.pin_groups[**group].expanded
+                        && py > span.min.y + ROW_HEIGHT * 0.5
+                        && py <= span.max.y
+                })
+                .map(|(group, _)| *group);
+            if self.drop_folder != drop_folder {
+                self.drop_folder = drop_folder;
+                ctx.request_repaint();
             }
+            let target = slot_from_pointer(py, &rects_for_target);
             if self.drag_target != Some(target) {
                 self.drag_target = Some(target);
                 ctx.request_repaint();
@@ -945,11 +3077,11 @@ impl MyApp {
             if !down {
                 self.press_candidate = None;
             } else if let Some(p) = cur {
-                if p.distance(start_pos) > REORDER_MOVE_TOLERANCE {
+                if p.distance(start_pos) > self.config.reorder_move_tolerance {
                     self.press_candidate = None;
-                } else if start.elapsed() >= Duration::from_millis(REORDER_HOLD_MS) {
+                } else if start.elapsed() >= Duration::from_millis(self.config.reorder_hold_ms) {
                     self.dragging_app = Some(idx);
-                    self.drag_target = Some(idx.min(self.pinned_apps.len()));
+                    self.drag_target = order.iter().position(|i| *i == idx);
                     self.press_candidate = None;
                     ctx.request_repaint();
                 }
@@ -960,25 +3092,45 @@ impl MyApp {
             && self.dragging_app.is_some()
             && ctx.input(|i| i.pointer.primary_released())
         {
-            if let (Some(from), Some(slot)) = (self.dragging_app.take(), self.drag_target.take()) {
+            if let (Some(from), Some(onto)) = (self.dragging_app, self.merge_target.take()) {
+                self.dragging_app = None;
+                self.drag_target = None;
+                self.merge_into_folder(from, onto);
+            } else if let (Some(from), Some(slot)) =
+                (self.dragging_app.take(), self.drag_target.take())
+            {
                 if from < self.pinned_apps.len() {
+                    // The slot counts in drawing order; in `pinned_apps` the pin
+                    // goes next to the one it landed beside in its group.
+                    let mut shown: Vec<usize> =
+                        order.iter().copied().filter(|idx| *idx != from).collect();
+                    shown.insert(slot.min(shown.len()), from);
+                    let stored: Vec<usize> = (0..self.pinned_apps.len()).collect();
+                    let placed = place_dragged_pin(&stored, &shown, from, &self.favorite_flags());
+                    let insert_at = placed.iter().position(|idx| *idx == from).unwrap_or(from);
+                    self.leave_folder(from);
+                    let entry = grid_entry_from_app(&self.pinned_apps[from]);
                     let start_y = ctx
                         .input(|i| i.pointer.hover_pos())
                         .map(|p| p.y - ROW_HEIGHT * 0.5)
-                        .unwrap_or(content_rect.min.y + CONTENT_PADDING);
+                        .unwrap_or(content_rect.min.y + theme.content_padding);
                     let end_y = if slot < rects_for_target.len() {
                         rects_for_target[slot].min.y
                     } else {
                         rects_for_target
                             .last()
                             .map(|r| r.max.y + 8.0)
-                            .unwrap_or(content_rect.min.y + CONTENT_PADDING)
+                            .unwrap_or(content_rect.min.y + theme.content_padding)
                     };
                     let item = self.pinned_apps.remove(from);
-                    let insert_at = slot.min(self.pinned_apps.len());
+                    let insert_at = insert_at.min(self.pinned_apps.len());
+                    if let Some(group) = self.drop_folder {
+                        self.join_folder(group, entry, insert_at);
+                    }
                     self.drop_anim = Some(DropAnim {
                         item,
                         insert_at,
+                        slot,
                         start: Instant::now(),
                         start_y,
                         end_y,
@@ -989,7 +3141,7 @@ impl MyApp {
         }
 
         self.draw_drag_row_overlay(ctx, content_rect, list_width, theme);
-        remove_idx
+        to_remove
     }
 
     fn draw_pinned_grid(
@@ -1000,20 +3152,20 @@ impl MyApp {
         theme: &LauncherTheme,
         is_dragging_file: bool,
         list_width: f32,
-    ) -> Option<usize> {
+    ) -> Vec<usize> {
         if self.pinned_apps.is_empty() {
             let empty_rect = egui::Rect::from_min_max(
                 egui::pos2(
-                    content_rect.min.x + CONTENT_PADDING,
-                    content_rect.min.y + CONTENT_PADDING,
+                    content_rect.min.x + theme.content_padding,
+                    content_rect.min.y + theme.content_padding,
                 ),
                 egui::pos2(
-                    content_rect.max.x - CONTENT_PADDING,
-                    content_rect.max.y - CONTENT_PADDING,
+                    content_rect.max.x - theme.content_padding,
+                    content_rect.max.y - theme.content_padding,
                 ),
             );
             ui.painter()
-                .rect_stroke(empty_rect, 12.0, egui::Stroke::new(1.0, theme.drop_hint));
+                .rect_stroke(empty_rect, 12.0, theme.drop_hint_stroke());
             ui.painter().text(
                 empty_rect.center_top() + egui::vec2(0.0, 42.0),
                 egui::Align2::CENTER_CENTER,
@@ -1028,63 +3180,92 @@ impl MyApp {
                     egui::Color32::from_rgba_premultiplied(75, 197, 165, 26),
                 );
             }
-            return None;
+            return Vec::new();
         }
 
-        let col_gap = 8.0;
-        let row_gap = 6.0;
-        let cell_width = ((list_width - col_gap).max(220.0)) * 0.5;
-        let column_left_x = content_rect.min.x + CONTENT_PADDING;
-        let column_right_x = column_left_x + cell_width + col_gap;
+        let col_gap = GRID_COL_GAP;
+        let row_gap = GRID_ROW_GAP;
+        let column_count = self.config.column_count();
+        let widths = grid_column_widths(
+            list_width,
+            col_gap,
+            &column_weights(&self.config.profile.column_weights, column_count),
+        );
+        let page_height = self
+            .config
+            .visible_grid_rows()
+            .map(|rows| grid_page_height(rows, row_gap));
+        let first_column_x = content_rect.min.x + theme.content_padding;
+        let column_xs: Vec<f32> = widths
+            .iter()
+            .scan(first_column_x, |x, width| {
+                let left = *x;
+                *x += width + col_gap;
+                Some(left)
+            })
+            .collect();
+        // Drop boundaries (and the dividers) sit in the middle of each gap.
+        let boundaries: Vec<f32> = (1..column_count)
+            .map(|col| column_xs[col] - col_gap * 0.5)
+            .collect();
 
-        let (left_indices, right_indices) =
-            resolve_two_column_indices(&self.pinned_apps, self.config.two_column_layout.as_ref());
+        let column_indices = self.render_columns();
 
         let dragging_idx = self
             .dragging_app
             .filter(|idx| *idx < self.pinned_apps.len());
-        let mut left_draw = left_indices.clone();
-        let mut right_draw = right_indices.clone();
+        let mut column_draw = column_indices.clone();
         if let Some(drag_idx) = dragging_idx {
-            if let Some(pos) = left_draw.iter().position(|&idx| idx == drag_idx) {
-                left_draw.remove(pos);
-            } else if let Some(pos) = right_draw.iter().position(|&idx| idx == drag_idx) {
-                right_draw.remove(pos);
+            for column in &mut column_draw {
+                column.retain(|&idx| idx != drag_idx);
             }
         }
 
-        let mut remove_idx = None;
-        let mut left_rects: Vec<egui::Rect> = Vec::new();
-        let mut right_rects: Vec<egui::Rect> = Vec::new();
+        let mut to_remove = Vec::new();
+        let mut column_rects: Vec<Vec<egui::Rect>> = vec![Vec::new(); column_count];
+        // First cell of each column with nothing to draw, so a drag can still aim there.
+        let mut empty_column_rects: Vec<Option<egui::Rect>> = vec![None; column_count];
 
-        egui::ScrollArea::vertical()
-            .max_height(content_rect.height() - CONTENT_PADDING * 2.0)
+        let scroll_output = self
+            .reorder_scroll_area(
+                ctx,
+                content_rect,
+                page_height,
+                dragging_idx.is_some(),
+                theme,
+            )
             .show(ui, |ui| {
-                let row_count = left_draw.len().max(right_draw.len());
+                let mut row_count = column_draw.iter().map(Vec::len).max().unwrap_or(0);
+                if dragging_idx.is_some() {
+                    // Dragging the only pin still lays out a row of empty drop cells.
+                    row_count = row_count.max(1);
+                }
                 for row in 0..row_count {
                     ui.horizontal(|ui| {
                         ui.spacing_mut().item_spacing.x = col_gap;
-                        for col in 0..2 {
-                            let app_idx = if col == 0 {
-                                left_draw.get(row).copied()
-                            } else {
-                                right_draw.get(row).copied()
-                            };
+                        for col in 0..column_count {
+                            let app_idx = column_draw[col].get(row).copied();
 
                             let (rect, resp) = ui.allocate_exact_size(
-                                egui::vec2(cell_width, ROW_HEIGHT),
+                                egui::vec2(widths[col], ROW_HEIGHT),
                                 egui::Sense::click_and_drag(),
                             );
 
                             let Some(idx) = app_idx else {
+                                if row == 0 && column_draw[col].is_empty() {
+                                    empty_column_rects[col] = Some(rect);
+                                    if dragging_idx.is_some() {
+                                        ui.painter().rect_stroke(
+                                            rect,
+                                            8.0,
+                                            egui::Stroke::new(1.0, theme.row_border),
+                                        );
+                                    }
+                                }
                                 continue;
                             };
 
-                            if col == 0 {
-                                left_rects.push(rect);
-                            } else {
-                                right_rects.push(rect);
-                            }
+                            column_rects[col].push(rect);
 
                             if resp.is_pointer_button_down_on()
                                 && self.drop_anim.is_none()
@@ -1096,7 +3277,7 @@ impl MyApp {
                                 }
                             }
 
-                            let is_selected = self.selected_app == Some(idx);
+                            let is_selected = self.is_selected(idx);
                             let fill = if is_selected {
                                 theme.row_selected
                             } else if resp.hovered() {
@@ -1118,17 +3299,10 @@ impl MyApp {
                                 egui::vec2(ICON_SIDE, ICON_SIDE),
                             );
 
-                            if self.pinned_apps[idx].texture.is_none()
-                                && !self.pinned_apps[idx].icon_requested
-                            {
-                                self.pinned_apps[idx].icon_requested = true;
-                                let _ = self.icon_req_tx.send(IconRequest {
-                                    path: self.pinned_apps[idx].path.clone(),
-                                    name_hint: Some(self.pinned_apps[idx].name.clone()),
-                                    size: self.config.icon_size,
-                                });
-                            }
+                            self.request_icon_if_needed(idx);
+                            let icon_rect = self.paint_launch_bounce(ui, idx, icon_rect, theme);
 
+                            let fade = pin_fade(&self.pinned_apps[idx]);
                             if let Some(tex) = &self.pinned_apps[idx].texture {
                                 ui.painter().image(
                                     tex.id(),
@@ -1137,16 +3311,23 @@ impl MyApp {
                                         egui::pos2(0.0, 0.0),
                                         egui::pos2(1.0, 1.0),
                                     ),
-                                    egui::Color32::WHITE,
+                                    egui::Color32::WHITE.gamma_multiply(fade),
                                 );
                             } else {
-                                ui.painter()
-                                    .rect_filled(icon_rect, 5.0, theme.icon_placeholder);
+                                self.paint_icon_pending(ui, idx, icon_rect, theme);
+                            }
+                            if self.pinned_apps[idx].unavailable {
+                                paint_unavailable_badge(ui.painter(), icon_rect, theme);
                             }
 
+                            let star_room = if self.pinned_apps[idx].favorite || resp.hovered() {
+                                FAVORITE_STAR_WIDTH
+                            } else {
+                                0.0
+                            };
                             let text_rect = egui::Rect::from_min_max(
                                 egui::pos2(icon_rect.max.x + 8.0, rect.min.y + 2.0),
-                                egui::pos2(rect.max.x - 8.0, rect.max.y - 2.0),
+                                egui::pos2(rect.max.x - 8.0 - star_room, rect.max.y - 2.0),
                             );
                             let text_painter = ui.painter().with_clip_rect(text_rect);
                             text_painter.text(
@@ -1154,30 +3335,23 @@ impl MyApp {
                                 egui::Align2::LEFT_CENTER,
                                 &self.pinned_apps[idx].name,
                                 egui::FontId::proportional(14.0),
-                                theme.title_color,
+                                theme.title_color.gamma_multiply(fade),
                             );
+                            self.draw_favorite_star(ui, idx, rect, resp.hovered(), theme);
 
-                            let resp =
-                                resp.on_hover_text(self.pinned_apps[idx].path.to_string_lossy());
-                            if self.dragging_app.is_none() {
-                                if resp.double_clicked() {
-                                    let app = &self.pinned_apps[idx];
-                                    let _ = crate::system::shell_open_with(
-                                        &app.path,
-                                        app.launch_args.as_deref(),
-                                        app.working_dir.as_deref(),
-                                    );
-                                } else if resp.clicked() {
-                                    self.selected_app = Some(idx);
-                                }
+                            if resp.hovered() {
+                                self.note_hover(idx, rect);
                             }
-
-                            resp.context_menu(|ui| {
-                                if ui.button("Remove").clicked() {
-                                    remove_idx = Some(idx);
-                                    ui.close_menu();
+                            self.handle_row_activation(ctx, idx, &resp);
+
+                            let mut menu_action = None;
+                            resp.context_menu(|ui| menu_action = self.pin_context_menu(ui, idx));
+                            if let Some(action) = menu_action {
+                                if let Some(indices) = self.apply_pin_menu_action(ctx, idx, action)
+                                {
+                                    to_remove = indices;
                                 }
-                            });
+                            }
                         }
                     });
 
@@ -1186,6 +3360,13 @@ impl MyApp {
                     }
                 }
             });
+        self.list_scroll_offset = scroll_output.state.offset.y;
+        self.list_overflows = scroll_output.content_size.y > scroll_output.inner_rect.height();
+
+        if dragging_idx.is_none() {
+            let total: f32 = widths.iter().sum();
+            self.draw_column_dividers(ui, &boundaries, scroll_output.inner_rect, total, theme);
+        }
 
         if let Some((idx, start, start_pos)) = self.press_candidate {
             ctx.request_repaint_after(Duration::from_millis(16));
@@ -1194,12 +3375,12 @@ impl MyApp {
             if !down {
                 self.press_candidate = None;
             } else if let Some(p) = cur {
-                if p.distance(start_pos) > REORDER_MOVE_TOLERANCE {
+                if p.distance(start_pos) > self.config.reorder_move_tolerance {
                     self.press_candidate = None;
-                } else if start.elapsed() >= Duration::from_millis(REORDER_HOLD_MS) {
+                } else if start.elapsed() >= Duration::from_millis(self.config.reorder_hold_ms) {
                     self.dragging_app = Some(idx);
                     self.drag_target = None;
-                    self.grid_drag_target = find_column_slot(idx, &left_indices, &right_indices);
+                    self.grid_drag_target = find_column_slot(idx, &column_indices);
                     self.press_candidate = None;
                     ctx.request_repaint();
                 }
@@ -1209,17 +3390,17 @@ impl MyApp {
         if let (Some(_drag_idx), Some(pointer_pos)) =
             (dragging_idx, ctx.input(|i| i.pointer.hover_pos()))
         {
-            let target_col = if pointer_pos.x < column_right_x { 0 } else { 1 };
-            let target_rects = if target_col == 0 {
-                &left_rects
-            } else {
-                &right_rects
-            };
-            let max_slot = if target_col == 0 {
-                left_draw.len()
+            // Stay in the current column until the pointer is clearly past the gap,
+            // so the placeholder doesn't flicker while hovering the boundary.
+            let current_col = self.grid_drag_target.map(|(col, _)| col).unwrap_or(0);
+            let target_col = pick_drag_column(pointer_pos.x, &boundaries, current_col);
+            let empty_rect: Vec<egui::Rect> = empty_column_rects[target_col].into_iter().collect();
+            let target_rects = if column_rects[target_col].is_empty() {
+                &empty_rect
             } else {
-                right_draw.len()
+                &column_rects[target_col]
             };
+            let max_slot = column_draw[target_col].len();
             let target_slot = slot_from_pointer(pointer_pos.y, target_rects).min(max_slot);
             let target = Some((target_col, target_slot));
             if self.grid_drag_target != target {
@@ -1230,49 +3411,43 @@ impl MyApp {
 
         if self.dragging_app.is_some() && ctx.input(|i| i.pointer.primary_released()) {
             if let Some(from_idx) = self.dragging_app.take() {
-                if let Some((from_col, from_slot)) =
-                    find_column_slot(from_idx, &left_indices, &right_indices)
-                {
-                    let mut left_new = left_indices.clone();
-                    let mut right_new = right_indices.clone();
-
-                    if from_col == 0 {
-                        left_new.remove(from_slot);
-                    } else {
-                        right_new.remove(from_slot);
-                    }
+                if let Some((from_col, from_slot)) = find_column_slot(from_idx, &column_indices) {
+                    let mut columns_new = column_indices.clone();
+                    columns_new[from_col].remove(from_slot);
 
                     let (target_col, target_slot) = self
                         .grid_drag_target
                         .take()
                         .unwrap_or((from_col, from_slot));
-                    let insert_vec = if target_col == 0 {
-                        &mut left_new
-                    } else {
-                        &mut right_new
-                    };
-                    let insert_slot = target_slot.min(insert_vec.len());
-                    insert_vec.insert(insert_slot, from_idx);
-
-                    if left_new != left_indices || right_new != right_indices {
-                        reorder_pinned_apps_by_columns(
-                            &mut self.pinned_apps,
-                            &left_new,
-                            &right_new,
-                        );
-                        self.config.two_column_layout = Some(two_column_layout_from_split(
+                    let target_col = target_col.min(column_count - 1);
+                    let insert_slot = target_slot.min(columns_new[target_col].len());
+                    columns_new[target_col].insert(insert_slot, from_idx);
+
+                    if columns_new != column_indices {
+                        // The columns as drawn put favorites first; the saved
+                        // order only moves the dragged pin.
+                        let stored = resolve_column_indices(
                             &self.pinned_apps,
-                            left_new.len(),
-                        ));
+                            self.config.profile.grid_layout.as_ref(),
+                            column_count,
+                        )
+                        .concat();
+                        let placed = place_dragged_pin(
+                            &stored,
+                            &columns_new.concat(),
+                            from_idx,
+                            &self.favorite_flags(),
+                        );
+                        let selected_idx = placed.iter().position(|idx| *idx == from_idx);
+                        reorder_pinned_apps_by_columns(&mut self.pinned_apps, &[placed]);
+                        let lengths = column_lengths(&columns_new);
+                        self.config.profile.grid_layout =
+                            Some(grid_layout_from_lengths(&self.pinned_apps, &lengths));
+
+                        if let Some(idx) = selected_idx {
+                            self.select_only(idx);
+                        }
                         self.sync_config_pins();
-
-                        let selected_idx = if target_col == 0 {
-                            insert_slot
-                        } else {
-                            left_new.len() + insert_slot
-                        };
-                        self.selected_app =
-                            Some(selected_idx.min(self.pinned_apps.len().saturating_sub(1)));
                     }
                 }
             }
@@ -1284,38 +3459,41 @@ impl MyApp {
 
         if let Some((target_col, target_slot)) = self.grid_drag_target {
             if dragging_idx.is_some() {
-                let target_rects = if target_col == 0 {
-                    &left_rects
-                } else {
-                    &right_rects
-                };
-                let x = if target_col == 0 {
-                    column_left_x
-                } else {
-                    column_right_x
-                };
+                let target_rects = &column_rects[target_col];
+                let x = column_xs[target_col];
+                // An empty column lines its placeholder up with the first row of the grid.
+                let first_row_y = column_rects
+                    .iter()
+                    .filter_map(|rects| rects.first())
+                    .map(|r| r.min.y)
+                    .reduce(f32::min);
                 let y = if target_slot < target_rects.len() {
                     target_rects[target_slot].min.y
                 } else {
                     target_rects
                         .last()
                         .map(|r| r.max.y + row_gap)
-                        .unwrap_or(content_rect.min.y + CONTENT_PADDING)
+                        .or(empty_column_rects[target_col].map(|r| r.min.y))
+                        .or(first_row_y)
+                        .unwrap_or(content_rect.min.y + theme.content_padding)
                 };
 
-                let placeholder =
-                    egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(cell_width, ROW_HEIGHT));
+                let placeholder = egui::Rect::from_min_size(
+                    egui::pos2(x, y),
+                    egui::vec2(widths[target_col], ROW_HEIGHT),
+                );
                 let painter = ctx.layer_painter(egui::LayerId::new(
                     egui::Order::Foreground,
                     egui::Id::new("grid_drop_placeholder"),
                 ));
-                painter.rect_stroke(placeholder, 8.0, egui::Stroke::new(1.0, theme.drop_hint));
+                painter.rect_stroke(placeholder, 8.0, theme.drop_hint_stroke());
             }
         }
 
         if let (Some(drag_idx), Some(pointer_pos)) =
             (dragging_idx, ctx.input(|i| i.pointer.hover_pos()))
         {
+            let cell_width = widths.iter().sum::<f32>() / column_count as f32;
             let ghost_rect = egui::Rect::from_min_size(
                 egui::pos2(
                     pointer_pos.x - cell_width * 0.5,
@@ -1333,7 +3511,7 @@ impl MyApp {
                 egui::Color32::from_rgba_premultiplied(0, 0, 0, 32),
             );
             painter.rect_filled(ghost_rect, 8.0, theme.row_selected);
-            painter.rect_stroke(ghost_rect, 8.0, egui::Stroke::new(1.0, theme.drop_hint));
+            painter.rect_stroke(ghost_rect, 8.0, theme.drop_hint_stroke());
 
             let icon_rect = egui::Rect::from_center_size(
                 egui::pos2(
@@ -1362,7 +3540,87 @@ impl MyApp {
             ctx.request_repaint();
         }
 
-        remove_idx
+        to_remove
+    }
+
+    /// Draggable dividers between grid columns; dragging moves width between
+    /// the two neighbouring columns and saves the weights on release.
+    fn draw_column_dividers(
+        &mut self,
+        ui: &egui::Ui,
+        boundaries: &[f32],
+        view: egui::Rect,
+        total_width: f32,
+        theme: &LauncherTheme,
+    ) {
+        let column_count = boundaries.len() + 1;
+        for (divider, &x) in boundaries.iter().enumerate() {
+            let rect = egui::Rect::from_min_max(
+                egui::pos2(x - 3.0, view.top()),
+                egui::pos2(x + 3.0, view.bottom()),
+            );
+            let resp = ui.interact(
+                rect,
+                egui::Id::new(("grid_column_divider", divider)),
+                egui::Sense::drag(),
+            );
+            if resp.hovered() || resp.dragged() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                ui.painter()
+                    .vline(x, view.y_range(), egui::Stroke::new(2.0, theme.row_border));
+            }
+            if resp.dragged() && resp.drag_delta().x != 0.0 && total_width > 0.0 {
+                let mut weights = column_weights(&self.config.profile.column_weights, column_count);
+                shift_column_weight(&mut weights, divider, resp.drag_delta().x / total_width);
+                self.config.profile.column_weights = weights;
+            }
+            if resp.drag_stopped() {
+                self.config.save();
+            }
+        }
+    }
+
+    /// Builds the list scroll area, nudging its offset while a reorder drag
+    /// hovers near the top or bottom edge so long lists can be traversed.
+    fn reorder_scroll_area(
+        &mut self,
+        ctx: &egui::Context,
+        content_rect: egui::Rect,
+        page_height: Option<f32>,
+        dragging: bool,
+        theme: &LauncherTheme,
+    ) -> egui::ScrollArea {
+        let mut max_height = content_rect.height() - theme.content_padding * 2.0;
+        if let Some(page_height) = page_height {
+            max_height = max_height.min(page_height);
+        }
+        let mut area = egui::ScrollArea::vertical().max_height(max_height);
+        if std::mem::take(&mut self.restore_scroll) {
+            // The scroll area clamps this against the current content height,
+            // so pins removed since it was saved can't leave it past the end.
+            area = area.vertical_scroll_offset(self.list_scroll_offset);
+        }
+        if !dragging || self.drop_anim.is_some() {
+            return area;
+        }
+        let Some(pointer) = ctx.input(|i| i.pointer.hover_pos()) else {
+            return area;
+        };
+
+        let view = egui::Rect::from_min_size(
+            egui::pos2(
+                content_rect.min.x,
+                content_rect.min.y + theme.content_padding,
+            ),
+            egui::vec2(content_rect.width(), max_height.max(0.0)),
+        );
+        let step = drag_scroll_step(pointer.y, view);
+        if step == 0.0 {
+            return area;
+        }
+        self.list_scroll_offset = (self.list_scroll_offset + step).max(0.0);
+        ctx.request_repaint();
+        area.vertical_scroll_offset(self.list_scroll_offset)
     }
 
     fn draw_drag_row_overlay(
@@ -1374,10 +3632,10 @@ impl MyApp {
     ) {
         if let Some(anim) = &self.drop_anim {
             let elapsed = anim.start.elapsed();
-            let duration = Duration::from_millis(200);
+            let duration = self.config.animation.drop();
             let t = (elapsed.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
-            let eased = ease_out_elastic(t);
-            let list_left = content_rect.min.x + CONTENT_PADDING;
+            let eased = self.motion_ease(t);
+            let list_left = content_rect.min.x + theme.content_padding;
             let list_right = list_left + list_width;
             let y = anim.start_y + (anim.end_y - anim.start_y) * eased;
             let r = egui::Rect::from_min_max(
@@ -1394,7 +3652,7 @@ impl MyApp {
                 egui::Color32::from_rgba_premultiplied(0, 0, 0, 32),
             );
             painter.rect_filled(r, 8.0, theme.row_selected);
-            painter.rect_stroke(r, 8.0, egui::Stroke::new(1.0, theme.drop_hint));
+            painter.rect_stroke(r, 8.0, theme.drop_hint_stroke());
 
             let icon_rect = egui::Rect::from_center_size(
                 egui::pos2(r.min.x + 14.0 + ICON_SIDE * 0.5, r.center().y),
@@ -1421,7 +3679,7 @@ impl MyApp {
         } else if let (Some(from), Some(pos)) =
             (self.dragging_app, ctx.input(|i| i.pointer.hover_pos()))
         {
-            let list_left = content_rect.min.x + CONTENT_PADDING;
+            let list_left = content_rect.min.x + theme.content_padding;
             let list_right = list_left + list_width;
             let y = pos.y - ROW_HEIGHT * 0.5;
             let r = egui::Rect::from_min_max(
@@ -1438,119 +3696,1175 @@ impl MyApp {
                 egui::Color32::from_rgba_premultiplied(0, 0, 0, 32),
             );
             painter.rect_filled(r, 8.0, theme.row_selected);
-            painter.rect_stroke(r, 8.0, egui::Stroke::new(1.0, theme.drop_hint));
+            painter.rect_stroke(r, 8.0, theme.drop_hint_stroke());
+
+            let icon_rect = egui::Rect::from_center_size(
+                egui::pos2(r.min.x + 14.0 + ICON_SIDE * 0.5, r.center().y),
+                egui::vec2(ICON_SIDE, ICON_SIDE),
+            );
+            if let Some(tex) = &self.pinned_apps[from].texture {
+                painter.image(
+                    tex.id(),
+                    icon_rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                );
+            } else {
+                painter.rect_filled(icon_rect, 5.0, theme.icon_placeholder);
+            }
+            painter.text(
+                egui::pos2(icon_rect.max.x + 9.0, r.center().y),
+                egui::Align2::LEFT_CENTER,
+                &self.pinned_apps[from].name,
+                egui::FontId::proportional(14.0),
+                theme.title_color,
+            );
+            ctx.request_repaint();
+        }
+    }
+
+    fn draw_flash_overlay(&mut self, ui: &egui::Ui) {
+        if let Some(start_time) = self.flash_start_time {
+            let elapsed = start_time.elapsed();
+            let duration = self.config.animation.flash();
+            if elapsed < duration {
+                let progress = elapsed.as_secs_f32() / duration.as_secs_f32();
+                let alpha = (1.0 - progress) * 0.35;
+                let color = egui::Color32::from_white_alpha((alpha * 255.0) as u8);
+                ui.painter().rect_filled(ui.clip_rect(), 0.0, color);
+                ui.ctx().request_repaint();
+            } else {
+                self.flash_start_time = None;
+            }
+        }
+    }
+
+    /// Mouse wheel over a collapsed dock (or a list that doesn't scroll) steps
+    /// the selection; Enter then launches it (see `handle_launch_key`).
+    fn handle_wheel_cycle(&mut self, ctx: &egui::Context) {
+        if self.pinned_apps.is_empty()
+            || self.dragging_app.is_some()
+            || (!self.config.collapsed && self.list_overflows)
+        {
+            self.wheel_accum = 0.0;
+            return;
+        }
+
+        let (delta, hovered) =
+            ctx.input(|i| (i.raw_scroll_delta.y, i.pointer.hover_pos().is_some()));
+        if hovered && delta != 0.0 {
+            self.wheel_accum += delta;
+            let steps = (self.wheel_accum / WHEEL_CYCLE_STEP).trunc();
+            if steps != 0.0 {
+                self.wheel_accum -= steps * WHEEL_CYCLE_STEP;
+                // Wheel up (positive delta) moves towards the top of the list.
+                let target =
+                    wheel_cycle_target(self.selected_app, -(steps as i32), self.pinned_apps.len());
+                self.select_only(target);
+                self.wheel_preview = Some(Instant::now());
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    fn draw_wheel_preview(&mut self, ui: &egui::Ui, theme: &LauncherTheme) {
+        let Some(start) = self.wheel_preview else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        let duration = Duration::from_millis(WHEEL_PREVIEW_MS);
+        let Some(idx) = self
+            .selected_app
+            .filter(|idx| *idx < self.pinned_apps.len() && elapsed < duration)
+        else {
+            self.wheel_preview = None;
+            return;
+        };
+        let app = &self.pinned_apps[idx];
+
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("wheel_preview"),
+        ));
+        let clip = ui.clip_rect();
+        let icon_side = ICON_SIDE * 2.4;
+        let galley = painter.layout(
+            app.name.clone(),
+            egui::FontId::proportional(16.0),
+            theme.toast_text,
+            (clip.width() - 40.0).max(60.0),
+        );
+        let size = egui::vec2(
+            galley.size().x.max(icon_side) + 24.0,
+            icon_side + 8.0 + galley.size().y + 20.0,
+        );
+        let card = egui::Rect::from_min_size(
+            egui::pos2(
+                clip.center().x - size.x * 0.5,
+                clip.min.y + HEADER_HEIGHT + 8.0,
+            ),
+            size,
+        );
+        painter.rect_filled(card, 12.0, theme.toast_bg);
+        painter.rect_stroke(card, 12.0, egui::Stroke::new(1.0, theme.row_border));
+
+        let icon_rect = egui::Rect::from_center_size(
+            egui::pos2(card.center().x, card.min.y + 10.0 + icon_side * 0.5),
+            egui::vec2(icon_side, icon_side),
+        );
+        if let Some(tex) = &app.texture {
+            painter.image(
+                tex.id(),
+                icon_rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+        } else {
+            painter.rect_filled(icon_rect, 8.0, theme.icon_placeholder);
+        }
+        let text_pos = egui::pos2(
+            card.center().x - galley.size().x * 0.5,
+            icon_rect.max.y + 8.0,
+        );
+        painter.galley(text_pos, galley, theme.toast_text);
+        ui.ctx().request_repaint_after(duration - elapsed);
+    }
+
+    /// Asks the icon worker for a pin's icon once, plus the one retry after a failure.
+    fn request_icon_if_needed(&mut self, idx: usize) {
+        let app = &mut self.pinned_apps[idx];
+        if app.texture.is_some() {
+            return;
+        }
+        let retry_due = app.icon_retry_at.is_some_and(|at| Instant::now() >= at);
+        if app.icon_requested && !retry_due {
+            return;
+        }
+        if retry_due {
+            app.icon_retry_at = None;
+            app.icon_retried = true;
+            app.icon_failed = false;
+        }
+        self.send_icon_request(idx);
+    }
+
+    /// Empties the on-disk icon cache and has every pin extract its icon again.
+    fn clear_icon_cache(&mut self) {
+        let removed = crate::icons::clear_icon_cache();
+        for app in &mut self.pinned_apps {
+            app.texture = None;
+            app.icon_requested = false;
+            app.icon_failed = false;
+            app.icon_retry_at = None;
+            app.icon_retried = false;
+        }
+        self.show_toast(Severity::Info, format!("Cleared {removed} cached icons"));
+    }
+
+    /// Puts a pin that was set aside on load back in the dock, once its
+    /// target is there again.
+    fn restore_broken_pin(&mut self, idx: usize) {
+        let Some(meta) = self.config.profile.broken_pins.get(idx) else {
+            return;
+        };
+        if !meta.path.exists() {
+            let message = format!("{} is still missing", broken_pin_name(meta));
+            self.show_toast(Severity::Warning, message);
+            return;
+        }
+        let meta = self.config.profile.broken_pins.remove(idx);
+        let app = pinned_app_from_meta(meta.path.clone(), Some(&meta));
+        let key = pin_launch_key(&app);
+        if !self
+            .pinned_apps
+            .iter()
+            .any(|pin| pin_launch_key(pin) == key)
+        {
+            if self.pinned_apps.len() >= self.config.pin_limit() {
+                self.config.profile.broken_pins.insert(idx, meta);
+                let limit = self.config.pin_limit();
+                self.show_toast(Severity::Warning, format!("Max {} apps", limit));
+                return;
+            }
+            self.pinned_apps.push(app);
+            self.sync_grid_layout_from_current();
+        }
+        self.sync_config_pins();
+    }
+
+    fn open_config_folder(&mut self) {
+        let opened = crate::config::AppConfig::config_dir().is_some_and(|dir| {
+            std::fs::create_dir_all(&dir).is_ok() && crate::system::shell_open(&dir)
+        });
+        if !opened {
+            self.show_toast(Severity::Warning, "Couldn't open the config folder");
+        }
+    }
+
+    fn open_log_folder(&mut self) {
+        let opened = crate::logging::log_dir().is_some_and(|dir| {
+            std::fs::create_dir_all(&dir).is_ok() && crate::system::shell_open(&dir)
+        });
+        if !opened {
+            self.show_toast(Severity::Warning, "Couldn't open the log folder");
+        }
+    }
+
+    /// Drops the pin's cached icon and extracts it again. The old texture
+    /// stays up until the new one arrives.
+    fn refresh_icon(&mut self, idx: usize) {
+        let app = &mut self.pinned_apps[idx];
+        crate::icons::remove_cached_icon(&app.path);
+        app.icon_failed = false;
+        app.icon_retry_at = None;
+        app.icon_retried = false;
+        self.send_icon_request(idx);
+    }
+
+    fn send_icon_request(&mut self, idx: usize) {
+        let app = &mut self.pinned_apps[idx];
+        app.icon_requested = true;
+        let _ = self.icon_req_tx.send(IconRequest {
+            path: app.path.clone(),
+            name_hint: Some(app.name.clone()),
+            size: self.config.profile.icon_size,
+            command: app.command,
+        });
+    }
+
+    /// Paints a pin's row background and icon, as the list and the search
+    /// results show them, and returns where the icon went; the caller adds
+    /// the name. `merge_hint` marks the row a dragged pin would merge with.
+    fn paint_pin_row(
+        &mut self,
+        ui: &egui::Ui,
+        idx: usize,
+        rect: egui::Rect,
+        hovered: bool,
+        merge_hint: bool,
+        theme: &LauncherTheme,
+    ) -> egui::Rect {
+        let is_selected = self.is_selected(idx);
+        let fill = if is_selected {
+            theme.row_selected
+        } else if hovered {
+            theme.row_hover
+        } else {
+            theme.row_bg
+        };
+        ui.painter().rect_filled(rect, 8.0, fill);
+        if merge_hint {
+            ui.painter()
+                .rect_filled(rect, 8.0, theme.drop_hint.gamma_multiply(0.25));
+            ui.painter()
+                .rect_stroke(rect, 8.0, theme.drop_hint_stroke());
+        } else if is_selected || hovered {
+            ui.painter()
+                .rect_stroke(rect, 8.0, egui::Stroke::new(1.0, theme.row_border));
+        }
 
-            let icon_rect = egui::Rect::from_center_size(
-                egui::pos2(r.min.x + 14.0 + ICON_SIDE * 0.5, r.center().y),
-                egui::vec2(ICON_SIDE, ICON_SIDE),
+        let icon_rect = egui::Rect::from_center_size(
+            egui::pos2(rect.min.x + 14.0 + ICON_SIDE * 0.5, rect.center().y),
+            egui::vec2(ICON_SIDE, ICON_SIDE),
+        );
+        self.request_icon_if_needed(idx);
+        let icon_rect = self.paint_launch_bounce(ui, idx, icon_rect, theme);
+        let fade = pin_fade(&self.pinned_apps[idx]);
+        if let Some(tex) = &self.pinned_apps[idx].texture {
+            ui.painter().image(
+                tex.id(),
+                icon_rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE.gamma_multiply(fade),
             );
-            if let Some(tex) = &self.pinned_apps[from].texture {
-                painter.image(
-                    tex.id(),
-                    icon_rect,
-                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                    egui::Color32::WHITE,
-                );
-            } else {
-                painter.rect_filled(icon_rect, 5.0, theme.icon_placeholder);
+        } else {
+            self.paint_icon_pending(ui, idx, icon_rect, theme);
+        }
+        if self.pinned_apps[idx].unavailable {
+            paint_unavailable_badge(ui.painter(), icon_rect, theme);
+        }
+        icon_rect
+    }
+
+    /// Paints the glow of a running launch bounce behind the pin's icon and
+    /// returns the icon rect grown to match; other rows get it back as is.
+    fn paint_launch_bounce(
+        &self,
+        ui: &egui::Ui,
+        idx: usize,
+        icon_rect: egui::Rect,
+        theme: &LauncherTheme,
+    ) -> egui::Rect {
+        let Some((bounce_idx, start)) = self.launch_bounce else {
+            return icon_rect;
+        };
+        let t = start.elapsed().as_secs_f32() / self.config.animation.launch().as_secs_f32();
+        if bounce_idx != idx || t >= 1.0 {
+            return icon_rect;
+        }
+        let lift = (t * std::f32::consts::PI).sin();
+        ui.painter().rect_filled(
+            icon_rect.expand(5.0 * lift),
+            6.0,
+            theme.drop_hint.gamma_multiply(0.45 * lift),
+        );
+        ui.ctx().request_repaint();
+        egui::Rect::from_center_size(icon_rect.center(), icon_rect.size() * (1.0 + 0.3 * lift))
+    }
+
+    /// Spinner while the worker is still extracting a pin's icon; the flat
+    /// placeholder once it has reported no icon.
+    fn paint_icon_pending(
+        &self,
+        ui: &egui::Ui,
+        idx: usize,
+        icon_rect: egui::Rect,
+        theme: &LauncherTheme,
+    ) {
+        let app = &self.pinned_apps[idx];
+        if app.icon_failed || !app.icon_requested {
+            ui.painter()
+                .rect_filled(icon_rect, 5.0, theme.icon_placeholder);
+            return;
+        }
+        let animate = !self.reduce_motion();
+        let time = if animate { ui.input(|i| i.time) } else { 0.0 };
+        paint_spinner(ui.painter(), icon_rect, theme.icon_placeholder, time);
+        if animate {
+            ui.ctx().request_repaint();
+        }
+    }
+
+    fn note_hover(&mut self, idx: usize, anchor: egui::Rect) {
+        match &mut self.hover_card {
+            Some(card) if card.index == idx => {
+                card.anchor = anchor;
+                card.seen = true;
+            }
+            _ => {
+                self.hover_card = Some(HoverCard {
+                    index: idx,
+                    since: Instant::now(),
+                    anchor,
+                    seen: true,
+                });
             }
-            painter.text(
-                egui::pos2(icon_rect.max.x + 9.0, r.center().y),
-                egui::Align2::LEFT_CENTER,
-                &self.pinned_apps[from].name,
-                egui::FontId::proportional(14.0),
-                theme.title_color,
-            );
-            ctx.request_repaint();
         }
     }
 
-    fn draw_flash_overlay(&mut self, ui: &egui::Ui) {
-        if let Some(start_time) = self.flash_start_time {
-            let elapsed = start_time.elapsed();
-            let duration = Duration::from_millis(160);
-            if elapsed < duration {
-                let progress = elapsed.as_secs_f32() / duration.as_secs_f32();
-                let alpha = (1.0 - progress) * 0.35;
-                let color = egui::Color32::from_white_alpha((alpha * 255.0) as u8);
-                ui.painter().rect_filled(ui.clip_rect(), 0.0, color);
-                ui.ctx().request_repaint();
+    /// Details card for the hovered pin, painted once the hover delay has passed.
+    fn draw_hover_card(&mut self, ui: &egui::Ui, theme: &LauncherTheme) {
+        let Some(card) = &mut self.hover_card else {
+            return;
+        };
+        if !std::mem::take(&mut card.seen)
+            || card.index >= self.pinned_apps.len()
+            || self.dragging_app.is_some()
+            || self.press_candidate.is_some()
+        {
+            self.hover_card = None;
+            return;
+        }
+        let delay = Duration::from_millis(self.config.hover_card_delay_ms);
+        let elapsed = card.since.elapsed();
+        if elapsed < delay {
+            ui.ctx().request_repaint_after(delay - elapsed);
+            return;
+        }
+        let anchor = card.anchor;
+        let app = &self.pinned_apps[card.index];
+
+        let mut details = match &app.shortcut {
+            _ if app.command => vec![format!("Command: {}", app.path.display())],
+            Some(shortcut) => vec![
+                format!("Target: {}", app.path.display()),
+                format!("Shortcut: {}", shortcut.display()),
+            ],
+            None => vec![app.path.display().to_string()],
+        };
+        if let Some(args) = &app.launch_args {
+            details.push(format!("Args: {}", args));
+        }
+        if let Some(dir) = &app.working_dir {
+            details.push(format!("Start in: {}", dir.display()));
+        }
+        if !app.env.is_empty() {
+            let names: Vec<&str> = app.env.iter().map(|(name, _)| name.as_str()).collect();
+            details.push(format!("Env: {}", names.join(", ")));
+        }
+        match app.show_state {
+            ShowState::Normal => {}
+            ShowState::Minimized => details.push("Starts minimized".to_string()),
+            ShowState::Maximized => details.push("Starts maximized".to_string()),
+        }
+        if !app.command {
+            details.push(if app.unavailable {
+                "Target unavailable".to_string()
+            } else if app.path.exists() {
+                "Target found".to_string()
             } else {
-                self.flash_start_time = None;
-            }
+                "Target missing".to_string()
+            });
+        }
+        let is_quick_launch = self
+            .config
+            .profile
+            .quick_launch_app
+            .as_deref()
+            .is_some_and(|quick| same_launch_path(quick, &app.path));
+        if is_quick_launch {
+            details.push("Quick launch: double-click the dock".to_string());
+        }
+        details.push("Middle-click: new instance · Shift-click: run as admin".to_string());
+
+        let bounds = ui.clip_rect().shrink(6.0);
+        let wrap = (bounds.width() - 20.0).clamp(80.0, 360.0);
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Tooltip,
+            egui::Id::new("hover_card"),
+        ));
+        let title = painter.layout(
+            app.name.clone(),
+            egui::FontId::proportional(14.0),
+            theme.toast_text,
+            wrap,
+        );
+        let body = painter.layout(
+            details.join("\n"),
+            egui::FontId::proportional(12.0),
+            theme.toast_text.gamma_multiply(0.8),
+            wrap,
+        );
+
+        let size = egui::vec2(
+            title.size().x.max(body.size().x),
+            title.size().y + 4.0 + body.size().y,
+        ) + egui::vec2(20.0, 16.0);
+        let mut min = egui::pos2(anchor.min.x, anchor.max.y + 4.0);
+        if min.y + size.y > bounds.max.y {
+            min.y = anchor.min.y - 4.0 - size.y;
         }
+        min.x = min.x.min(bounds.max.x - size.x).max(bounds.min.x);
+        min.y = min.y.max(bounds.min.y);
+        let card_rect = egui::Rect::from_min_size(min, size);
+
+        painter.rect_filled(card_rect, 10.0, theme.toast_bg);
+        painter.rect_stroke(card_rect, 10.0, egui::Stroke::new(1.0, theme.row_border));
+        let text_min = card_rect.min + egui::vec2(10.0, 8.0);
+        let title_height = title.size().y;
+        painter.galley(text_min, title, theme.toast_text);
+        painter.galley(
+            text_min + egui::vec2(0.0, title_height + 4.0),
+            body,
+            theme.toast_text,
+        );
     }
 
+    /// Live toasts stacked around the panel center, oldest on top; each
+    /// expires on its own timer.
     fn draw_warning_overlay(&mut self, ui: &egui::Ui, theme: &LauncherTheme) {
-        if let Some((msg, start_time)) = &self.warning_message {
-            let elapsed = start_time.elapsed();
-            if elapsed < Duration::from_secs(2) {
-                let painter = ui.ctx().layer_painter(egui::LayerId::new(
-                    egui::Order::Foreground,
-                    egui::Id::new("warning"),
-                ));
-                let rect = ui.clip_rect();
+        self.toasts
+            .retain(|toast| toast.start.elapsed() < Duration::from_secs(2));
+        if self.toasts.is_empty() {
+            return;
+        }
 
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("warning"),
+        ));
+        let rect = ui.clip_rect();
+        let galleys: Vec<_> = self
+            .toasts
+            .iter()
+            .map(|toast| {
+                let stroke = theme.toast_stroke(toast.severity);
+                let text = if toast.count > 1 {
+                    format!("{} (×{})", toast.message, toast.count)
+                } else {
+                    toast.message.clone()
+                };
                 let galley = painter.layout(
-                    msg.clone(),
+                    text,
                     egui::FontId::proportional(15.0),
                     theme.toast_text,
                     f32::INFINITY,
                 );
+                (galley, stroke)
+            })
+            .collect();
+
+        let gap = 8.0;
+        let pad = 10.0;
+        let total_height: f32 = galleys
+            .iter()
+            .map(|(g, _)| g.size().y + pad * 2.0)
+            .sum::<f32>()
+            + gap * (galleys.len() - 1) as f32;
+        let mut top = rect.center().y - total_height * 0.5 + pad;
+        for (galley, stroke) in galleys {
+            let text_rect = egui::Rect::from_min_size(
+                egui::pos2(rect.center().x - galley.size().x * 0.5, top),
+                galley.size(),
+            );
+            painter.rect_filled(text_rect.expand(pad), 10.0, theme.toast_bg);
+            painter.rect_stroke(text_rect.expand(pad), 10.0, stroke);
+            top += galley.size().y + pad * 2.0 + gap;
+            painter.galley(text_rect.min, galley, theme.toast_text);
+        }
+        ui.ctx().request_repaint();
+    }
 
-                let text_rect = galley.rect;
-                let centered_rect = text_rect.translate(rect.center() - text_rect.center());
-                painter.rect_filled(centered_rect.expand(10.0), 10.0, theme.toast_bg);
-                painter.rect_stroke(
-                    centered_rect.expand(10.0),
-                    10.0,
-                    egui::Stroke::new(1.0, theme.row_border),
-                );
-                painter.galley(centered_rect.min, galley, theme.toast_text);
-                ui.ctx().request_repaint();
-            } else {
-                self.warning_message = None;
-            }
+    fn draw_quit_prompt(&mut self, ui: &egui::Ui, theme: &LauncherTheme) {
+        if !self.quit_prompt_open {
+            return;
+        }
+        let ctx = ui.ctx().clone();
+        let question = format!("Quit {}?", APP_DISPLAY_NAME);
+        match confirm_prompt(&ctx, "quit_prompt", &question, theme, true) {
+            Some(true) => self.shutdown(&ctx),
+            Some(false) => self.quit_prompt_open = false,
+            None => {}
         }
     }
 
-    fn draw_fade_in_overlay(&mut self, ui: &egui::Ui, panel_rounding: f32) {
-        if let Some(start) = self.fade_in_start {
-            let elapsed = start.elapsed();
-            let duration = Duration::from_millis(160);
-            if elapsed < duration {
-                let t = elapsed.as_secs_f32() / duration.as_secs_f32();
-                let alpha = ((1.0 - t) * 80.0) as u8;
-                ui.painter().rect_stroke(
-                    ui.clip_rect().shrink(6.0),
-                    panel_rounding,
-                    egui::Stroke::new(
-                        1.5,
-                        egui::Color32::from_rgba_premultiplied(190, 220, 255, alpha),
-                    ),
-                );
-                ui.ctx().request_repaint();
-            } else {
-                self.fade_in_start = None;
+    fn draw_launch_prompt(&mut self, ui: &egui::Ui, theme: &LauncherTheme) {
+        let Some(pending) = &mut self.pending_launch else {
+            return;
+        };
+        let question = if pending.elevated {
+            format!("Run {} as administrator?", pending.name)
+        } else {
+            format!("Launch {} from the network?", pending.name)
+        };
+        // The Enter that asked for the launch mustn't also confirm it.
+        let accept_keys = std::mem::replace(&mut pending.shown, true);
+        let Some(confirmed) =
+            confirm_prompt(ui.ctx(), "launch_prompt", &question, theme, accept_keys)
+        else {
+            return;
+        };
+        let Some(pending) = self.pending_launch.take().filter(|_| confirmed) else {
+            return;
+        };
+        match self.pin_index_for_key(&pending.key) {
+            Some(idx) => self.launch_pin(idx, pending.elevated),
+            None => self.show_toast(Severity::Warning, "That pin was removed"),
+        }
+    }
+}
+
+/// The pin's target and, for pins added from a shortcut, that shortcut.
+/// `None` for command pins, which have no file to show.
+fn pin_locations(app: &PinnedApp) -> Option<(PathBuf, Option<PathBuf>)> {
+    (!app.command).then(|| (app.path.clone(), app.shortcut.clone()))
+}
+
+/// A pin menu entry acting on the pin's file, as a Target/Shortcut submenu
+/// when the pin came from a shortcut. Returns the path picked.
+fn draw_location_menu(
+    ui: &mut egui::Ui,
+    label: &str,
+    locations: &(PathBuf, Option<PathBuf>),
+) -> Option<PathBuf> {
+    let (target, shortcut) = locations;
+    let Some(shortcut) = shortcut else {
+        if ui.button(label).clicked() {
+            ui.close_menu();
+            return Some(target.clone());
+        }
+        return None;
+    };
+    ui.menu_button(label, |ui| {
+        let picked = if ui.button("Target").clicked() {
+            Some(target.clone())
+        } else if ui.button("Shortcut").clicked() {
+            Some(shortcut.clone())
+        } else {
+            None
+        };
+        if picked.is_some() {
+            ui.close_menu();
+        }
+        picked
+    })
+    .inner
+    .flatten()
+}
+
+/// Picked on a folder row.
+enum FolderAction {
+    Toggle,
+    Rename(String),
+    LaunchAll,
+    Ungroup,
+}
+
+/// Folders with only the members still pinned, each pin in the first folder
+/// naming it; folders left with fewer than two pins are dissolved.
+fn prune_folders(apps: &[PinnedApp], groups: Vec<PinGroup>) -> Vec<PinGroup> {
+    let pinned: HashSet<String> = apps.iter().map(pin_launch_key).collect();
+    let mut claimed = HashSet::new();
+    groups
+        .into_iter()
+        .filter_map(|mut group| {
+            group.members.retain(|member| {
+                let key = member.key();
+                pinned.contains(&key) && claimed.insert(key)
+            });
+            (group.members.len() >= 2).then_some(group)
+        })
+        .collect()
+}
+
+/// The folder each pin is in, by position in `apps`.
+fn pin_folders(apps: &[PinnedApp], groups: &[PinGroup]) -> Vec<Option<usize>> {
+    let by_key: HashMap<String, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(group, folder)| folder.members.iter().map(move |m| (m.key(), group)))
+        .collect();
+    apps.iter()
+        .map(|app| by_key.get(&pin_launch_key(app)).copied())
+        .collect()
+}
+
+/// Pin indices with each folder's members pulled up to where its first
+/// member is, in the folder's own order.
+fn folder_order(apps: &[PinnedApp], groups: &[PinGroup]) -> Vec<usize> {
+    let folders = pin_folders(apps, groups);
+    let mut index_by_key: HashMap<String, usize> = HashMap::new();
+    for (idx, app) in apps.iter().enumerate() {
+        index_by_key.entry(pin_launch_key(app)).or_insert(idx);
+    }
+
+    let mut placed = vec![false; apps.len()];
+    let mut order = Vec::with_capacity(apps.len());
+    for idx in 0..apps.len() {
+        if let Some(group) = folders[idx].filter(|_| !placed[idx]) {
+            for member in &groups[group].members {
+                if let Some(&m) = index_by_key.get(&member.key()) {
+                    if !std::mem::replace(&mut placed[m], true) {
+                        order.push(m);
+                    }
+                }
             }
         }
+        if !std::mem::replace(&mut placed[idx], true) {
+            order.push(idx);
+        }
+    }
+    order
+}
+
+/// Takes `entry` out of whichever folder holds it.
+fn leave_folders(groups: &mut [PinGroup], entry: &TwoColumnEntry) {
+    for group in groups {
+        group.members.retain(|member| member != entry);
+    }
+}
+
+/// Puts `entry` into `folder` in front of the folder's pins from slot `slot`
+/// of `apps` on.
+fn insert_folder_member(
+    folder: &mut PinGroup,
+    entry: TwoColumnEntry,
+    apps: &[PinnedApp],
+    slot: usize,
+) {
+    let before = apps[..slot.min(apps.len())]
+        .iter()
+        .filter(|app| folder.members.contains(&grid_entry_from_app(app)))
+        .count();
+    folder
+        .members
+        .insert(before.min(folder.members.len()), entry);
+}
+
+/// "Folder", then "Folder 2", "Folder 3"… whichever isn't taken yet.
+fn next_folder_name(groups: &[PinGroup]) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => "Folder".to_string(),
+            n => format!("Folder {n}"),
+        })
+        .find(|name| groups.iter().all(|group| &group.name != name))
+        .unwrap_or_default()
+}
+
+/// Gap a dragged row would drop into, indented when it's inside a folder.
+fn paint_drop_slot(ui: &mut egui::Ui, width: f32, in_folder: bool, theme: &LauncherTheme) {
+    let (slot, _) = ui.allocate_exact_size(egui::vec2(width, ROW_HEIGHT), egui::Sense::hover());
+    let slot = if in_folder {
+        slot.with_min_x(slot.min.x + FOLDER_INDENT)
+    } else {
+        slot
+    };
+    ui.painter()
+        .rect_stroke(slot, 8.0, theme.drop_hint_stroke());
+    ui.add_space(5.0);
+}
+
+/// Flat folder outline: a tab on the top left of the body.
+fn paint_folder_glyph(painter: &egui::Painter, rect: egui::Rect, color: egui::Color32) {
+    let tab = egui::Rect::from_min_size(
+        rect.left_top() + egui::vec2(0.0, rect.height() * 0.12),
+        egui::vec2(rect.width() * 0.45, rect.height() * 0.2),
+    );
+    let body = egui::Rect::from_min_max(
+        rect.left_top() + egui::vec2(0.0, rect.height() * 0.25),
+        rect.right_bottom() - egui::vec2(0.0, rect.height() * 0.1),
+    );
+    painter.rect_filled(tab, 2.0, color);
+    painter.rect_filled(body, 3.0, color);
+}
+
+/// Small triangle pointing down when `open`, right when folded.
+fn paint_chevron(painter: &egui::Painter, rect: egui::Rect, open: bool, color: egui::Color32) {
+    let points = if open {
+        vec![rect.left_top(), rect.right_top(), rect.center_bottom()]
+    } else {
+        vec![rect.left_top(), rect.right_center(), rect.left_bottom()]
+    };
+    painter.add(egui::Shape::convex_polygon(
+        points,
+        color,
+        egui::Stroke::NONE,
+    ));
+}
+
+/// Picked from a pin's right-click menu; see `pin_context_menu`.
+enum PinMenuAction {
+    OpenTerminal,
+    Reveal(PathBuf),
+    Properties(PathBuf),
+    OpenWith(OpenWithChoice),
+    EditOptions,
+    ToggleFavorite,
+    LaunchSelected(Vec<usize>),
+    Remove(Vec<usize>),
+}
+
+/// Picked from a pin's "Open with" submenu.
+enum OpenWithChoice {
+    Browse,
+    /// Hand the file to the system picker once, without remembering it.
+    SystemPicker,
+    Default,
+}
+
+/// "Open with" for a file or folder pin, naming the current opener if set.
+fn draw_open_with_menu(ui: &mut egui::Ui, opener: Option<&Path>) -> Option<OpenWithChoice> {
+    ui.menu_button("Open with", |ui| {
+        if let Some(opener) = opener {
+            let name = opener
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| opener.display().to_string());
+            ui.add_enabled(false, egui::Button::new(format!("✔ {name}")));
+        }
+        let choice = if ui.button("Choose program…").clicked() {
+            Some(OpenWithChoice::Browse)
+        } else if cfg!(windows) && ui.button("Other app (once)…").clicked() {
+            Some(OpenWithChoice::SystemPicker)
+        } else if opener.is_some() && ui.button("Use default app").clicked() {
+            Some(OpenWithChoice::Default)
+        } else {
+            None
+        };
+        if choice.is_some() {
+            ui.close_menu();
+        }
+        choice
+    })
+    .inner
+    .flatten()
+}
+
+/// Opacity of a pin's icon and name: full, or faded while its target is unreachable.
+fn pin_fade(app: &PinnedApp) -> f32 {
+    if app.unavailable {
+        0.4
+    } else {
+        1.0
+    }
+}
+
+/// Small warning dot on the icon's corner: the target is unavailable.
+fn paint_unavailable_badge(painter: &egui::Painter, icon_rect: egui::Rect, theme: &LauncherTheme) {
+    let center = icon_rect.right_bottom() - egui::vec2(2.0, 2.0);
+    painter.circle_filled(center, 5.0, theme.toast_warning);
+    painter.text(
+        center,
+        egui::Align2::CENTER_CENTER,
+        "!",
+        egui::FontId::proportional(9.0),
+        theme.toast_bg,
+    );
+}
+
+/// Menu label for a pin set aside on load.
+fn broken_pin_name(meta: &PinnedLaunchMeta) -> String {
+    meta.display_name
+        .clone()
+        .or_else(|| {
+            meta.path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| meta.path.display().to_string())
+}
+
+/// The pin's current opener when it can take one; `None` hides "Open with".
+fn document_opener(app: &PinnedApp) -> Option<Option<PathBuf>> {
+    (!app.command && opens_as_document(&app.path)).then(|| app.opener.clone())
+}
+
+/// Folders and documents, which can take an opener; programs and shortcuts
+/// run themselves.
+fn opens_as_document(path: &Path) -> bool {
+    if path.is_dir() {
+        return true;
+    }
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            !["exe", "com", "bat", "cmd", "lnk", "desktop", "appref-ms"]
+                .iter()
+                .any(|program| ext.eq_ignore_ascii_case(program))
+        })
+}
+
+/// Launches `app` the way a click does: through its opener, or as a command.
+/// `elevated` runs it as administrator, which passes neither its environment
+/// nor its window state.
+fn launch_app(launcher: &dyn Launcher, app: &PinnedApp, elevated: bool) -> Result<(), LaunchError> {
+    let (program, args) = launch_command(app);
+    if elevated {
+        return launcher.open_elevated(&program, args.as_deref(), app.working_dir.as_deref());
+    }
+    launcher.open_with_env(
+        &program,
+        args.as_deref(),
+        app.working_dir.as_deref(),
+        &app.env,
+        app.show_state,
+    )
+}
+
+/// Toast for a pin launch that didn't start. Declining the UAC prompt is
+/// only a warning.
+fn launch_failure_toast(name: &str, elevated: bool, err: LaunchError) -> (Severity, String) {
+    match (elevated, err) {
+        (true, LaunchError::AccessDenied) => {
+            (Severity::Warning, "Elevated launch cancelled".to_string())
+        }
+        (true, err) => (
+            Severity::Error,
+            format!("Couldn't launch {name} as administrator: {err}"),
+        ),
+        (false, err) => (Severity::Error, format!("Couldn't launch {name}: {err}")),
+    }
+}
+
+/// Program and arguments a pin launches with: a command pin's split command
+/// line, its opener with the target as the first argument when one is set,
+/// otherwise the target itself.
+fn launch_command(app: &PinnedApp) -> (PathBuf, Option<String>) {
+    if app.command {
+        // An empty command can only come from a hand-edited config; the
+        // empty program then fails to launch and says so.
+        return crate::system::split_program(&app.path.to_string_lossy()).unwrap_or_default();
+    }
+    let Some(opener) = &app.opener else {
+        return (app.path.clone(), app.launch_args.clone());
+    };
+    let mut target = app.path.display().to_string();
+    // A trailing backslash would escape the closing quote (`"C:\"`).
+    if target.ends_with('\\') {
+        target.push('\\');
+    }
+    let args = match &app.launch_args {
+        Some(extra) => format!("\"{target}\" {extra}"),
+        None => format!("\"{target}\""),
+    };
+    (opener.clone(), Some(args))
+}
+
+/// What the "Launch options" dialog saves to its pin.
+#[derive(Debug, PartialEq)]
+struct LaunchOptions {
+    args: Option<String>,
+    working_dir: Option<PathBuf>,
+    env: Vec<(String, String)>,
+}
+
+/// Checks the dialog's fields, returning the message to show on a problem.
+/// A pin that takes no environment drops whatever its draft holds.
+fn launch_options_from_draft(draft: &LaunchOptionsDraft) -> Result<LaunchOptions, String> {
+    let env = if draft.takes_env {
+        parse_env_lines(&draft.env).map_err(|line| format!("Not a NAME=value line: {line}"))?
+    } else {
+        Vec::new()
+    };
+    let args = Some(draft.args.trim().to_string()).filter(|args| !args.is_empty());
+    let working_dir = Some(draft.working_dir.trim())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    if working_dir.as_ref().is_some_and(|dir| !dir.is_dir()) {
+        return Err("Start-in folder not found".to_string());
+    }
+    Ok(LaunchOptions {
+        args,
+        working_dir,
+        env,
+    })
+}
+
+/// Reads the dialog's `NAME=value` lines, skipping blank ones; a line
+/// without a name is returned as the error.
+fn parse_env_lines(text: &str) -> Result<Vec<(String, String)>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                Ok((name.trim().to_string(), value.to_string()))
+            }
+            _ => Err(line.to_string()),
+        })
+        .collect()
+}
+
+fn format_env_lines(env: &[(String, String)]) -> String {
+    env.iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn favorite_menu_label(favorite: bool) -> &'static str {
+    if favorite {
+        "Remove from favorites"
+    } else {
+        "Add to favorites"
+    }
+}
+
+/// Pin indices with the favorites moved to the front, each group keeping
+/// its order.
+fn favorites_first_order(favorites: &[bool]) -> Vec<usize> {
+    let (mut order, rest): (Vec<usize>, Vec<usize>) =
+        (0..favorites.len()).partition(|idx| favorites[*idx]);
+    order.extend(rest);
+    order
+}
+
+/// `columns` read down each column in turn, with the favorites moved to the
+/// front and poured back into columns of the same lengths.
+fn favorites_first_columns(columns: &[Vec<usize>], favorites: &[bool]) -> Vec<Vec<usize>> {
+    let flat = columns.concat();
+    let flags: Vec<bool> = flat.iter().map(|idx| favorites[*idx]).collect();
+    let mut order = favorites_first_order(&flags)
+        .into_iter()
+        .map(|pos| flat[pos]);
+    columns
+        .iter()
+        .map(|column| order.by_ref().take(column.len()).collect())
+        .collect()
+}
+
+/// The stored pin order after a drag left the pins drawn as `shown`:
+/// `moved` goes next to a pin drawn beside it in its own favorites group,
+/// and every other pin keeps its place in `stored`.
+fn place_dragged_pin(
+    stored: &[usize],
+    shown: &[usize],
+    moved: usize,
+    favorites: &[bool],
+) -> Vec<usize> {
+    let mut order: Vec<usize> = stored.iter().copied().filter(|idx| *idx != moved).collect();
+    let same_group = |idx: &usize| favorites[*idx] == favorites[moved];
+    let pos = shown.iter().position(|idx| *idx == moved);
+    let next = pos
+        .and_then(|pos| shown.get(pos + 1))
+        .filter(|idx| same_group(idx));
+    let prev = pos
+        .and_then(|pos| pos.checked_sub(1))
+        .map(|pos| &shown[pos])
+        .filter(|idx| same_group(idx));
+    let at = if let Some(next) = next {
+        order.iter().position(|idx| idx == next)
+    } else if let Some(prev) = prev {
+        order.iter().position(|idx| idx == prev).map(|pos| pos + 1)
+    } else {
+        stored.iter().position(|idx| *idx == moved)
+    };
+    order.insert(at.unwrap_or(order.len()).min(order.len()), moved);
+    order
+}
+
+/// Centered Yes/No prompt; with `accept_keys`, Enter answers yes and Escape
+/// no. `None` until answered.
+fn confirm_prompt(
+    ctx: &egui::Context,
+    id: &str,
+    question: &str,
+    theme: &LauncherTheme,
+    accept_keys: bool,
+) -> Option<bool> {
+    let (confirm, cancel) = ctx.input(|i| {
+        (
+            accept_keys && i.key_pressed(egui::Key::Enter),
+            accept_keys && i.key_pressed(egui::Key::Escape),
+        )
+    });
+    let mut answer = if confirm {
+        Some(true)
+    } else if cancel {
+        Some(false)
+    } else {
+        None
+    };
+
+    egui::Area::new(egui::Id::new(id))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Frame::none()
+                .fill(theme.toast_bg)
+                .stroke(egui::Stroke::new(1.0, theme.row_border))
+                .rounding(10.0)
+                .inner_margin(egui::Margin::same(12.0))
+                .show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(question)
+                            .color(theme.toast_text)
+                            .size(15.0),
+                    );
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Yes").clicked() {
+                            answer = Some(true);
+                        }
+                        if ui.button("No").clicked() {
+                            answer = Some(false);
+                        }
+                    });
+                });
+        });
+    answer
+}
+
+/// Snapped origin along one axis when the window's near or far edge is within
+/// `threshold` of the matching bound.
+fn snap_axis(pos: f32, len: f32, bounds: egui::Rangef, threshold: f32) -> Option<f32> {
+    if (pos - bounds.min).abs() < threshold {
+        Some(bounds.min)
+    } else if (pos + len - bounds.max).abs() < threshold {
+        Some(bounds.max - len)
+    } else {
+        None
     }
 }
 
-fn clamp_window_origin(pos: egui::Pos2, size: egui::Vec2, monitor_size: egui::Vec2) -> egui::Pos2 {
-    let min_x = MIN_VISIBLE_WIDTH - size.x;
-    let max_x = (monitor_size.x - MIN_VISIBLE_WIDTH).max(min_x);
-    let min_y = 0.0;
-    let max_y = (monitor_size.y - HEADER_HEIGHT).max(min_y);
+/// Area the window is kept on, in points: every monitor's bounding box, or
+/// just `monitor_size` at the origin when the platform can't tell.
+pub(super) fn desktop_bounds(ctx: &egui::Context, monitor_size: egui::Vec2) -> egui::Rect {
+    let Some((left, top, right, bottom)) = crate::system::virtual_desktop() else {
+        return egui::Rect::from_min_size(egui::Pos2::ZERO, monitor_size);
+    };
+    let ppp = ctx.pixels_per_point();
+    egui::Rect::from_min_max(
+        egui::pos2(left as f32 / ppp, top as f32 / ppp),
+        egui::pos2(right as f32 / ppp, bottom as f32 / ppp),
+    )
+}
+
+/// Keeps the top edge inside `bounds`, `MIN_VISIBLE_WIDTH` on screen at
+/// either side and the header plus a couple of rows (or the whole window,
+/// if shorter) above the bottom edge.
+pub(super) fn clamp_window_origin(
+    pos: egui::Pos2,
+    size: egui::Vec2,
+    bounds: egui::Rect,
+) -> egui::Pos2 {
+    let min_x = bounds.min.x + MIN_VISIBLE_WIDTH - size.x;
+    let max_x = (bounds.max.x - MIN_VISIBLE_WIDTH).max(min_x);
+    let min_y = bounds.min.y;
+    let max_y = (bounds.max.y - MIN_VISIBLE_HEIGHT.min(size.y)).max(min_y);
 
     egui::pos2(pos.x.clamp(min_x, max_x), pos.y.clamp(min_y, max_y))
 }
+
+/// `size` reshaped to `ratio` (width / height), keeping the dragged side
+/// and deriving the other. When the derived side would leave
+/// `min`..=`max` it is pinned there and the dragged side follows.
+fn lock_aspect(
+    size: egui::Vec2,
+    ratio: f32,
+    width_drives: bool,
+    min: egui::Vec2,
+    max: egui::Vec2,
+) -> egui::Vec2 {
+    if width_drives {
+        let height = (size.x / ratio).clamp(min.y, max.y);
+        egui::vec2((height * ratio).clamp(min.x, max.x), height)
+    } else {
+        let width = (size.y * ratio).clamp(min.x, max.x);
+        egui::vec2(width, (width / ratio).clamp(min.y, max.y))
+    }
+}
+
+/// Which sides an edge drag moves: (left, right, top, bottom).
+fn resize_edge_sides(edge: ResizeEdge) -> (bool, bool, bool, bool) {
+    match edge {
+        ResizeEdge::Left => (true, false, false, false),
+        ResizeEdge::Right => (false, true, false, false),
+        ResizeEdge::Top => (false, false, true, false),
+        ResizeEdge::Bottom => (false, false, false, true),
+        ResizeEdge::TopLeft => (true, false, true, false),
+        ResizeEdge::TopRight => (false, true, true, false),
+        ResizeEdge::BottomLeft => (true, false, false, true),
+        ResizeEdge::BottomRight => (false, true, false, true),
+    }
+}
+
+/// Window origin after resizing from `start_size` to `size`. Left and top
+/// edges move the origin so the opposite edge stays put.
+fn resized_origin(
+    edge: ResizeEdge,
+    start_pos: egui::Pos2,
+    start_size: egui::Vec2,
+    size: egui::Vec2,
+) -> egui::Pos2 {
+    let (from_left, _, from_top, _) = resize_edge_sides(edge);
+    let mut pos = start_pos;
+    if from_left {
+        pos.x += start_size.x - size.x;
+    }
+    if from_top {
+        pos.y += start_size.y - size.y;
+    }
+    pos
+}
+
+/// How far the mouse has moved on screen since a resize drag started, from
+/// the window's current origin and the pointer's position inside it.
+fn resize_pointer_delta(
+    window_origin: egui::Pos2,
+    pointer: egui::Pos2,
+    start_global_mouse: egui::Pos2,
+) -> egui::Vec2 {
+    window_origin + pointer.to_vec2() - start_global_mouse
+}
+
 fn resize_edge_cursor(edge: ResizeEdge) -> egui::CursorIcon {
     match edge {
         ResizeEdge::Left | ResizeEdge::Right => egui::CursorIcon::ResizeHorizontal,
-        ResizeEdge::Bottom => egui::CursorIcon::ResizeVertical,
-        ResizeEdge::BottomLeft => egui::CursorIcon::ResizeNeSw,
-        ResizeEdge::BottomRight => egui::CursorIcon::ResizeNwSe,
+        ResizeEdge::Top | ResizeEdge::Bottom => egui::CursorIcon::ResizeVertical,
+        ResizeEdge::TopRight | ResizeEdge::BottomLeft => egui::CursorIcon::ResizeNeSw,
+        ResizeEdge::TopLeft | ResizeEdge::BottomRight => egui::CursorIcon::ResizeNwSe,
     }
 }
 
@@ -1558,7 +4872,10 @@ fn resize_edge_key(edge: ResizeEdge) -> &'static str {
     match edge {
         ResizeEdge::Left => "left",
         ResizeEdge::Right => "right",
+        ResizeEdge::Top => "top",
         ResizeEdge::Bottom => "bottom",
+        ResizeEdge::TopLeft => "top_left",
+        ResizeEdge::TopRight => "top_right",
         ResizeEdge::BottomLeft => "bottom_left",
         ResizeEdge::BottomRight => "bottom_right",
     }
@@ -1595,8 +4912,10 @@ enum AddPinResult {
     LimitReached,
 }
 
-fn is_supported_app_path(path: &Path) -> bool {
-    if path.is_dir() {
+/// Folders, executables and shortcuts; any other file only when `allow_any_file`
+/// (the shell opens those with their associated app).
+fn is_supported_app_path(path: &Path, allow_any_file: bool) -> bool {
+    if path.is_dir() || (allow_any_file && path.is_file()) {
         return true;
     }
     path.extension()
@@ -1605,27 +4924,22 @@ fn is_supported_app_path(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn normalize_path_key(path: &Path) -> String {
-    path.to_string_lossy().to_ascii_lowercase()
-}
-
-fn normalize_launch_key(path: &Path, args: Option<&str>, working_dir: Option<&Path>) -> String {
-    let normalized_args = args.map(str::trim).unwrap_or_default();
-    let normalized_wd = working_dir.map(normalize_path_key).unwrap_or_default();
-    format!(
-        "{}|{}|{}",
-        normalize_path_key(path),
-        normalized_args,
-        normalized_wd
-    )
+fn same_launch_path(a: &Path, b: &Path) -> bool {
+    normalize_launch_key(a, None, None) == normalize_launch_key(b, None, None)
 }
 
-fn resolve_two_column_indices(
+/// Splits pin indices into `column_count` columns following the saved layout.
+/// Pins the layout doesn't mention go to the first column; columns beyond
+/// `column_count` fold into the last one.
+fn resolve_column_indices(
     apps: &[PinnedApp],
-    layout: Option<&TwoColumnLayout>,
-) -> (Vec<usize>, Vec<usize>) {
+    layout: Option<&GridLayout>,
+    column_count: usize,
+) -> Vec<Vec<usize>> {
+    let column_count = column_count.max(1);
+    let mut columns = vec![Vec::with_capacity(apps.len()); column_count];
     if apps.is_empty() {
-        return (Vec::new(), Vec::new());
+        return columns;
     }
 
     let keys: Vec<String> = apps
@@ -1639,63 +4953,139 @@ fn resolve_two_column_indices(
         })
         .collect();
 
+    // Indices per key in pin order, so duplicates are matched first-come and
+    // large docks don't pay a linear scan per saved entry.
+    let mut unused_by_key: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (idx, key) in keys.iter().enumerate() {
+        unused_by_key
+            .entry(key.as_str())
+            .or_default()
+            .push_back(idx);
+    }
+
     let mut used = vec![false; apps.len()];
-    let mut left = Vec::with_capacity(apps.len());
-    let mut right = Vec::with_capacity(apps.len());
+    let mut placed_any = false;
 
     if let Some(layout) = layout {
-        for entry in &layout.left {
-            if let Some(idx) = find_unused_index_by_key(&keys, entry.key().as_str(), &used) {
-                used[idx] = true;
-                left.push(idx);
-            }
-        }
-        for entry in &layout.right {
-            if let Some(idx) = find_unused_index_by_key(&keys, entry.key().as_str(), &used) {
-                used[idx] = true;
-                right.push(idx);
+        for (col, entries) in layout.columns.iter().enumerate() {
+            let target = col.min(column_count - 1);
+            for entry in entries {
+                let next = unused_by_key
+                    .get_mut(entry.key().as_str())
+                    .and_then(VecDeque::pop_front);
+                if let Some(idx) = next {
+                    used[idx] = true;
+                    columns[target].push(idx);
+                    placed_any = true;
+                }
             }
         }
     }
 
-    if left.is_empty() && right.is_empty() {
+    if !placed_any {
         for idx in 0..apps.len() {
-            if idx % 2 == 0 {
-                left.push(idx);
-            } else {
-                right.push(idx);
-            }
+            columns[idx % column_count].push(idx);
         }
-        return (left, right);
+        return columns;
     }
 
-    for idx in 0..apps.len() {
-        if !used[idx] {
-            left.push(idx);
+    for (idx, is_used) in used.iter().enumerate() {
+        if !is_used {
+            columns[0].push(idx);
         }
     }
 
-    (left, right)
+    columns
 }
 
-fn find_unused_index_by_key(keys: &[String], target: &str, used: &[bool]) -> Option<usize> {
-    keys.iter()
-        .enumerate()
-        .find(|(idx, key)| !used[*idx] && key.as_str() == target)
-        .map(|(idx, _)| idx)
+fn column_lengths(columns: &[Vec<usize>]) -> Vec<usize> {
+    columns.iter().map(Vec::len).collect()
 }
 
-fn find_column_slot(index: usize, left: &[usize], right: &[usize]) -> Option<(usize, usize)> {
-    if let Some(pos) = left.iter().position(|&idx| idx == index) {
-        return Some((0, pos));
+/// Narrowest share of the grid width a column can be dragged down to.
+const MIN_COLUMN_FRACTION: f32 = 0.12;
+
+/// Saved column weights normalized to sum to 1, or equal weights when the
+/// saved list doesn't match `count` or holds nonsense.
+fn column_weights(saved: &[f32], count: usize) -> Vec<f32> {
+    let valid = saved.len() == count && saved.iter().all(|w| w.is_finite() && *w > 0.0);
+    if !valid {
+        return vec![1.0 / count as f32; count];
     }
-    right
+    let sum: f32 = saved.iter().sum();
+    saved.iter().map(|w| w / sum).collect()
+}
+
+/// Cell widths for `weights`, keeping every cell at least `GRID_MIN_CELL_WIDTH`
+/// (the grid scrolls sideways rather than squashing rows).
+fn grid_column_widths(list_width: f32, col_gap: f32, weights: &[f32]) -> Vec<f32> {
+    let count = weights.len();
+    let available = (list_width - col_gap * (count - 1) as f32).max(0.0);
+    weights
         .iter()
-        .position(|&idx| idx == index)
-        .map(|pos| (1, pos))
+        .map(|w| (available * w).max(GRID_MIN_CELL_WIDTH))
+        .collect()
+}
+
+/// Moves `delta` (a fraction of the grid width) from the column right of
+/// `divider` to the one left of it, keeping both above `MIN_COLUMN_FRACTION`.
+fn shift_column_weight(weights: &mut [f32], divider: usize, delta: f32) {
+    let (left, right) = (weights[divider], weights[divider + 1]);
+    if !delta.is_finite() || left + right < MIN_COLUMN_FRACTION * 2.0 {
+        return;
+    }
+    let delta = delta.clamp(MIN_COLUMN_FRACTION - left, right - MIN_COLUMN_FRACTION);
+    weights[divider] = left + delta;
+    weights[divider + 1] = right - delta;
+}
+
+/// Column a grid drag should target. `boundaries` are the x positions between
+/// neighbouring columns; the current column is kept until the pointer is
+/// clearly past one of its own boundaries.
+fn pick_drag_column(pointer_x: f32, boundaries: &[f32], current_col: usize) -> usize {
+    let current_col = current_col.min(boundaries.len());
+    let leaving_right = boundaries
+        .get(current_col)
+        .is_some_and(|&right| pointer_x > right + COLUMN_SWITCH_HYSTERESIS);
+    let leaving_left = current_col
+        .checked_sub(1)
+        .and_then(|left| boundaries.get(left))
+        .is_some_and(|&left| pointer_x < left - COLUMN_SWITCH_HYSTERESIS);
+    if leaving_right || leaving_left {
+        boundaries.iter().filter(|&&x| pointer_x > x).count()
+    } else {
+        current_col
+    }
+}
+
+fn find_column_slot(index: usize, columns: &[Vec<usize>]) -> Option<(usize, usize)> {
+    columns.iter().enumerate().find_map(|(col, indices)| {
+        indices
+            .iter()
+            .position(|&idx| idx == index)
+            .map(|pos| (col, pos))
+    })
+}
+
+/// Maps an index to its position after the (descending) `removed` indices are
+/// taken out, or `None` if it was removed itself.
+fn shift_index_after_removal(index: usize, removed: &[usize]) -> Option<usize> {
+    if removed.contains(&index) {
+        return None;
+    }
+    Some(index - removed.iter().filter(|&&r| r < index).count())
 }
 
 fn slot_from_pointer(pointer_y: f32, rects: &[egui::Rect]) -> usize {
+    let (Some(first), Some(last)) = (rects.first(), rects.last()) else {
+        return 0;
+    };
+    if pointer_y <= first.min.y {
+        return 0;
+    }
+    if pointer_y >= last.max.y {
+        return rects.len();
+    }
     for (slot, rect) in rects.iter().enumerate() {
         if pointer_y < rect.center().y {
             return slot;
@@ -1704,15 +5094,106 @@ fn slot_from_pointer(pointer_y: f32, rects: &[egui::Rect]) -> usize {
     rects.len()
 }
 
-fn reorder_pinned_apps_by_columns(apps: &mut Vec<PinnedApp>, left: &[usize], right: &[usize]) {
+/// Three-quarter ring rotating with `time` (seconds).
+fn paint_spinner(painter: &egui::Painter, rect: egui::Rect, color: egui::Color32, time: f64) {
+    let radius = rect.width().min(rect.height()) * 0.38;
+    let start = (time * 5.0) as f32;
+    let points: Vec<egui::Pos2> = (0..=18)
+        .map(|i| {
+            let angle = start + i as f32 / 18.0 * std::f32::consts::TAU * 0.75;
+            rect.center() + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(2.0, color)));
+}
+
+/// Two columns of dots marking a row's drag grip.
+fn paint_grip(painter: &egui::Painter, rect: egui::Rect, color: egui::Color32) {
+    let center = rect.center() + egui::vec2(1.0, 0.0);
+    for dx in [-2.0, 2.0] {
+        for dy in [-5.0, 0.0, 5.0] {
+            painter.circle_filled(center + egui::vec2(dx, dy), 1.2, color);
+        }
+    }
+}
+
+/// Selection after moving `steps` pins from `current`, clamped to the list.
+/// With nothing selected, the first step lands on the first or last pin.
+fn wheel_cycle_target(current: Option<usize>, steps: i32, len: usize) -> usize {
+    let last = len.saturating_sub(1) as i64;
+    let from = match current.filter(|idx| *idx < len) {
+        Some(idx) => idx as i64,
+        None if steps > 0 => -1,
+        None => last + 1,
+    };
+    (from + steps as i64).clamp(0, last) as usize
+}
+
+/// Height of a grid page showing exactly `rows` rows.
+fn grid_page_height(rows: usize, row_gap: f32) -> f32 {
+    let rows = rows.max(1) as f32;
+    rows * ROW_HEIGHT + (rows - 1.0) * row_gap
+}
+
+/// Smallest window that fits `columns` grid columns of at least
+/// `GRID_MIN_CELL_WIDTH` and, when set, a whole page of `rows` rows.
+fn grid_window_size(columns: usize, rows: Option<usize>, padding: f32) -> egui::Vec2 {
+    let columns = columns.max(1) as f32;
+    let width = columns * GRID_MIN_CELL_WIDTH + (columns - 1.0) * GRID_COL_GAP + padding * 2.0;
+    let height = rows.map_or(0.0, |rows| {
+        HEADER_HEIGHT + grid_page_height(rows, GRID_ROW_GAP) + padding * 2.0
+    });
+    sanitize_window_size(egui::vec2(width, height))
+}
+
+/// Offset change for one frame of drag auto-scroll, growing as the pointer
+/// approaches (or passes) the edge of the visible list.
+fn drag_scroll_step(pointer_y: f32, view: egui::Rect) -> f32 {
+    let top_gap = pointer_y - view.top();
+    if top_gap < DRAG_SCROLL_EDGE {
+        let closeness = 1.0 - (top_gap / DRAG_SCROLL_EDGE).max(0.0);
+        return -DRAG_SCROLL_MAX_STEP * closeness;
+    }
+    let bottom_gap = view.bottom() - pointer_y;
+    if bottom_gap < DRAG_SCROLL_EDGE {
+        let closeness = 1.0 - (bottom_gap / DRAG_SCROLL_EDGE).max(0.0);
+        return DRAG_SCROLL_MAX_STEP * closeness;
+    }
+    0.0
+}
+
+/// Where Ctrl+Up (or Ctrl+Down with `down`) moves the pin at `idx`; `None`
+/// at the end of the list.
+fn key_move_target(idx: usize, down: bool, len: usize) -> Option<usize> {
+    if down {
+        Some(idx + 1).filter(|next| *next < len)
+    } else {
+        idx.checked_sub(1)
+    }
+}
+
+/// Puts `apps` in `order` (old indices by new position) and returns each old
+/// index's new position; `None` when nothing moved.
+fn reorder_pin_list(apps: &mut Vec<PinnedApp>, order: Vec<usize>) -> Option<Vec<usize>> {
+    if order.iter().enumerate().all(|(pos, idx)| pos == *idx) {
+        return None;
+    }
+
+    let mut new_pos = vec![0; order.len()];
+    for (pos, idx) in order.iter().enumerate() {
+        new_pos[*idx] = pos;
+    }
+    reorder_pinned_apps_by_columns(apps, &[order]);
+    Some(new_pos)
+}
+
+fn reorder_pinned_apps_by_columns(apps: &mut Vec<PinnedApp>, columns: &[Vec<usize>]) {
     let total = apps.len();
     if total == 0 {
         return;
     }
 
-    let mut order = Vec::with_capacity(total);
-    order.extend(left.iter().copied());
-    order.extend(right.iter().copied());
+    let order: Vec<usize> = columns.iter().flatten().copied().collect();
 
     if order.len() != total {
         return;
@@ -1739,22 +5220,30 @@ fn reorder_pinned_apps_by_columns(apps: &mut Vec<PinnedApp>, left: &[usize], rig
     }
 }
 
-fn two_column_layout_from_split(apps: &[PinnedApp], left_len: usize) -> TwoColumnLayout {
-    let split = left_len.min(apps.len());
-    let left = apps
-        .iter()
-        .take(split)
-        .map(two_column_entry_from_app)
-        .collect();
-    let right = apps
+/// Layout for pins already in column order, cut into columns of `lengths`.
+/// Anything past the given lengths lands in the last column.
+fn grid_layout_from_lengths(apps: &[PinnedApp], lengths: &[usize]) -> GridLayout {
+    let mut rest = apps;
+    let mut columns: Vec<Vec<TwoColumnEntry>> = lengths
         .iter()
-        .skip(split)
-        .map(two_column_entry_from_app)
+        .map(|&len| {
+            let (head, tail) = rest.split_at(len.min(rest.len()));
+            rest = tail;
+            head.iter().map(grid_entry_from_app).collect()
+        })
         .collect();
-    TwoColumnLayout { left, right }
+    if !rest.is_empty() {
+        if columns.is_empty() {
+            columns.push(Vec::new());
+        }
+        if let Some(last) = columns.last_mut() {
+            last.extend(rest.iter().map(grid_entry_from_app));
+        }
+    }
+    GridLayout { columns }
 }
 
-fn two_column_entry_from_app(app: &PinnedApp) -> TwoColumnEntry {
+fn grid_entry_from_app(app: &PinnedApp) -> TwoColumnEntry {
     TwoColumnEntry::from_launch(
         app.path.clone(),
         app.launch_args.clone(),
@@ -1769,6 +5258,7 @@ fn paint_glow_blob(painter: &egui::Painter, center: egui::Pos2, radius: f32, col
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::normalize_path_key;
     use std::path::PathBuf;
 
     fn make_app(name: &str) -> PinnedApp {
@@ -1791,21 +5281,53 @@ mod tests {
     #[test]
     fn two_column_layout_restores_saved_right_column() {
         let mut apps = vec![make_app("A"), make_app("B"), make_app("C"), make_app("D")];
-        let saved_layout = TwoColumnLayout {
-            left: vec![make_entry("A"), make_entry("C")],
-            right: vec![make_entry("B"), make_entry("D")],
+        let saved_layout = GridLayout {
+            columns: vec![
+                vec![make_entry("A"), make_entry("C")],
+                vec![make_entry("B"), make_entry("D")],
+            ],
         };
 
-        let (left, right) = resolve_two_column_indices(&apps, Some(&saved_layout));
-        assert_eq!(left, vec![0, 2]);
-        assert_eq!(right, vec![1, 3]);
+        let columns = resolve_column_indices(&apps, Some(&saved_layout), 2);
+        assert_eq!(columns, vec![vec![0, 2], vec![1, 3]]);
 
-        reorder_pinned_apps_by_columns(&mut apps, &left, &right);
+        reorder_pinned_apps_by_columns(&mut apps, &columns);
         assert_eq!(names(&apps), vec!["A", "C", "B", "D"]);
 
-        let (left_again, right_again) = resolve_two_column_indices(&apps, Some(&saved_layout));
-        assert_eq!(left_again, vec![0, 1]);
-        assert_eq!(right_again, vec![2, 3]);
+        let again = resolve_column_indices(&apps, Some(&saved_layout), 2);
+        assert_eq!(again, vec![vec![0, 1], vec![2, 3]]);
+    }
+
+    #[test]
+    fn three_column_layout_round_trips_and_folds_extra_columns() {
+        let mut apps: Vec<PinnedApp> = ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|name| make_app(name))
+            .collect();
+
+        let fresh = resolve_column_indices(&apps, None, 3);
+        assert_eq!(fresh, vec![vec![0, 3], vec![1, 4], vec![2]]);
+
+        reorder_pinned_apps_by_columns(&mut apps, &fresh);
+        let layout = grid_layout_from_lengths(&apps, &column_lengths(&fresh));
+        assert_eq!(
+            resolve_column_indices(&apps, Some(&layout), 3),
+            vec![vec![0, 1], vec![2, 3], vec![4]]
+        );
+
+        // Dropping to two columns keeps the third column's pins in the last one.
+        assert_eq!(
+            resolve_column_indices(&apps, Some(&layout), 2),
+            vec![vec![0, 1], vec![2, 3, 4]]
+        );
+    }
+
+    #[test]
+    fn legacy_two_column_layout_deserializes_into_columns() {
+        let json = r#"{"left":[{"path":"C:\\Apps\\A.exe"}],"right":[{"path":"C:\\Apps\\B.exe"}]}"#;
+        let layout: GridLayout = serde_json::from_str(json).expect("legacy layout");
+        assert_eq!(layout.columns.len(), 2);
+        assert_eq!(layout.columns[1][0].path, PathBuf::from(r"C:\Apps\B.exe"));
     }
 
     #[test]
@@ -1817,13 +5339,763 @@ mod tests {
             make_app("D"),
             make_app("E"),
         ];
-        let saved_layout = TwoColumnLayout {
-            left: vec![make_entry("A"), make_entry("C")],
-            right: vec![make_entry("B"), make_entry("D")],
+        let saved_layout = GridLayout {
+            columns: vec![
+                vec![make_entry("A"), make_entry("C")],
+                vec![make_entry("B"), make_entry("D")],
+            ],
+        };
+
+        let columns = resolve_column_indices(&apps, Some(&saved_layout), 2);
+        assert_eq!(columns, vec![vec![0, 1, 4], vec![2, 3]]);
+    }
+
+    #[test]
+    fn slot_from_pointer_clamps_outside_rows() {
+        let rects: Vec<egui::Rect> = (0..3)
+            .map(|i| {
+                let top = 100.0 + i as f32 * (ROW_HEIGHT + 5.0);
+                egui::Rect::from_min_size(egui::pos2(0.0, top), egui::vec2(200.0, ROW_HEIGHT))
+            })
+            .collect();
+
+        assert_eq!(slot_from_pointer(-500.0, &rects), 0);
+        assert_eq!(slot_from_pointer(rects[0].min.y, &rects), 0);
+        assert_eq!(slot_from_pointer(rects[1].center().y - 1.0, &rects), 1);
+        assert_eq!(slot_from_pointer(rects[2].max.y, &rects), rects.len());
+        assert_eq!(slot_from_pointer(10_000.0, &rects), rects.len());
+        assert_eq!(slot_from_pointer(42.0, &[]), 0);
+    }
+
+    #[test]
+    fn selection_shifts_after_batch_removal() {
+        let removed = vec![5, 2, 0];
+        assert_eq!(shift_index_after_removal(0, &removed), None);
+        assert_eq!(shift_index_after_removal(1, &removed), Some(0));
+        assert_eq!(shift_index_after_removal(3, &removed), Some(1));
+        assert_eq!(shift_index_after_removal(5, &removed), None);
+        assert_eq!(shift_index_after_removal(7, &removed), Some(4));
+    }
+
+    #[test]
+    fn drag_scroll_step_scales_with_edge_distance() {
+        let view = egui::Rect::from_min_size(egui::pos2(0.0, 100.0), egui::vec2(200.0, 400.0));
+
+        assert_eq!(drag_scroll_step(300.0, view), 0.0);
+        assert!(drag_scroll_step(view.top() + 2.0, view) < 0.0);
+        assert!(drag_scroll_step(view.bottom() - 2.0, view) > 0.0);
+        assert!(
+            drag_scroll_step(view.top() + 4.0, view).abs()
+                > drag_scroll_step(view.top() + 20.0, view).abs()
+        );
+        assert_eq!(
+            drag_scroll_step(view.top() - 50.0, view),
+            -DRAG_SCROLL_MAX_STEP
+        );
+        assert_eq!(
+            drag_scroll_step(view.bottom() + 50.0, view),
+            DRAG_SCROLL_MAX_STEP
+        );
+    }
+
+    #[test]
+    fn wheel_cycle_clamps_at_list_ends() {
+        assert_eq!(wheel_cycle_target(Some(2), 1, 5), 3);
+        assert_eq!(wheel_cycle_target(Some(2), -3, 5), 0);
+        assert_eq!(wheel_cycle_target(Some(4), 2, 5), 4);
+        assert_eq!(wheel_cycle_target(None, 1, 5), 0);
+        assert_eq!(wheel_cycle_target(None, -1, 5), 4);
+        assert_eq!(wheel_cycle_target(Some(9), 1, 5), 0);
+    }
+
+    #[test]
+    fn column_divider_drag_stays_within_limits() {
+        let mut weights = column_weights(&[], 2);
+        assert_eq!(weights, vec![0.5, 0.5]);
+
+        shift_column_weight(&mut weights, 0, 0.2);
+        assert!((weights[0] - 0.7).abs() < 1e-6);
+        assert!((weights[1] - 0.3).abs() < 1e-6);
+
+        shift_column_weight(&mut weights, 0, 0.5);
+        assert!((weights[1] - MIN_COLUMN_FRACTION).abs() < 1e-6);
+        assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+
+        // A saved list for a different column count falls back to equal widths.
+        assert_eq!(column_weights(&[0.7, 0.3], 3).len(), 3);
+        assert_eq!(column_weights(&[3.0, 1.0], 2), vec![0.75, 0.25]);
+    }
+
+    #[test]
+    fn sole_pin_moves_between_columns_and_back() {
+        let mut apps = vec![make_app("A"), make_app("B")];
+        let columns = resolve_column_indices(&apps, None, 2);
+        assert_eq!(columns, vec![vec![0], vec![1]]);
+
+        // Drop A under B, leaving the left column empty.
+        let moved = vec![vec![], vec![1, 0]];
+        reorder_pinned_apps_by_columns(&mut apps, &moved);
+        let layout = grid_layout_from_lengths(&apps, &column_lengths(&moved));
+        let columns = resolve_column_indices(&apps, Some(&layout), 2);
+        assert_eq!(columns, vec![vec![], vec![0, 1]]);
+        assert_eq!(names(&apps), vec!["B", "A"]);
+
+        // And back into the empty column's slot 0.
+        let back = vec![vec![1], vec![0]];
+        reorder_pinned_apps_by_columns(&mut apps, &back);
+        let layout = grid_layout_from_lengths(&apps, &column_lengths(&back));
+        assert_eq!(
+            resolve_column_indices(&apps, Some(&layout), 2),
+            vec![vec![0], vec![1]]
+        );
+        assert_eq!(names(&apps), vec!["A", "B"]);
+    }
+
+    #[test]
+    fn hundred_pins_reorder_and_keep_their_layout() {
+        let mut apps: Vec<PinnedApp> = (0..100).map(|i| make_app(&format!("App{i:03}"))).collect();
+        let mut expected = names(&apps);
+        expected.sort();
+
+        let columns = resolve_column_indices(&apps, None, 4);
+        assert_eq!(column_lengths(&columns), vec![25, 25, 25, 25]);
+        reorder_pinned_apps_by_columns(&mut apps, &columns);
+        let layout = grid_layout_from_lengths(&apps, &column_lengths(&columns));
+
+        // Drag every tenth pin to the front, the way a list drop reorders.
+        for from in (10..100).step_by(10) {
+            let name = apps[from].name.clone();
+            let order: Vec<usize> = std::iter::once(from)
+                .chain((0..100).filter(|&idx| idx != from))
+                .collect();
+            let new_pos = reorder_pin_list(&mut apps, order).expect("pins moved");
+            assert_eq!(new_pos[from], 0);
+            assert_eq!(apps[0].name, name);
+        }
+        assert!(reorder_pin_list(&mut apps, (0..100).collect()).is_none());
+        let mut after = names(&apps);
+        after.sort();
+        assert_eq!(after, expected);
+
+        let restored = resolve_column_indices(&apps, Some(&layout), 4);
+        assert_eq!(column_lengths(&restored), vec![25, 25, 25, 25]);
+        let mut seen: Vec<usize> = restored.concat();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..100).collect::<Vec<_>>());
+
+        let rects: Vec<egui::Rect> = (0..apps.len())
+            .map(|i| {
+                let top = i as f32 * (ROW_HEIGHT + 5.0);
+                egui::Rect::from_min_size(egui::pos2(0.0, top), egui::vec2(200.0, ROW_HEIGHT))
+            })
+            .collect();
+        assert_eq!(slot_from_pointer(rects[73].center().y - 1.0, &rects), 73);
+        assert_eq!(slot_from_pointer(f32::MAX, &rects), 100);
+    }
+
+    #[test]
+    fn key_moves_swap_neighbours_and_keep_grid_columns() {
+        assert_eq!(key_move_target(0, false, 3), None);
+        assert_eq!(key_move_target(2, true, 3), None);
+        assert_eq!(key_move_target(1, false, 3), Some(0));
+
+        // Moving the last pin of the left column down crosses into the right
+        // one, and the columns keep their lengths.
+        let mut apps = vec![make_app("A"), make_app("B"), make_app("C")];
+        let lengths = [2, 1];
+        let target = key_move_target(1, true, apps.len()).unwrap();
+        apps.swap(1, target);
+        let layout = grid_layout_from_lengths(&apps, &lengths);
+        assert_eq!(names(&apps), vec!["A", "C", "B"]);
+        assert_eq!(
+            resolve_column_indices(&apps, Some(&layout), 2),
+            vec![vec![0, 1], vec![2]]
+        );
+    }
+
+    #[test]
+    fn grid_page_height_fits_whole_rows() {
+        assert_eq!(grid_page_height(1, 6.0), ROW_HEIGHT);
+        assert_eq!(grid_page_height(3, 6.0), ROW_HEIGHT * 3.0 + 12.0);
+        assert_eq!(grid_page_height(0, 6.0), ROW_HEIGHT);
+    }
+
+    #[test]
+    fn grid_window_size_fits_columns_and_rows() {
+        let min = egui::vec2(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT);
+        assert_eq!(grid_window_size(1, None, 10.0), min);
+        let four = grid_window_size(4, None, 10.0);
+        assert_eq!(
+            four.x,
+            4.0 * GRID_MIN_CELL_WIDTH + 3.0 * GRID_COL_GAP + 20.0
+        );
+        assert_eq!(four.y, MIN_WINDOW_HEIGHT);
+        let tall = grid_window_size(2, Some(8), 10.0);
+        assert_eq!(
+            tall.y,
+            HEADER_HEIGHT + grid_page_height(8, GRID_ROW_GAP) + 20.0
+        );
+    }
+
+    #[test]
+    fn find_column_slot_locates_both_columns() {
+        let columns = vec![vec![0, 2], vec![1, 3, 4]];
+
+        assert_eq!(find_column_slot(2, &columns), Some((0, 1)));
+        assert_eq!(find_column_slot(4, &columns), Some((1, 2)));
+        assert_eq!(find_column_slot(9, &columns), None);
+        assert_eq!(find_column_slot(0, &[vec![], vec![0]]), Some((1, 0)));
+    }
+
+    #[test]
+    fn drag_column_holds_inside_hysteresis_band() {
+        let boundary = 150.0;
+        let two = [boundary];
+
+        assert_eq!(pick_drag_column(boundary + 4.0, &two, 0), 0);
+        assert_eq!(pick_drag_column(boundary - 4.0, &two, 1), 1);
+        assert_eq!(pick_drag_column(boundary + 20.0, &two, 0), 1);
+        assert_eq!(pick_drag_column(boundary - 20.0, &two, 1), 0);
+        assert_eq!(pick_drag_column(10.0, &two, 0), 0);
+
+        // Jumping across a middle column lands on the column under the pointer.
+        let three = [100.0, 200.0];
+        assert_eq!(pick_drag_column(250.0, &three, 0), 2);
+        assert_eq!(pick_drag_column(205.0, &three, 1), 1);
+    }
+
+    #[test]
+    fn folder_keys_ignore_trailing_separator() {
+        assert_eq!(
+            normalize_launch_key(Path::new(r"C:\Tools\"), None, None),
+            normalize_launch_key(Path::new(r"C:\Tools"), None, None)
+        );
+        assert_eq!(normalize_path_key(Path::new(r"c:\")), r"c:\");
+        assert_eq!(normalize_path_key(Path::new("c:/tools//")), r"c:\tools");
+    }
+
+    #[test]
+    fn canonical_path_resolves_dot_dot_and_keeps_missing_paths() {
+        let base =
+            std::env::temp_dir().join(format!("float_dock_canonical_{}", std::process::id()));
+        let apps = base.join("Apps");
+        std::fs::create_dir_all(&apps).expect("create temp dir");
+        let app = apps.join("tool.exe");
+        std::fs::write(&app, b"MZ").expect("write temp file");
+
+        let roundabout = apps.join("..").join("Apps").join("tool.exe");
+        let canonical = crate::system::canonical_path(&roundabout);
+        assert_eq!(canonical, crate::system::canonical_path(&app));
+        assert!(!canonical
+            .components()
+            .any(|c| c == std::path::Component::ParentDir));
+        assert!(!canonical.to_string_lossy().starts_with(r"\\?\"));
+
+        let missing = base.join("gone").join("..").join("tool.exe");
+        assert_eq!(crate::system::canonical_path(&missing), missing);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn launch_keys_ignore_slash_style_and_extended_prefix() {
+        let key = normalize_launch_key(
+            Path::new(r"C:\Tools\app.exe"),
+            Some(" --x "),
+            Some(Path::new(r"C:\Work\")),
+        );
+        assert_eq!(
+            normalize_launch_key(
+                Path::new("C:/Tools/app.exe"),
+                Some("--x"),
+                Some(Path::new("C:/Work")),
+            ),
+            key
+        );
+        assert_eq!(
+            normalize_launch_key(
+                Path::new(r"\\?\C:\Tools\\app.exe"),
+                Some("--x"),
+                Some(Path::new(r"C:\Work")),
+            ),
+            key
+        );
+
+        // UNC shares keep their leading pair, with or without the long prefix.
+        assert_eq!(
+            normalize_path_key(Path::new(r"\\?\UNC\server\share\app.exe")),
+            r"\\server\share\app.exe"
+        );
+        assert_eq!(
+            normalize_path_key(Path::new("//server/share/")),
+            r"\\server\share"
+        );
+    }
+
+    #[test]
+    fn saved_layout_matches_pins_added_with_other_slashes() {
+        let apps = vec![make_app("a"), make_app("b")];
+        let layout = GridLayout {
+            columns: vec![
+                vec![TwoColumnEntry::from_launch(
+                    PathBuf::from("C:/Apps/b.exe"),
+                    None,
+                    None,
+                )],
+                vec![TwoColumnEntry::from_launch(
+                    PathBuf::from(r"\\?\C:\Apps\a.exe"),
+                    None,
+                    None,
+                )],
+            ],
+        };
+        assert_eq!(
+            resolve_column_indices(&apps, Some(&layout), 2),
+            [vec![1], vec![0]]
+        );
+    }
+
+    #[test]
+    fn opener_gets_the_quoted_target_and_pin_args() {
+        let mut app = PinnedApp::new(PathBuf::from(r"C:\Work\"), None, None, None);
+        assert_eq!(launch_command(&app), (PathBuf::from(r"C:\Work\"), None));
+
+        app.opener = Some(PathBuf::from(r"C:\Tools\code.exe"));
+        assert_eq!(
+            launch_command(&app),
+            (
+                PathBuf::from(r"C:\Tools\code.exe"),
+                Some(r#""C:\Work\\""#.to_string())
+            )
+        );
+
+        app.path = PathBuf::from(r"C:\Docs\notes.md");
+        app.launch_args = Some("--new-window".to_string());
+        assert_eq!(
+            launch_command(&app).1.as_deref(),
+            Some(r#""C:\Docs\notes.md" --new-window"#)
+        );
+    }
+
+    #[test]
+    fn command_pins_split_into_program_and_rest() {
+        let mut app = PinnedApp::new(
+            PathBuf::from(r#"  "C:\Program Files\PowerShell\pwsh.exe"  -c "Get-Date"  "#),
+            None,
+            None,
+            None,
+        );
+        app.command = true;
+        // A command pin never goes through an opener.
+        app.opener = Some(PathBuf::from(r"C:\Tools\code.exe"));
+        assert_eq!(
+            launch_command(&app),
+            (
+                PathBuf::from(r"C:\Program Files\PowerShell\pwsh.exe"),
+                Some(r#"-c "Get-Date""#.to_string())
+            )
+        );
+
+        app.path = PathBuf::from("cmd /c backup.bat");
+        assert_eq!(
+            launch_command(&app),
+            (PathBuf::from("cmd"), Some("/c backup.bat".to_string()))
+        );
+        app.path = PathBuf::from("notepad");
+        assert_eq!(launch_command(&app), (PathBuf::from("notepad"), None));
+
+        assert_eq!(crate::system::split_program("   "), None);
+        assert_eq!(crate::system::split_program(r#""" --flag"#), None);
+    }
+
+    #[test]
+    fn launches_reach_the_launcher_with_opener_args_and_working_dir() {
+        use super::super::launcher::{Launch, RecordingLauncher};
+        let launcher = RecordingLauncher::default();
+
+        let mut app = PinnedApp::new(
+            PathBuf::from(r"C:\Apps\tool.exe"),
+            None,
+            Some("--fast".to_string()),
+            Some(PathBuf::from(r"C:\Work")),
+        );
+        launch_app(&launcher, &app, false).unwrap();
+        app.opener = Some(PathBuf::from(r"C:\Tools\code.exe"));
+        app.path = PathBuf::from(r"C:\Notes\");
+        app.env = vec![("RUST_LOG".to_string(), "debug".to_string())];
+        app.show_state = ShowState::Minimized;
+        launch_app(&launcher, &app, false).unwrap();
+        launch_app(&launcher, &app, true).unwrap();
+
+        assert_eq!(
+            *launcher.launches.lock().unwrap(),
+            [
+                Launch {
+                    path: PathBuf::from(r"C:\Apps\tool.exe"),
+                    args: Some("--fast".to_string()),
+                    working_dir: Some(PathBuf::from(r"C:\Work")),
+                    env: Vec::new(),
+                    show: ShowState::Normal,
+                    elevated: false,
+                },
+                Launch {
+                    path: PathBuf::from(r"C:\Tools\code.exe"),
+                    args: Some(r#""C:\Notes\\" --fast"#.to_string()),
+                    working_dir: Some(PathBuf::from(r"C:\Work")),
+                    env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+                    show: ShowState::Minimized,
+                    elevated: false,
+                },
+                // Elevated: same command, without the env or window state.
+                Launch {
+                    path: PathBuf::from(r"C:\Tools\code.exe"),
+                    args: Some(r#""C:\Notes\\" --fast"#.to_string()),
+                    working_dir: Some(PathBuf::from(r"C:\Work")),
+                    env: Vec::new(),
+                    show: ShowState::Normal,
+                    elevated: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn launch_options_check_env_and_start_in() {
+        let dir = std::env::temp_dir();
+        let mut draft = LaunchOptionsDraft {
+            key: String::new(),
+            name: "tool".to_string(),
+            args: "  --fast  ".to_string(),
+            working_dir: format!(" {} ", dir.display()),
+            env: "A=1\n\n B = two=2 ".to_string(),
+            takes_env: true,
+            show_state: ShowState::Normal,
+        };
+        assert_eq!(
+            launch_options_from_draft(&draft),
+            Ok(LaunchOptions {
+                args: Some("--fast".to_string()),
+                working_dir: Some(dir.clone()),
+                env: vec![
+                    ("A".to_string(), "1".to_string()),
+                    ("B".to_string(), " two=2".to_string()),
+                ],
+            })
+        );
+
+        draft.env = "A=1\n=nameless".to_string();
+        assert_eq!(
+            launch_options_from_draft(&draft),
+            Err("Not a NAME=value line: =nameless".to_string())
+        );
+        // Folders and documents take no environment, so theirs is dropped.
+        draft.takes_env = false;
+        assert_eq!(launch_options_from_draft(&draft).unwrap().env, []);
+
+        draft.working_dir = dir.join("float_dock_missing_dir").display().to_string();
+        assert_eq!(
+            launch_options_from_draft(&draft),
+            Err("Start-in folder not found".to_string())
+        );
+    }
+
+    #[test]
+    fn failed_launches_report_through_the_launcher() {
+        use super::super::launcher::RecordingLauncher;
+        let launcher = RecordingLauncher {
+            fail_with: Some(LaunchError::AccessDenied),
+            ..Default::default()
+        };
+        let app = make_app("tool");
+
+        let err = launch_app(&launcher, &app, true).unwrap_err();
+        assert_eq!(
+            launch_failure_toast(&app.name, true, err),
+            (Severity::Warning, "Elevated launch cancelled".to_string())
+        );
+        let err = launch_app(&launcher, &app, false).unwrap_err();
+        assert_eq!(
+            launch_failure_toast(&app.name, false, err).0,
+            Severity::Error
+        );
+        assert_eq!(launcher.launches.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn folders_gather_their_pins_and_dissolve_below_two() {
+        let apps: Vec<PinnedApp> = ["a", "b", "c", "d", "e"].map(make_app).into();
+        let folder = |name: &str, members: &[&str]| PinGroup {
+            name: name.to_string(),
+            expanded: true,
+            members: members.iter().map(|m| make_entry(m)).collect(),
+            launch_delay_ms: None,
+        };
+        let groups = prune_folders(
+            &apps,
+            vec![
+                folder("Tools", &["d", "b", "gone"]),
+                folder("Solo", &["c", "gone"]),
+                folder("Late", &["b", "e", "a"]),
+            ],
+        );
+
+        assert_eq!(
+            groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(),
+            ["Tools", "Late"]
+        );
+        assert_eq!(groups[0].members, [make_entry("d"), make_entry("b")]);
+        assert_eq!(groups[1].members, [make_entry("e"), make_entry("a")]);
+        assert_eq!(
+            pin_folders(&apps, &groups),
+            [Some(1), Some(0), None, Some(0), Some(1)]
+        );
+        // Each folder moves up to its first pin, in the folder's own order.
+        assert_eq!(folder_order(&apps, &groups), [4, 0, 3, 1, 2]);
+        assert_eq!(next_folder_name(&groups), "Folder");
+    }
+
+    #[test]
+    fn pin_dropped_inside_a_folder_joins_at_that_slot() {
+        let mut apps = vec![make_app("A"), make_app("B"), make_app("C"), make_app("D")];
+        let mut folder = PinGroup {
+            name: "Tools".to_string(),
+            expanded: true,
+            members: vec![make_entry("B"), make_entry("C")],
+            launch_delay_ms: None,
         };
 
-        let (left, right) = resolve_two_column_indices(&apps, Some(&saved_layout));
-        assert_eq!(left, vec![0, 1, 4]);
-        assert_eq!(right, vec![2, 3]);
+        // D dropped between B and C.
+        let item = apps.remove(3);
+        insert_folder_member(&mut folder, make_entry("D"), &apps, 2);
+        apps.insert(2, item);
+
+        assert_eq!(
+            folder.members,
+            vec![make_entry("B"), make_entry("D"), make_entry("C")]
+        );
+        assert_eq!(names(&apps), vec!["A", "B", "D", "C"]);
+        assert_eq!(folder_order(&apps, &[folder]), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn pin_dragged_within_its_folder_moves_inside_it() {
+        let mut apps = vec![make_app("A"), make_app("B"), make_app("C"), make_app("D")];
+        let mut groups = vec![PinGroup {
+            name: "Tools".to_string(),
+            expanded: true,
+            members: vec![make_entry("B"), make_entry("C"), make_entry("D")],
+            launch_delay_ms: None,
+        }];
+
+        // D dragged up to the folder's first slot.
+        leave_folders(&mut groups, &make_entry("D"));
+        let item = apps.remove(3);
+        insert_folder_member(&mut groups[0], make_entry("D"), &apps, 1);
+        apps.insert(1, item);
+
+        assert_eq!(
+            groups[0].members,
+            vec![make_entry("D"), make_entry("B"), make_entry("C")]
+        );
+        assert_eq!(names(&apps), vec!["A", "D", "B", "C"]);
+        assert_eq!(folder_order(&apps, &groups), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn pin_dragged_out_of_its_folder_lands_at_top_level() {
+        let mut apps = vec![make_app("A"), make_app("B"), make_app("C"), make_app("D")];
+        let mut groups = vec![PinGroup {
+            name: "Tools".to_string(),
+            expanded: true,
+            members: vec![make_entry("B"), make_entry("C"), make_entry("D")],
+            launch_delay_ms: None,
+        }];
+
+        // C dragged below the list, outside any folder.
+        leave_folders(&mut groups, &make_entry("C"));
+        let item = apps.remove(2);
+        apps.insert(3, item);
+
+        assert_eq!(groups[0].members, vec![make_entry("B"), make_entry("D")]);
+        assert_eq!(names(&apps), vec!["A", "B", "D", "C"]);
+        assert_eq!(
+            pin_folders(&apps, &groups),
+            vec![None, Some(0), Some(0), None]
+        );
+    }
+
+    #[test]
+    fn env_lines_parse_and_format_back() {
+        let env = parse_env_lines("RUST_LOG=debug\n\n  PATH_EXTRA = C:\\bin \nEMPTY=\n").unwrap();
+        assert_eq!(
+            env,
+            [
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("PATH_EXTRA".to_string(), " C:\\bin".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(parse_env_lines(&format_env_lines(&env)).unwrap(), env);
+        assert_eq!(parse_env_lines("=oops"), Err("=oops".to_string()));
+        assert_eq!(parse_env_lines("NOVALUE"), Err("NOVALUE".to_string()));
+    }
+
+    #[test]
+    fn favorites_fill_the_first_column_without_moving_saved_pins() {
+        let flags = [false, true, false, true, false];
+        assert_eq!(favorites_first_order(&flags), [1, 3, 0, 2, 4]);
+
+        let apps: Vec<PinnedApp> = ["a", "b", "c", "d", "e"].map(make_app).into();
+        let layout = grid_layout_from_lengths(&apps, &[2, 3]);
+        let columns = resolve_column_indices(&apps, Some(&layout), 2);
+        assert_eq!(
+            favorites_first_columns(&columns, &flags),
+            vec![vec![1, 3], vec![0, 2, 4]]
+        );
+        assert_eq!(columns, vec![vec![0, 1], vec![2, 3, 4]]);
+    }
+
+    #[test]
+    fn dragged_pin_lands_beside_its_neighbour_in_the_saved_order() {
+        // Saved a B c D e, drawn B D a c e with B and D favorites.
+        let flags = [false, true, false, true, false];
+        let stored = [0, 1, 2, 3, 4];
+        // e dragged above a: it goes in front of a.
+        assert_eq!(
+            place_dragged_pin(&stored, &[1, 3, 4, 0, 2], 4, &flags),
+            [4, 0, 1, 2, 3]
+        );
+        // B dragged below D, the last favorite: it follows D.
+        assert_eq!(
+            place_dragged_pin(&stored, &[3, 1, 0, 2, 4], 1, &flags),
+            [0, 2, 3, 1, 4]
+        );
+        // With favorites-first off every pin is in the same group.
+        assert_eq!(
+            place_dragged_pin(&stored, &[0, 1, 2, 4, 3], 4, &[false; 5]),
+            [0, 1, 2, 4, 3]
+        );
+    }
+
+    #[test]
+    fn snap_axis_lands_flush_above_taskbar() {
+        // 1080px monitor with a 40px taskbar at the bottom.
+        let work_y = egui::Rangef::new(0.0, 1040.0);
+
+        assert_eq!(
+            snap_axis(1040.0 - 600.0 + 20.0, 600.0, work_y, 48.0),
+            Some(440.0)
+        );
+        assert_eq!(snap_axis(30.0, 600.0, work_y, 48.0), Some(0.0));
+        assert_eq!(snap_axis(200.0, 600.0, work_y, 48.0), None);
+    }
+
+    #[test]
+    fn aspect_lock_derives_the_other_side_within_limits() {
+        let min = egui::vec2(200.0, 180.0);
+        let max = egui::vec2(1000.0, 800.0);
+
+        assert_eq!(
+            lock_aspect(egui::vec2(400.0, 300.0), 1.0, true, min, max),
+            egui::vec2(400.0, 400.0)
+        );
+        assert_eq!(
+            lock_aspect(egui::vec2(400.0, 300.0), 2.0, false, min, max),
+            egui::vec2(600.0, 300.0)
+        );
+        // The derived height would pass the max, so the width gives way.
+        assert_eq!(
+            lock_aspect(egui::vec2(950.0, 300.0), 1.0, true, min, max),
+            egui::vec2(800.0, 800.0)
+        );
+        // And the minimum holds even when the dragged side goes below it.
+        assert_eq!(
+            lock_aspect(egui::vec2(200.0, 500.0), 2.0, true, min, max),
+            egui::vec2(360.0, 180.0)
+        );
+    }
+
+    #[test]
+    fn top_edges_keep_the_bottom_edge_in_place() {
+        let start = egui::pos2(100.0, 200.0);
+        let size = egui::vec2(300.0, 400.0);
+        let taller = egui::vec2(300.0, 430.0);
+        assert_eq!(
+            resized_origin(ResizeEdge::Top, start, size, taller),
+            egui::pos2(100.0, 170.0)
+        );
+        assert_eq!(
+            resized_origin(ResizeEdge::TopLeft, start, size, egui::vec2(320.0, 430.0)),
+            egui::pos2(80.0, 170.0)
+        );
+        assert_eq!(
+            resized_origin(ResizeEdge::TopRight, start, size, egui::vec2(320.0, 430.0)),
+            egui::pos2(100.0, 170.0)
+        );
+        assert_eq!(
+            resized_origin(ResizeEdge::Bottom, start, size, taller),
+            start
+        );
+        assert_eq!(
+            resize_edge_sides(ResizeEdge::Top),
+            (false, false, true, false)
+        );
+    }
+
+    #[test]
+    fn left_edge_drag_delta_holds_while_the_window_catches_up() {
+        let start_mouse = egui::pos2(100.0, 300.0);
+        // The mouse moved 40 points left; the window hasn't followed yet.
+        let before = resize_pointer_delta(
+            egui::pos2(100.0, 200.0),
+            egui::pos2(-40.0, 100.0),
+            start_mouse,
+        );
+        // Once the origin lands, the pointer sits at the edge again.
+        let after =
+            resize_pointer_delta(egui::pos2(60.0, 200.0), egui::pos2(0.0, 100.0), start_mouse);
+        assert_eq!(before, egui::vec2(-40.0, 0.0));
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn clamp_keeps_header_and_rows_on_screen_for_tall_windows() {
+        let monitor = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1920.0, 1080.0));
+        let tall = egui::vec2(360.0, 1600.0);
+
+        // Dragged far down: the header and two rows stay above the bottom edge.
+        let pos = clamp_window_origin(egui::pos2(100.0, 2000.0), tall, monitor);
+        assert_eq!(pos, egui::pos2(100.0, 1080.0 - MIN_VISIBLE_HEIGHT));
+        // Dragged up: the top edge, and with it the header, never leaves.
+        let pos = clamp_window_origin(egui::pos2(100.0, -300.0), tall, monitor);
+        assert_eq!(pos.y, 0.0);
+        // Off either side, a strip of the window stays reachable.
+        let pos = clamp_window_origin(egui::pos2(-1000.0, 10.0), tall, monitor);
+        assert_eq!(pos.x, MIN_VISIBLE_WIDTH - tall.x);
+        let pos = clamp_window_origin(egui::pos2(5000.0, 10.0), tall, monitor);
+        assert_eq!(pos.x, 1920.0 - MIN_VISIBLE_WIDTH);
+
+        // A collapsed window shorter than the minimum stays fully visible.
+        let short = egui::vec2(360.0, HEADER_HEIGHT);
+        let pos = clamp_window_origin(egui::pos2(0.0, 2000.0), short, monitor);
+        assert_eq!(pos.y, 1080.0 - HEADER_HEIGHT);
+    }
+
+    #[test]
+    fn clamp_uses_the_whole_virtual_desktop() {
+        // A second monitor to the left of the primary, taller and offset up.
+        let desktop =
+            egui::Rect::from_min_max(egui::pos2(-1280.0, -200.0), egui::pos2(1920.0, 1240.0));
+        let size = egui::vec2(360.0, 600.0);
+
+        let pos = clamp_window_origin(egui::pos2(-900.0, -100.0), size, desktop);
+        assert_eq!(pos, egui::pos2(-900.0, -100.0));
+        let pos = clamp_window_origin(egui::pos2(-2000.0, -500.0), size, desktop);
+        assert_eq!(
+            pos,
+            egui::pos2(-1280.0 + MIN_VISIBLE_WIDTH - size.x, -200.0)
+        );
+        let pos = clamp_window_origin(egui::pos2(0.0, 1500.0), size, desktop);
+        assert_eq!(pos.y, 1240.0 - MIN_VISIBLE_HEIGHT);
     }
 }
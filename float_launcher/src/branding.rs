@@ -1,3 +1,6 @@
 pub const APP_DISPLAY_NAME: &str = "Float Dock";
+// Value names under the `Run` registry key; only the Win32 backend uses them.
+#[cfg(windows)]
 pub const APP_AUTOSTART_VALUE: &str = "FloatDock";
+#[cfg(windows)]
 pub const LEGACY_AUTOSTART_VALUE: &str = "FloatLauncher";
@@ -1,14 +1,78 @@
+use crate::system::ShowState;
 use log::warn;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WindowShape {
-    Circle,
+    /// Fully rounded ends (a stadium); saved as "Circle" by older builds.
+    #[serde(alias = "Circle")]
+    Pill,
     Square,
     RoundedRect,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeMode {
+    /// High contrast while the Windows high-contrast setting is on.
+    #[default]
+    System,
+    Standard,
+    HighContrast,
+}
+
+/// How much the panel decorates itself around the pins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Appearance {
+    /// Soft glows behind the header and roomy padding.
+    #[default]
+    Decorative,
+    /// No glows and tighter padding, for a denser dock.
+    Minimal,
+}
+
+/// What `validate_and_repair` does with pins whose target is gone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissingPins {
+    /// Leave them in place, dimmed until the target is back (a network
+    /// drive that isn't mounted yet, say).
+    #[default]
+    Keep,
+    /// Move them to `broken_pins` on load.
+    SetAside,
+}
+
+/// Where the dock sits in the window stack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DockLevel {
+    Normal,
+    #[default]
+    AlwaysOnTop,
+    /// Below every normal window, just above the wallpaper, like a widget.
+    Desktop,
+}
+
+/// How the dock leaves the screen when it goes to the tray. Either way it
+/// stays out of Alt-Tab while hidden.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HideMethod {
+    #[default]
+    Minimize,
+    /// Park the window off-screen; it keeps running, so tray picks and
+    /// hotkeys still reach it.
+    Hide,
+}
+
+/// Which click on a row launches it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivationMode {
+    SingleClick,
+    #[default]
+    DoubleClick,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TwoColumnEntry {
     pub path: PathBuf,
@@ -36,39 +100,324 @@ impl TwoColumnEntry {
     }
 }
 
+/// Animation lengths in milliseconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct AnimationConfig {
+    pub panel_ms: u64,
+    pub drop_ms: u64,
+    pub fade_in_ms: u64,
+    pub fade_out_ms: u64,
+    pub flash_ms: u64,
+    /// Bounce on a pin's icon after it launches.
+    pub launch_ms: u64,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            panel_ms: 220,
+            drop_ms: 200,
+            fade_in_ms: 160,
+            fade_out_ms: 150,
+            flash_ms: 160,
+            launch_ms: 420,
+        }
+    }
+}
+
+impl AnimationConfig {
+    /// Every animation at half its default length.
+    pub fn snappy() -> Self {
+        let normal = Self::default();
+        Self {
+            panel_ms: normal.panel_ms / 2,
+            drop_ms: normal.drop_ms / 2,
+            fade_in_ms: normal.fade_in_ms / 2,
+            fade_out_ms: normal.fade_out_ms / 2,
+            flash_ms: normal.flash_ms / 2,
+            launch_ms: normal.launch_ms / 2,
+        }
+    }
+
+    pub fn panel(&self) -> Duration {
+        millis(self.panel_ms)
+    }
+
+    pub fn drop(&self) -> Duration {
+        millis(self.drop_ms)
+    }
+
+    pub fn fade_in(&self) -> Duration {
+        millis(self.fade_in_ms)
+    }
+
+    pub fn fade_out(&self) -> Duration {
+        millis(self.fade_out_ms)
+    }
+
+    pub fn flash(&self) -> Duration {
+        millis(self.flash_ms)
+    }
+
+    pub fn launch(&self) -> Duration {
+        millis(self.launch_ms)
+    }
+}
+
+/// Never zero, so progress math doesn't divide by it.
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms.max(1))
+}
+
+/// Last window placement on one monitor.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowGeometry {
+    pub pos: (f32, f32),
+    pub size: (f32, f32),
+}
+
+/// Saved grid arrangement, one entry list per column (left to right).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
-pub struct TwoColumnLayout {
-    #[serde(default)]
-    pub left: Vec<TwoColumnEntry>,
+#[serde(from = "GridLayoutCompat")]
+pub struct GridLayout {
+    pub columns: Vec<Vec<TwoColumnEntry>>,
+}
+
+/// Accepts both the current `columns` form and the old `{ left, right }` two-column form.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GridLayoutCompat {
+    Columns {
+        columns: Vec<Vec<TwoColumnEntry>>,
+    },
+    TwoColumn {
+        #[serde(default)]
+        left: Vec<TwoColumnEntry>,
+        #[serde(default)]
+        right: Vec<TwoColumnEntry>,
+    },
+}
+
+impl From<GridLayoutCompat> for GridLayout {
+    fn from(raw: GridLayoutCompat) -> Self {
+        match raw {
+            GridLayoutCompat::Columns { columns } => Self { columns },
+            GridLayoutCompat::TwoColumn { left, right } => Self {
+                columns: vec![left, right],
+            },
+        }
+    }
+}
+
+/// Pins shown together under one folder row in the list. Members are the
+/// pins' launch entries in display order; the pins themselves stay in
+/// `pinned_apps`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PinGroup {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub expanded: bool,
+    pub members: Vec<TwoColumnEntry>,
+    /// Pause between members on "Launch all"; `None` uses the global
+    /// `launch_delay_ms`.
     #[serde(default)]
-    pub right: Vec<TwoColumnEntry>,
+    pub launch_delay_ms: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AppConfig {
-    #[serde(default, deserialize_with = "deserialize_pinned_apps")]
+/// Settings that switch with the profile: the pins and how the dock lays
+/// out and draws them. Everything else in `AppConfig` is shared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// Shown in the profile menus; see `Profile::label`.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
     pub pinned_apps: Vec<PathBuf>,
     #[serde(default)]
     pub pinned_launch_meta: Vec<PinnedLaunchMeta>,
-    pub shape: WindowShape,
-    pub last_pos: Option<(f32, f32)>,
-    #[serde(default)]
-    pub last_size: Option<(f32, f32)>,
     #[serde(default)]
     pub quick_launch_app: Option<PathBuf>,
+    /// Folders of the single-column list; the grid shows their pins loose.
+    #[serde(default)]
+    pub pin_groups: Vec<PinGroup>,
+    /// Pins taken out on load because their target was gone, with their
+    /// settings, so they can be put back from the menu.
+    #[serde(default)]
+    pub broken_pins: Vec<PinnedLaunchMeta>,
+    /// List scroll position when the dock was last hidden or closed.
+    #[serde(default)]
+    pub scroll_offset: f32,
+    /// Keep favorite pins above the rest, in their manual order.
+    #[serde(default)]
+    pub favorites_first: bool,
+    /// Lay pins out in `grid_cols` columns instead of a single list.
     #[serde(default)]
-    pub two_column_mode: bool,
+    pub grid_mode: bool,
     #[serde(default)]
-    pub two_column_layout: Option<TwoColumnLayout>,
+    pub grid_layout: Option<GridLayout>,
     #[serde(default = "default_icon_size")]
     pub icon_size: u32,
     #[serde(default = "default_grid_cols")]
     pub grid_cols: u32,
+    /// Rows shown before the grid scrolls; 0 fills the window.
     #[serde(default = "default_grid_rows")]
     pub grid_rows: u32,
+    /// Relative grid column widths set by dragging the dividers; empty (or a
+    /// stale length after the column count changed) means equal widths.
+    #[serde(default)]
+    pub column_weights: Vec<f32>,
+    pub shape: WindowShape,
+    #[serde(default)]
+    pub theme: ThemeMode,
+    #[serde(default)]
+    pub appearance: Appearance,
+    /// Blur the desktop behind the panel (Windows 11 22H2 and later).
+    #[serde(default)]
+    pub acrylic: bool,
+    /// Let the system draw the drop shadow instead of painting one.
+    #[serde(default)]
+    pub system_shadow: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Bumped whenever a saved field changes shape; `migrate_config` upgrades
+/// older files on load.
+pub const CONFIG_VERSION: u32 = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppConfig {
+    /// Layout the file was written with; 0 for files from before versioning.
+    #[serde(default)]
+    pub version: u32,
+    /// The active profile's pins, layout and appearance, kept at the top
+    /// level of the file as before profiles existed.
+    #[serde(flatten)]
+    pub profile: Profile,
+    /// Every profile, the active one at `active_profile`; empty while there is
+    /// only the one. The active slot is rewritten from `profile` on save.
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub active_profile: usize,
+    pub last_pos: Option<(f32, f32)>,
+    #[serde(default)]
+    pub last_size: Option<(f32, f32)>,
+    /// Geometry keyed by monitor device name; `last_pos`/`last_size` remain the fallback.
+    #[serde(default)]
+    pub monitor_geometry: BTreeMap<String, WindowGeometry>,
+    /// Put the window back at its saved position and size every time it is
+    /// shown, undoing moves by other tools or DPI changes while hidden.
+    #[serde(default)]
+    pub apply_size_on_show: bool,
+    /// Keep `aspect_ratio` while resizing; Shift flips it for one drag.
+    #[serde(default)]
+    pub aspect_lock: bool,
+    /// Width over height the aspect lock holds; 1.0 keeps the window square.
+    #[serde(default = "default_aspect_ratio")]
+    pub aspect_ratio: f32,
+    #[serde(default)]
+    pub collapsed: bool,
+    #[serde(default)]
+    pub start_minimized: bool,
+    /// Whether the dock was in the tray when last hidden or closed; the next
+    /// launch starts hidden too unless it was booted with `--hidden` anyway.
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub confirm_quit: bool,
+    #[serde(default)]
+    pub window_level: DockLevel,
+    #[serde(default)]
+    pub hide_method: HideMethod,
+    /// Activate the dock when it's shown; off leaves the keyboard with the
+    /// app in front, except for the search hotkey, which needs it.
+    #[serde(default = "default_true")]
+    pub focus_on_show: bool,
+    /// Step out of the way while a fullscreen game or video is in front.
+    #[serde(default)]
+    pub hide_in_fullscreen: bool,
+    /// Zoom on top of the monitor's own scaling, for mixed-DPI setups where
+    /// the OS factor leaves the dock too small or too large. See `ui_scale()`.
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Spin the tray icon for a moment after a launch, as a sign it's underway.
+    #[serde(default = "default_true")]
+    pub animate_tray_on_launch: bool,
+    /// Leave the dock out of screenshots and shared screens.
+    #[serde(default)]
+    pub exclude_from_capture: bool,
+    /// Ask before elevated (Shift-click) launches and pins on network paths,
+    /// so a stray click doesn't raise a UAC prompt or wait on a share.
+    #[serde(default)]
+    pub confirm_risky_launch: bool,
+    /// Snap the dock to screen edges when a window drag ends near one.
+    #[serde(default = "default_true")]
+    pub snap_enabled: bool,
+    /// How close to an edge, in points, a drag has to end to snap.
+    #[serde(default = "default_snap_threshold")]
+    pub snap_threshold: f32,
+    #[serde(default = "default_true")]
+    pub snap_to_work_area: bool,
+    /// Skip or flatten animations; `None` follows the Windows animation setting.
+    #[serde(default)]
+    pub reduce_motion: Option<bool>,
+    /// Most pins the dock accepts; see `pin_limit()`.
+    #[serde(default = "default_pin_limit")]
+    pub pin_limit: usize,
+    /// Free slots left at which the header shows the pin count and adding warns.
+    #[serde(default = "default_pin_warning_slots")]
+    pub pin_warning_slots: usize,
+    /// How long a row must be hovered before its details card appears.
+    #[serde(default = "default_hover_card_delay_ms")]
+    pub hover_card_delay_ms: u64,
+    /// Press-and-hold time before a row lifts for reordering; 0 lifts immediately.
+    #[serde(default = "default_reorder_hold_ms")]
+    pub reorder_hold_ms: u64,
+    /// Pointer travel (points) during the hold that cancels the reorder press.
+    #[serde(default = "default_reorder_move_tolerance")]
+    pub reorder_move_tolerance: f32,
+    /// Reorder list rows from a grip on their left edge instead of by long-press.
+    #[serde(default)]
+    pub reorder_handle: bool,
+    #[serde(default)]
+    pub activate_on: ActivationMode,
+    /// Pause between apps when a selection is launched together, so heavy
+    /// apps don't all hit the disk at once.
+    #[serde(default)]
+    pub launch_delay_ms: u64,
+    /// Accept any existing file as a pin, not just `.exe`/`.lnk`/folders.
+    #[serde(default = "default_true")]
+    pub pin_any_file: bool,
+    #[serde(default)]
+    pub animation: AnimationConfig,
+    /// Program "Open in terminal here" launches with the folder as working directory.
+    #[serde(default = "default_terminal")]
+    pub terminal: String,
+    /// Font files loaded after egui's own fonts, in order, for glyphs those
+    /// lack (CJK, Thai, symbols). Missing files are skipped.
+    #[serde(default = "default_font_fallbacks")]
+    pub font_fallbacks: Vec<PathBuf>,
+    /// Most detailed level written to the log file: `error`, `warn`, `info`,
+    /// `debug`, `trace` or `off`. See `log_level()`.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    #[serde(default)]
+    pub missing_pins: MissingPins,
+    /// Key that switches to the next profile when pressed with
+    /// Ctrl+Alt+Shift: a letter, a digit or `F1`–`F24`. Empty turns the
+    /// hotkey off. Read at startup; see `next_profile_vk()`.
+    #[serde(default = "default_next_profile_key")]
+    pub next_profile_key: String,
+    /// What `load` had to repair; never saved.
+    #[serde(skip)]
+    pub repairs: RepairReport,
+    /// Keys this build doesn't know, kept so that saving doesn't drop
+    /// settings written by a newer version.
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PinnedLaunchMeta {
     pub path: PathBuf,
     #[serde(default)]
@@ -77,61 +426,702 @@ pub struct PinnedLaunchMeta {
     pub args: Option<String>,
     #[serde(default)]
     pub working_dir: Option<PathBuf>,
+    /// The shortcut this pin was added from; `path` is what it resolved to.
+    #[serde(default)]
+    pub shortcut: Option<PathBuf>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Program that opens this file or folder instead of its default handler.
+    #[serde(default)]
+    pub opener: Option<PathBuf>,
+    /// `path` is a command line to run rather than a file on disk.
+    #[serde(default)]
+    pub command: bool,
+    /// Variables added to the environment the pin launches with. Not part
+    /// of the pin's identity: two pins differing only here are duplicates.
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    /// How the pin's window comes up: normal, minimized (for tools that run
+    /// in the background) or maximized.
+    #[serde(default)]
+    pub show_state: ShowState,
+}
+
+impl AppConfig {
+    /// Number of columns pins are laid out in; 1 means the plain list.
+    pub fn column_count(&self) -> usize {
+        if self.profile.grid_mode {
+            (self.profile.grid_cols as usize).clamp(1, MAX_GRID_COLS)
+        } else {
+            1
+        }
+    }
+
+    /// The configured log level; anything unrecognized logs at `info`.
+    pub fn log_level(&self) -> log::LevelFilter {
+        self.log_level
+            .trim()
+            .parse()
+            .unwrap_or(log::LevelFilter::Info)
+    }
+
+    /// Snap distance in points, or `None` when snapping is off.
+    pub fn snap_threshold(&self) -> Option<f32> {
+        (self.snap_enabled && self.snap_threshold > 0.0)
+            .then(|| self.snap_threshold.min(MAX_SNAP_THRESHOLD))
+    }
+
+    /// The configured aspect ratio, kept to something a window can take.
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0 {
+            self.aspect_ratio
+                .clamp(MIN_ASPECT_RATIO, 1.0 / MIN_ASPECT_RATIO)
+        } else {
+            default_aspect_ratio()
+        }
+    }
+
+    /// Ratio resizes hold, or `None` when the lock is off.
+    pub fn locked_aspect(&self) -> Option<f32> {
+        self.aspect_lock.then(|| self.aspect_ratio())
+    }
+
+    /// `ui_scale` kept within `MIN_UI_SCALE..=MAX_UI_SCALE`.
+    pub fn ui_scale(&self) -> f32 {
+        clamp_ui_scale(self.ui_scale)
+    }
+
+    pub fn pin_limit(&self) -> usize {
+        self.pin_limit.clamp(1, MAX_PIN_LIMIT)
+    }
+
+    /// Rows of the grid visible at once, or `None` to use the full window height.
+    pub fn visible_grid_rows(&self) -> Option<usize> {
+        match self.profile.grid_rows {
+            0 => None,
+            rows => Some((rows as usize).min(MAX_GRID_ROWS)),
+        }
+    }
+
+    /// Saved position and size for `monitor`, falling back to the legacy single entry.
+    pub fn geometry_for(&self, monitor: Option<&str>) -> Option<WindowGeometry> {
+        monitor
+            .and_then(|id| self.monitor_geometry.get(id).copied())
+            .or_else(|| {
+                self.last_pos
+                    .zip(self.last_size)
+                    .map(|(pos, size)| WindowGeometry { pos, size })
+            })
+    }
+
+    /// `next_profile_key` as a virtual-key code, or `None` when it's empty or
+    /// not a key the hotkey can use.
+    pub fn next_profile_vk(&self) -> Option<u32> {
+        hotkey_vk(&self.next_profile_key)
+    }
+
+    /// Profiles to pick from; 1 until a second one is made.
+    pub fn profile_count(&self) -> usize {
+        self.profiles.len().max(1)
+    }
+
+    /// Menu labels of every profile in order, the active one's from `profile`.
+    pub fn profile_labels(&self) -> Vec<String> {
+        if self.profiles.is_empty() {
+            return vec![self.profile.label(0)];
+        }
+        self.profiles
+            .iter()
+            .enumerate()
+            .map(|(index, profile)| {
+                if index == self.active_profile {
+                    self.profile.label(index)
+                } else {
+                    profile.label(index)
+                }
+            })
+            .collect()
+    }
+
+    /// Makes profile `index` the live one, keeping the current profile's
+    /// changes in its slot. False when it's already active or doesn't exist.
+    pub fn switch_profile(&mut self, index: usize) -> bool {
+        if index == self.active_profile || index >= self.profiles.len() {
+            return false;
+        }
+        self.stash_profile();
+        self.profile = self.profiles[index].clone();
+        self.active_profile = index;
+        true
+    }
+
+    /// Adds an empty profile called `name` and switches to it. The first time,
+    /// the profile in use until now becomes the first entry.
+    pub fn add_profile(&mut self, name: String) {
+        if self.profiles.is_empty() {
+            self.profiles.push(self.profile.clone());
+            self.active_profile = 0;
+        }
+        self.profiles.push(Profile {
+            name,
+            ..Profile::default()
+        });
+        self.switch_profile(self.profiles.len() - 1);
+    }
+
+    /// Deletes the active profile and switches to the one before it (the
+    /// next one for the first). With one profile left the list goes away.
+    pub fn remove_active_profile(&mut self) -> bool {
+        if self.profiles.len() < 2 {
+            return false;
+        }
+        let removed = self.active_profile;
+        let next = if removed == 0 { 1 } else { removed - 1 };
+        self.switch_profile(next);
+        self.profiles.remove(removed);
+        if self.active_profile > removed {
+            self.active_profile -= 1;
+        }
+        if self.profiles.len() == 1 {
+            self.profiles.clear();
+            self.active_profile = 0;
+        }
+        true
+    }
+
+    /// Copies the live profile into its slot in `profiles`.
+    fn stash_profile(&mut self) {
+        if let Some(slot) = self.profiles.get_mut(self.active_profile) {
+            *slot = self.profile.clone();
+        }
+    }
+
+    /// What `save` writes: the config as is, except that the active slot in
+    /// `profiles` holds the live profile rather than its copy from the last
+    /// switch.
+    fn saved_value(&self) -> serde_json::Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(slot) = value
+            .get_mut("profiles")
+            .and_then(|profiles| profiles.get_mut(self.active_profile))
+        {
+            *slot = serde_json::to_value(&self.profile)?;
+        }
+        Ok(value)
+    }
+}
+
+impl Profile {
+    /// `name`, or "Profile 2" and so on for an unnamed profile at `index`.
+    pub fn label(&self, index: usize) -> String {
+        match self.name.trim() {
+            "" => format!("Profile {}", index + 1),
+            name => name.to_string(),
+        }
+    }
+
+    /// `validate_and_repair` for one profile's settings; returns how many
+    /// pins were set aside. A field repaired in several profiles is listed
+    /// once in `fixed`.
+    fn repair(
+        &mut self,
+        missing_pins: MissingPins,
+        exists: impl Fn(&Path) -> bool,
+        fixed: &mut Vec<&'static str>,
+    ) -> usize {
+        let mut repaired = Vec::new();
+        let icon_size = match self.icon_size {
+            0 => default_icon_size(),
+            size => size.clamp(MIN_ICON_SIZE, MAX_ICON_SIZE),
+        };
+        repair(&mut self.icon_size, icon_size, "icon_size", &mut repaired);
+        let grid_cols = self.grid_cols.clamp(1, MAX_GRID_COLS as u32);
+        repair(&mut self.grid_cols, grid_cols, "grid_cols", &mut repaired);
+        let grid_rows = self.grid_rows.min(MAX_GRID_ROWS as u32);
+        repair(&mut self.grid_rows, grid_rows, "grid_rows", &mut repaired);
+        let scroll_offset = Some(self.scroll_offset)
+            .filter(|offset| offset.is_finite())
+            .unwrap_or(0.0)
+            .max(0.0);
+        repair(
+            &mut self.scroll_offset,
+            scroll_offset,
+            "scroll_offset",
+            &mut repaired,
+        );
+        if self
+            .column_weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight <= 0.0)
+        {
+            repair(
+                &mut self.column_weights,
+                Vec::new(),
+                "column_weights",
+                &mut repaired,
+            );
+        }
+        for name in repaired {
+            if !fixed.contains(&name) {
+                fixed.push(name);
+            }
+        }
+
+        if missing_pins == MissingPins::Keep {
+            return 0;
+        }
+        let mut meta: HashMap<String, PinnedLaunchMeta> = self
+            .pinned_launch_meta
+            .iter()
+            .map(|meta| (meta.key(), meta.clone()))
+            .collect();
+        let mut broken = Vec::new();
+        self.pinned_apps.retain(|path| {
+            let key = normalize_path_key(path);
+            // A command pin's path is a command line, not a file.
+            if meta.get(&key).is_some_and(|meta| meta.command) || exists(path) {
+                return true;
+            }
+            warn!(
+                "config: pinned target {} is missing, set aside",
+                path.display()
+            );
+            broken.push(meta.remove(&key).unwrap_or_else(|| PinnedLaunchMeta {
+                path: path.clone(),
+                ..Default::default()
+            }));
+            false
+        });
+        let count = broken.len();
+        if count > 0 {
+            let keys: HashSet<String> = broken.iter().map(PinnedLaunchMeta::key).collect();
+            self.pinned_launch_meta
+                .retain(|meta| !keys.contains(&meta.key()));
+            self.broken_pins.retain(|meta| !keys.contains(&meta.key()));
+            self.broken_pins.extend(broken);
+        }
+        count
+    }
+}
+
+impl PinGroup {
+    /// The pause between members on "Launch all", falling back to
+    /// `default_ms` when the folder doesn't set its own.
+    pub fn launch_delay(&self, default_ms: u64) -> Duration {
+        Duration::from_millis(self.launch_delay_ms.unwrap_or(default_ms))
+    }
 }
 
 impl PinnedLaunchMeta {
     pub fn key(&self) -> String {
-        self.path.to_string_lossy().to_ascii_lowercase()
+        normalize_path_key(&self.path)
     }
 }
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum PinnedAppCompat {
-    Path(PathBuf),
-    Entry(PinnedLaunchMeta),
+/// What `validate_and_repair` changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepairReport {
+    /// Pins moved to `broken_pins`.
+    pub missing_pins: usize,
+    /// Settings that were out of range, by field name.
+    pub fixed: Vec<&'static str>,
+}
+
+impl RepairReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing_pins == 0 && self.fixed.is_empty()
+    }
+
+    /// "Removed 2 missing apps.", plus the settings that were reset.
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        match self.missing_pins {
+            0 => {}
+            1 => parts.push("Removed 1 missing app.".to_string()),
+            n => parts.push(format!("Removed {n} missing apps.")),
+        }
+        if !self.fixed.is_empty() {
+            parts.push(format!("Reset invalid {}.", self.fixed.join(", ")));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Largest saved window side or coordinate that is taken as real.
+const MAX_SAVED_EXTENT: f32 = 100_000.0;
+
+fn valid_geometry(pos: (f32, f32), size: (f32, f32)) -> bool {
+    let coordinate = |v: f32| v.is_finite() && v.abs() <= MAX_SAVED_EXTENT;
+    let side = |v: f32| v.is_finite() && v > 0.0 && v <= MAX_SAVED_EXTENT;
+    coordinate(pos.0) && coordinate(pos.1) && side(size.0) && side(size.1)
+}
+
+/// Sets `field` to `value`, noting `name` in `fixed` if that changed it.
+fn repair<T: PartialEq>(
+    field: &mut T,
+    value: T,
+    name: &'static str,
+    fixed: &mut Vec<&'static str>,
+) {
+    if *field != value {
+        warn!("config: {name} was out of range, repaired");
+        *field = value;
+        fixed.push(name);
+    }
+}
+
+/// Collapses entries for the same pin into the first one's place. Later
+/// entries win field by field, but an empty field never clears an earlier
+/// value; each collision is logged so a hand edit that lost out can be traced.
+pub fn dedupe_launch_meta(items: Vec<PinnedLaunchMeta>) -> Vec<PinnedLaunchMeta> {
+    let mut out: Vec<PinnedLaunchMeta> = Vec::with_capacity(items.len());
+    let mut slots: HashMap<String, usize> = HashMap::with_capacity(items.len());
+    for item in items {
+        let key = item.key();
+        let Some(&slot) = slots.get(&key) else {
+            slots.insert(key, out.len());
+            out.push(item);
+            continue;
+        };
+        let kept = &mut out[slot];
+        let mut replaced = Vec::new();
+        merge_meta_field(
+            &mut kept.display_name,
+            item.display_name,
+            "name",
+            &mut replaced,
+        );
+        merge_meta_field(&mut kept.args, item.args, "args", &mut replaced);
+        merge_meta_field(
+            &mut kept.working_dir,
+            item.working_dir,
+            "working dir",
+            &mut replaced,
+        );
+        merge_meta_field(&mut kept.shortcut, item.shortcut, "shortcut", &mut replaced);
+        merge_meta_field(&mut kept.opener, item.opener, "opener", &mut replaced);
+        if !item.env.is_empty() {
+            if !kept.env.is_empty() && kept.env != item.env {
+                replaced.push("env");
+            }
+            kept.env = item.env;
+        }
+        kept.favorite |= item.favorite;
+        kept.command |= item.command;
+        if item.show_state != ShowState::Normal {
+            if kept.show_state != ShowState::Normal && kept.show_state != item.show_state {
+                replaced.push("window state");
+            }
+            kept.show_state = item.show_state;
+        }
+        if replaced.is_empty() {
+            warn!(
+                "merged duplicate launch settings for {}",
+                item.path.display()
+            );
+        } else {
+            warn!(
+                "duplicate launch settings for {}: the later entry's {} replaced the earlier one",
+                item.path.display(),
+                replaced.join(", ")
+            );
+        }
+    }
+    out
+}
+
+fn merge_meta_field<T: PartialEq>(
+    kept: &mut Option<T>,
+    later: Option<T>,
+    name: &'static str,
+    replaced: &mut Vec<&'static str>,
+) {
+    let Some(later) = later else {
+        return;
+    };
+    if kept.as_ref().is_some_and(|value| *value != later) {
+        replaced.push(name);
+    }
+    *kept = Some(later);
+}
+
+/// Upgrades a parsed config file to `CONFIG_VERSION` in place, one step per
+/// version. Returns whether anything changed, so the caller can rewrite it.
+/// Files from a newer build are left alone.
+fn migrate_config(value: &mut serde_json::Value) -> bool {
+    let Some(root) = value.as_object_mut() else {
+        return false;
+    };
+    let version = root
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version > u64::from(CONFIG_VERSION) {
+        warn!(
+            "config is from a newer version ({version}); keeping settings this build doesn't know"
+        );
+        return false;
+    }
+    if version == u64::from(CONFIG_VERSION) {
+        return false;
+    }
+    if version < 1 {
+        migrate_config_v0(root);
+    }
+    if version < 2 {
+        migrate_config_v1(root);
+    }
+    if version < 3 {
+        migrate_config_v2(root);
+    }
+    root.insert("version".to_string(), CONFIG_VERSION.into());
+    true
+}
+
+/// Before versioning: pins could be saved as objects carrying their launch
+/// settings, and the grid was a two-column toggle with a left/right layout.
+/// `grid_rows` was saved but unused, so its old default of 3 goes rather
+/// than start paging the grid.
+fn migrate_config_v0(root: &mut serde_json::Map<String, serde_json::Value>) {
+    root.remove("grid_rows");
+    let mut legacy_meta = Vec::new();
+    if let Some(serde_json::Value::Array(pins)) = root.get_mut("pinned_apps") {
+        for pin in pins.iter_mut() {
+            if let Some(path) = pin.get("path").cloned() {
+                legacy_meta.push(std::mem::replace(pin, path));
+            }
+        }
+    }
+    if !legacy_meta.is_empty() {
+        // Newer explicit meta goes last so it wins when both exist.
+        if let Some(serde_json::Value::Array(meta)) = root.remove("pinned_launch_meta") {
+            legacy_meta.extend(meta);
+        }
+        root.insert("pinned_launch_meta".to_string(), legacy_meta.into());
+    }
+
+    if let Some(enabled) = root.remove("two_column_mode").and_then(|v| v.as_bool()) {
+        root.insert("grid_mode".to_string(), enabled.into());
+        root.insert("grid_cols".to_string(), 2.into());
+    }
+    if let Some(layout) = root.remove("two_column_layout") {
+        root.entry("grid_layout").or_insert(layout);
+    }
+}
+
+/// Version 1: the window level was an `always_on_top` toggle.
+fn migrate_config_v1(root: &mut serde_json::Map<String, serde_json::Value>) {
+    if let Some(on_top) = root.remove("always_on_top").and_then(|v| v.as_bool()) {
+        let level = if on_top {
+            DockLevel::AlwaysOnTop
+        } else {
+            DockLevel::Normal
+        };
+        root.insert(
+            "window_level".to_string(),
+            serde_json::to_value(level).unwrap_or_default(),
+        );
+    }
+}
+
+/// Version 2: a pin's window could only be asked to start minimized, with a
+/// `launch_minimized` toggle.
+fn migrate_config_v2(root: &mut serde_json::Map<String, serde_json::Value>) {
+    let Some(serde_json::Value::Array(meta)) = root.get_mut("pinned_launch_meta") else {
+        return;
+    };
+    for item in meta.iter_mut().filter_map(serde_json::Value::as_object_mut) {
+        if item.remove("launch_minimized").and_then(|v| v.as_bool()) == Some(true) {
+            item.insert(
+                "show_state".to_string(),
+                serde_json::to_value(ShowState::Minimized).unwrap_or_default(),
+            );
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
-fn deserialize_pinned_apps<'de, D>(deserializer: D) -> Result<Vec<PathBuf>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let raw = Vec::<PinnedAppCompat>::deserialize(deserializer)?;
-    Ok(raw
-        .into_iter()
-        .map(|item| match item {
-            PinnedAppCompat::Path(path) => path,
-            PinnedAppCompat::Entry(entry) => entry.path,
-        })
-        .collect())
+fn default_terminal() -> String {
+    "cmd.exe".to_string()
+}
+
+fn default_font_fallbacks() -> Vec<PathBuf> {
+    [
+        r"C:\Windows\Fonts\msyh.ttc",
+        r"C:\Windows\Fonts\msyhbd.ttc",
+        r"C:\Windows\Fonts\simhei.ttf",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
+fn default_hover_card_delay_ms() -> u64 {
+    450
+}
+
+fn default_reorder_hold_ms() -> u64 {
+    260
+}
+
+fn default_reorder_move_tolerance() -> f32 {
+    18.0
+}
+
+pub const MAX_SNAP_THRESHOLD: f32 = 400.0;
+
+/// Narrowest ratio the aspect lock accepts; its inverse is the widest.
+const MIN_ASPECT_RATIO: f32 = 0.2;
+
+fn default_aspect_ratio() -> f32 {
+    1.0
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_snap_threshold() -> f32 {
+    48.0
+}
+
+pub const MAX_PIN_LIMIT: usize = 500;
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+/// Rounded to whole percents so stepping and saving don't drift.
+pub fn clamp_ui_scale(scale: f32) -> f32 {
+    if !scale.is_finite() {
+        return default_ui_scale();
+    }
+    (scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE) * 100.0).round() / 100.0
+}
+
+fn default_pin_limit() -> usize {
+    20
+}
+
+fn default_pin_warning_slots() -> usize {
+    3
 }
 
 fn default_icon_size() -> u32 {
     48
 }
 
+const MIN_ICON_SIZE: u32 = 16;
+const MAX_ICON_SIZE: u32 = 256;
+
+pub const MAX_GRID_COLS: usize = 6;
+pub const MAX_GRID_ROWS: usize = 12;
+
 fn default_grid_cols() -> u32 {
     3
 }
 
 fn default_grid_rows() -> u32 {
-    3
+    0
 }
 
-impl Default for AppConfig {
+fn default_next_profile_key() -> String {
+    "P".to_string()
+}
+
+/// Windows virtual-key code of a hotkey key name: `A`–`Z` and `0`–`9` are
+/// their ASCII codes, `F1`–`F24` start at 0x70.
+fn hotkey_vk(name: &str) -> Option<u32> {
+    let name = name.trim().to_ascii_uppercase();
+    let mut chars = name.chars();
+    match (chars.next()?, chars.next()) {
+        (key @ ('A'..='Z' | '0'..='9'), None) => Some(key as u32),
+        ('F', Some(_)) => match name[1..].parse::<u32>() {
+            Ok(n @ 1..=24) => Some(0x70 + n - 1),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl Default for Profile {
     fn default() -> Self {
         Self {
+            name: String::new(),
             pinned_apps: Vec::new(),
             pinned_launch_meta: Vec::new(),
-            shape: WindowShape::Circle,
-            last_pos: None,
-            last_size: None,
             quick_launch_app: None,
-            two_column_mode: false,
-            two_column_layout: None,
+            pin_groups: Vec::new(),
+            broken_pins: Vec::new(),
+            scroll_offset: 0.0,
+            favorites_first: false,
+            grid_mode: false,
+            grid_layout: None,
             icon_size: default_icon_size(),
             grid_cols: default_grid_cols(),
             grid_rows: default_grid_rows(),
+            column_weights: Vec::new(),
+            shape: WindowShape::Pill,
+            theme: ThemeMode::System,
+            appearance: Appearance::Decorative,
+            acrylic: false,
+            system_shadow: false,
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            profile: Profile::default(),
+            profiles: Vec::new(),
+            active_profile: 0,
+            last_pos: None,
+            last_size: None,
+            monitor_geometry: BTreeMap::new(),
+            apply_size_on_show: false,
+            aspect_lock: false,
+            aspect_ratio: default_aspect_ratio(),
+            collapsed: false,
+            start_minimized: false,
+            hidden: false,
+            confirm_quit: false,
+            window_level: DockLevel::default(),
+            hide_method: HideMethod::default(),
+            focus_on_show: true,
+            ui_scale: default_ui_scale(),
+            animate_tray_on_launch: true,
+            exclude_from_capture: false,
+            hide_in_fullscreen: false,
+            snap_enabled: true,
+            snap_threshold: default_snap_threshold(),
+            snap_to_work_area: true,
+            reduce_motion: None,
+            pin_limit: default_pin_limit(),
+            pin_warning_slots: default_pin_warning_slots(),
+            confirm_risky_launch: false,
+            launch_delay_ms: 0,
+            hover_card_delay_ms: default_hover_card_delay_ms(),
+            reorder_hold_ms: default_reorder_hold_ms(),
+            reorder_move_tolerance: default_reorder_move_tolerance(),
+            reorder_handle: false,
+            activate_on: ActivationMode::DoubleClick,
+            pin_any_file: true,
+            animation: AnimationConfig::default(),
+            terminal: default_terminal(),
+            font_fallbacks: default_font_fallbacks(),
+            log_level: default_log_level(),
+            missing_pins: MissingPins::Keep,
+            next_profile_key: default_next_profile_key(),
+            repairs: RepairReport::default(),
+            extra: serde_json::Map::new(),
         }
     }
 }
@@ -142,6 +1132,83 @@ impl AppConfig {
             .map(|dirs| dirs.config_dir().to_path_buf())
     }
 
+    /// Brings numeric settings back into range and, with `missing_pins` set
+    /// to `SetAside`, moves pins whose target no longer exists to
+    /// `broken_pins`, in every profile.
+    pub fn validate_and_repair(&mut self) -> RepairReport {
+        self.repair_with(|path| path.exists())
+    }
+
+    fn repair_with(&mut self, exists: impl Fn(&Path) -> bool) -> RepairReport {
+        let mut report = RepairReport::default();
+        let fixed = &mut report.fixed;
+
+        if let Some((pos, size)) = self.last_pos.zip(self.last_size) {
+            if !valid_geometry(pos, size) {
+                warn!("config: last_pos/last_size out of range, dropped");
+                self.last_pos = None;
+                self.last_size = None;
+                fixed.push("last_size");
+            }
+        }
+        let monitors = self.monitor_geometry.len();
+        self.monitor_geometry
+            .retain(|_, geometry| valid_geometry(geometry.pos, geometry.size));
+        if self.monitor_geometry.len() != monitors {
+            warn!("config: dropped out-of-range monitor_geometry entries");
+            fixed.push("monitor_geometry");
+        }
+        let ui_scale = clamp_ui_scale(self.ui_scale);
+        repair(&mut self.ui_scale, ui_scale, "ui_scale", fixed);
+        let aspect_ratio = self.aspect_ratio();
+        repair(&mut self.aspect_ratio, aspect_ratio, "aspect_ratio", fixed);
+        let snap_threshold = if self.snap_threshold.is_finite() {
+            self.snap_threshold.clamp(0.0, MAX_SNAP_THRESHOLD)
+        } else {
+            default_snap_threshold()
+        };
+        repair(
+            &mut self.snap_threshold,
+            snap_threshold,
+            "snap_threshold",
+            fixed,
+        );
+        let pin_limit = self.pin_limit();
+        repair(&mut self.pin_limit, pin_limit, "pin_limit", fixed);
+        let tolerance = Some(self.reorder_move_tolerance)
+            .filter(|t| t.is_finite() && *t >= 0.0)
+            .unwrap_or_else(default_reorder_move_tolerance);
+        repair(
+            &mut self.reorder_move_tolerance,
+            tolerance,
+            "reorder_move_tolerance",
+            fixed,
+        );
+
+        if self.active_profile >= self.profile_count() {
+            // Keep what's live rather than lose it to a bad index.
+            warn!("config: active_profile out of range, live profile kept as a new entry");
+            if !self.profiles.is_empty() {
+                self.profiles.push(self.profile.clone());
+            }
+            self.active_profile = self.profiles.len().saturating_sub(1);
+            fixed.push("active_profile");
+        }
+
+        // The active slot is only a copy of `profile` until the next save.
+        let active = self.active_profile;
+        let inactive = self
+            .profiles
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .map(|(_, profile)| profile);
+        for profile in std::iter::once(&mut self.profile).chain(inactive) {
+            report.missing_pins += profile.repair(self.missing_pins, &exists, &mut report.fixed);
+        }
+        report
+    }
+
     pub fn load() -> Self {
         if let Some(proj_dirs) =
             directories::ProjectDirs::from("com", "float_launcher", "float_launcher")
@@ -149,10 +1216,22 @@ impl AppConfig {
             let config_path = proj_dirs.config_dir().join("config.json");
             if config_path.exists() {
                 if let Ok(file) = std::fs::File::open(config_path) {
-                    if let Ok(config) = serde_json::from_reader(file) {
-                        return config;
-                    } else {
-                        warn!("Failed to parse config, using default");
+                    let parsed = serde_json::from_reader::<_, serde_json::Value>(file).and_then(
+                        |mut value| {
+                            let migrated = migrate_config(&mut value);
+                            serde_json::from_value::<Self>(value).map(|config| (config, migrated))
+                        },
+                    );
+                    match parsed {
+                        Ok((mut config, migrated)) => {
+                            let repairs = config.validate_and_repair();
+                            if migrated || !repairs.is_empty() {
+                                config.save();
+                            }
+                            config.repairs = repairs;
+                            return config;
+                        }
+                        Err(err) => warn!("Failed to parse config, using default: {err}"),
                     }
                 }
             }
@@ -160,26 +1239,64 @@ impl AppConfig {
         Self::default()
     }
 
+    /// Writes the config file, with the live profile also in its slot in
+    /// `profiles`.
     pub fn save(&self) {
         if let Some(proj_dirs) =
             directories::ProjectDirs::from("com", "float_launcher", "float_launcher")
         {
             let config_dir = proj_dirs.config_dir();
-            if std::fs::create_dir_all(config_dir).is_ok() {
-                let config_path = config_dir.join("config.json");
-                if let Ok(file) = std::fs::File::create(config_path) {
-                    let _ = serde_json::to_writer_pretty(file, self);
-                }
+            let written = std::fs::create_dir_all(config_dir)
+                .and_then(|()| std::fs::File::create(config_dir.join("config.json")))
+                .map_err(|err| err.to_string())
+                .and_then(|file| {
+                    let saved = self.saved_value().map_err(|err| err.to_string())?;
+                    serde_json::to_writer_pretty(file, &saved).map_err(|err| err.to_string())
+                });
+            if let Err(err) = written {
+                warn!("failed to save config: {err}");
             }
         }
     }
 }
 
-fn normalize_path_key(path: &Path) -> String {
-    path.to_string_lossy().to_ascii_lowercase()
+/// Comparison key for a pinned path: `/` and `\\` alike, without the `\\?\`
+/// extended-length prefix, doubled separators or a trailing separator, and
+/// case-insensitive where the file system is (`C:\Tools\` and `c:/tools`
+/// are the same pin on Windows).
+pub fn normalize_path_key(path: &Path) -> String {
+    let mut key = path.to_string_lossy().replace('/', "\\");
+    if cfg!(windows) {
+        key.make_ascii_lowercase();
+    }
+    if let Some(rest) = key
+        .strip_prefix(r"\\?\UNC\")
+        .or_else(|| key.strip_prefix(r"\\?\unc\"))
+    {
+        key = format!(r"\\{rest}");
+    } else if let Some(rest) = key.strip_prefix(r"\\?\") {
+        key = rest.to_string();
+    }
+
+    // A UNC path keeps its leading `\\`; any other run of separators is one.
+    let unc = key.starts_with(r"\\");
+    let mut collapsed = String::with_capacity(key.len());
+    for c in key.chars() {
+        if c == '\\' && collapsed.ends_with('\\') && !(unc && collapsed.len() == 1) {
+            continue;
+        }
+        collapsed.push(c);
+    }
+
+    let trimmed = collapsed.trim_end_matches('\\');
+    if trimmed.is_empty() || trimmed.ends_with(':') {
+        collapsed
+    } else {
+        trimmed.to_string()
+    }
 }
 
-fn normalize_launch_key(path: &Path, args: Option<&str>, working_dir: Option<&Path>) -> String {
+pub fn normalize_launch_key(path: &Path, args: Option<&str>, working_dir: Option<&Path>) -> String {
     let normalized_args = args.map(str::trim).unwrap_or_default();
     let normalized_wd = working_dir.map(normalize_path_key).unwrap_or_default();
     format!(
@@ -189,3 +1306,324 @@ fn normalize_launch_key(path: &Path, args: Option<&str>, working_dir: Option<&Pa
         normalized_wd
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_config_migrates_legacy_pins_and_two_column_mode() {
+        let mut value = serde_json::json!({
+            "pinned_apps": [
+                "C:\\Tools\\plain.exe",
+                { "path": "C:\\Tools\\app.exe", "args": "--old", "display_name": "App" }
+            ],
+            "pinned_launch_meta": [{ "path": "C:\\Tools\\app.exe", "args": "--new" }],
+            "shape": "Pill",
+            "last_pos": null,
+            "two_column_mode": true,
+            "two_column_layout": { "left": [], "right": [] },
+            "grid_rows": 3,
+            "from_the_future": { "keep": 1 }
+        });
+        assert!(migrate_config(&mut value));
+        let config: AppConfig = serde_json::from_value(value).expect("migrated config");
+
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(
+            config.profile.pinned_apps,
+            [
+                PathBuf::from(r"C:\Tools\plain.exe"),
+                PathBuf::from(r"C:\Tools\app.exe")
+            ]
+        );
+        let meta = dedupe_launch_meta(config.profile.pinned_launch_meta.clone());
+        assert_eq!(meta.len(), 1);
+        assert_eq!(meta[0].args.as_deref(), Some("--new"));
+        assert_eq!(meta[0].display_name.as_deref(), Some("App"));
+        assert!(config.profile.grid_mode);
+        assert_eq!(config.profile.grid_cols, 2);
+        assert_eq!(
+            config.profile.grid_layout.as_ref().map(|l| l.columns.len()),
+            Some(2)
+        );
+        assert_eq!(config.visible_grid_rows(), None);
+
+        let saved = serde_json::to_value(&config).expect("serialize");
+        assert_eq!(saved["from_the_future"]["keep"], 1);
+        assert!(saved.get("two_column_mode").is_none());
+
+        let mut again = saved;
+        assert!(!migrate_config(&mut again));
+        let mut newer = serde_json::json!({ "version": CONFIG_VERSION + 1 });
+        assert!(!migrate_config(&mut newer));
+    }
+
+    #[test]
+    fn always_on_top_toggle_becomes_window_level() {
+        let mut value = serde_json::json!({
+            "version": 1,
+            "shape": "Pill",
+            "last_pos": null,
+            "always_on_top": false
+        });
+        assert!(migrate_config(&mut value));
+        let config: AppConfig = serde_json::from_value(value).expect("migrated config");
+        assert_eq!(config.window_level, DockLevel::Normal);
+
+        let mut value = serde_json::json!({ "version": 1, "shape": "Pill", "last_pos": null });
+        assert!(migrate_config(&mut value));
+        let config: AppConfig = serde_json::from_value(value).expect("migrated config");
+        assert_eq!(config.window_level, DockLevel::AlwaysOnTop);
+    }
+
+    #[test]
+    fn version_2_launch_minimized_upgrades_and_grid_rows_stay() {
+        let mut value = serde_json::json!({
+            "version": 2,
+            "shape": "Pill",
+            "last_pos": null,
+            "grid_rows": 3,
+            "pinned_apps": ["C:\\Tools\\agent.exe", "C:\\Tools\\editor.exe"],
+            "pinned_launch_meta": [
+                { "path": "C:\\Tools\\agent.exe", "launch_minimized": true },
+                { "path": "C:\\Tools\\editor.exe", "launch_minimized": false }
+            ]
+        });
+        assert!(migrate_config(&mut value));
+        assert!(value["pinned_launch_meta"][1]
+            .get("launch_minimized")
+            .is_none());
+        let config: AppConfig = serde_json::from_value(value).expect("migrated config");
+        let states: Vec<_> = config
+            .profile
+            .pinned_launch_meta
+            .iter()
+            .map(|meta| meta.show_state)
+            .collect();
+        assert_eq!(states, [ShowState::Minimized, ShowState::Normal]);
+        assert_eq!(config.visible_grid_rows(), Some(3));
+    }
+
+    fn meta(path: &str, args: Option<&str>, working_dir: Option<&str>) -> PinnedLaunchMeta {
+        PinnedLaunchMeta {
+            path: PathBuf::from(path),
+            display_name: None,
+            args: args.map(str::to_string),
+            working_dir: working_dir.map(PathBuf::from),
+            shortcut: None,
+            favorite: false,
+            opener: None,
+            command: false,
+            env: Vec::new(),
+            show_state: ShowState::Normal,
+        }
+    }
+
+    #[test]
+    fn ui_scale_is_clamped_and_rounded() {
+        assert_eq!(clamp_ui_scale(1.234), 1.23);
+        assert_eq!(clamp_ui_scale(0.1), MIN_UI_SCALE);
+        assert_eq!(clamp_ui_scale(9.0), MAX_UI_SCALE);
+        assert_eq!(clamp_ui_scale(f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn snap_threshold_is_off_when_disabled_or_zero_and_capped() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.snap_threshold(), Some(48.0));
+        config.snap_threshold = 1000.0;
+        assert_eq!(config.snap_threshold(), Some(MAX_SNAP_THRESHOLD));
+        config.snap_threshold = 0.0;
+        assert_eq!(config.snap_threshold(), None);
+        config.snap_threshold = 20.0;
+        config.snap_enabled = false;
+        assert_eq!(config.snap_threshold(), None);
+    }
+
+    #[test]
+    fn folder_launch_delay_falls_back_to_the_global_one() {
+        let mut group: PinGroup = serde_json::from_value(serde_json::json!({
+            "name": "Work",
+            "members": []
+        }))
+        .expect("folder");
+        assert_eq!(group.launch_delay_ms, None);
+        assert_eq!(group.launch_delay(500), Duration::from_millis(500));
+        group.launch_delay_ms = Some(0);
+        assert_eq!(group.launch_delay(500), Duration::ZERO);
+    }
+
+    #[test]
+    fn repair_clamps_settings_and_sets_missing_pins_aside() {
+        let mut config = AppConfig {
+            missing_pins: MissingPins::SetAside,
+            profile: Profile {
+                icon_size: 0,
+                grid_cols: 40,
+                pinned_apps: vec![
+                    PathBuf::from(r"C:\Tools\app.exe"),
+                    PathBuf::from(r"D:\Gone\old.exe"),
+                    PathBuf::from("notepad --new"),
+                ],
+                ..Profile::default()
+            },
+            last_pos: Some((10.0, 10.0)),
+            last_size: Some((f32::NAN, 400.0)),
+            ..AppConfig::default()
+        };
+        let mut gone = meta(r"D:\Gone\old.exe", Some("--x"), None);
+        gone.display_name = Some("Old".to_string());
+        let mut command = meta("notepad --new", None, None);
+        command.command = true;
+        config.profile.pinned_launch_meta = vec![gone.clone(), command];
+
+        let report = config.repair_with(|path| !path.to_string_lossy().starts_with("D:"));
+
+        assert_eq!(config.profile.icon_size, default_icon_size());
+        assert_eq!(config.profile.grid_cols, MAX_GRID_COLS as u32);
+        assert_eq!((config.last_pos, config.last_size), (None, None));
+        assert_eq!(
+            config.profile.pinned_apps,
+            [
+                PathBuf::from(r"C:\Tools\app.exe"),
+                PathBuf::from("notepad --new")
+            ]
+        );
+        assert_eq!(config.profile.pinned_launch_meta.len(), 1);
+        assert_eq!(config.profile.broken_pins, [gone]);
+        assert_eq!(report.missing_pins, 1);
+        assert_eq!(report.fixed, ["last_size", "icon_size", "grid_cols"]);
+        assert_eq!(
+            report.summary().as_deref(),
+            Some("Removed 1 missing app. Reset invalid last_size, icon_size, grid_cols.")
+        );
+        assert!(config.repair_with(|_| true).is_empty());
+
+        config.missing_pins = MissingPins::Keep;
+        assert!(config.repair_with(|_| false).is_empty());
+        assert_eq!(config.profile.pinned_apps.len(), 2);
+    }
+
+    #[test]
+    fn profiles_keep_their_own_pins_and_collapse_back_to_one() {
+        let work = PathBuf::from(r"C:\Work\ide.exe");
+        let game = PathBuf::from(r"D:\Games\launcher.exe");
+        let mut config = AppConfig::default();
+        config.profile.pinned_apps = vec![work.clone()];
+
+        config.add_profile("Gaming".to_string());
+        assert_eq!(config.active_profile, 1);
+        assert!(config.profile.pinned_apps.is_empty());
+        config.profile.pinned_apps.push(game.clone());
+        config.profile.grid_mode = true;
+        assert_eq!(config.profile_labels(), ["Profile 1", "Gaming"]);
+
+        assert!(config.switch_profile(0));
+        assert!(!config.switch_profile(0));
+        assert!(!config.switch_profile(2));
+        assert_eq!(config.profile.pinned_apps, std::slice::from_ref(&work));
+        assert!(!config.profile.grid_mode);
+
+        // Profile keys are read into the live profile, not kept as unknown.
+        let saved = serde_json::to_value(&config).expect("serialize");
+        assert_eq!(saved["pinned_apps"][0], r"C:\Work\ide.exe");
+        let loaded: AppConfig = serde_json::from_value(saved).expect("deserialize");
+        assert!(loaded.extra.is_empty());
+        assert_eq!(loaded.profiles[1].pinned_apps, [game]);
+
+        // Edits to the live profile reach its slot when saved.
+        config.profile.favorites_first = true;
+        let saved = config.saved_value().expect("serialize");
+        assert_eq!(saved["profiles"][0]["favorites_first"], true);
+        assert!(!config.profiles[0].favorites_first);
+
+        assert!(config.switch_profile(1));
+        assert!(config.remove_active_profile());
+        assert!(config.profiles.is_empty());
+        assert_eq!(config.active_profile, 0);
+        assert_eq!(config.profile.pinned_apps, [work]);
+        assert!(!config.remove_active_profile());
+    }
+
+    #[test]
+    fn repair_covers_every_profile_not_just_the_active_one() {
+        let gone = PathBuf::from(r"D:\Gone\old.exe");
+        let kept = PathBuf::from(r"C:\Tools\app.exe");
+        let broken = Profile {
+            grid_cols: 40,
+            pinned_apps: vec![gone.clone(), kept.clone()],
+            ..Profile::default()
+        };
+        let mut config = AppConfig {
+            missing_pins: MissingPins::SetAside,
+            profile: broken.clone(),
+            profiles: vec![broken.clone(), broken],
+            active_profile: 0,
+            ..AppConfig::default()
+        };
+
+        let report = config.repair_with(|path| !path.to_string_lossy().starts_with("D:"));
+
+        // The live profile and the other one; the active slot is its copy.
+        assert_eq!(report.missing_pins, 2);
+        assert_eq!(report.fixed, ["grid_cols"]);
+        for profile in [&config.profile, &config.profiles[1]] {
+            assert_eq!(profile.grid_cols, MAX_GRID_COLS as u32);
+            assert_eq!(profile.pinned_apps, std::slice::from_ref(&kept));
+            assert_eq!(profile.broken_pins.len(), 1);
+        }
+        assert!(config.switch_profile(1));
+        assert_eq!(config.profile.pinned_apps, [kept]);
+    }
+
+    #[test]
+    fn hotkey_keys_map_to_virtual_key_codes() {
+        assert_eq!(hotkey_vk("P"), Some(0x50));
+        assert_eq!(hotkey_vk(" p "), Some(0x50));
+        assert_eq!(hotkey_vk("7"), Some(0x37));
+        assert_eq!(hotkey_vk("F"), Some(0x46));
+        assert_eq!(hotkey_vk("F1"), Some(0x70));
+        assert_eq!(hotkey_vk("f24"), Some(0x87));
+        for off in ["", "F0", "F25", "PP", "Space", "["] {
+            assert_eq!(hotkey_vk(off), None, "{off:?}");
+        }
+    }
+
+    #[test]
+    fn path_keys_fold_case_only_on_windows() {
+        let key = normalize_path_key(Path::new("C:/Tools/App.exe"));
+        if cfg!(windows) {
+            assert_eq!(key, r"c:\tools\app.exe");
+        } else {
+            assert_eq!(key, r"C:\Tools\App.exe");
+        }
+        assert_eq!(
+            normalize_path_key(Path::new(r"\\?\UNC\Server\Share")),
+            normalize_path_key(Path::new("//Server/Share/"))
+        );
+    }
+
+    #[test]
+    fn duplicate_launch_meta_merges_with_later_entry_winning() {
+        let mut first = meta(r"C:\Tools\app.exe", Some("--old"), Some(r"C:\Work"));
+        first.display_name = Some("Old name".to_string());
+        first.show_state = ShowState::Minimized;
+        let mut later = meta("C:/Tools/app.exe", Some("--new"), None);
+        later.favorite = true;
+        later.show_state = ShowState::Maximized;
+        let other = meta(r"C:\Tools\other.exe", None, None);
+
+        let merged = dedupe_launch_meta(vec![first, other.clone(), later]);
+
+        assert_eq!(merged.len(), 2);
+        let app = &merged[0];
+        assert_eq!(app.path, PathBuf::from(r"C:\Tools\app.exe"));
+        assert_eq!(app.args.as_deref(), Some("--new"));
+        assert_eq!(app.working_dir, Some(PathBuf::from(r"C:\Work")));
+        assert_eq!(app.display_name.as_deref(), Some("Old name"));
+        assert!(app.favorite);
+        assert_eq!(app.show_state, ShowState::Maximized);
+        assert_eq!(merged[1], other);
+    }
+}
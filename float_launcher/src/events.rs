@@ -5,18 +5,53 @@ use std::path::PathBuf;
 pub enum UserEvent {
     Show,
     Hide,
+    /// Follows `Show` for the search hotkey: expand the dock and focus the search box.
+    ShowSearch,
     Quit,
+    /// Quit was asked for but `confirm_quit` is on; the UI shows a prompt first.
+    QuitRequested,
+    /// A pin entry in the tray menu was clicked; carries the pin's launch key.
+    LaunchPin(String),
+    /// A profile in the tray menu was picked; carries its index.
+    SwitchProfile(usize),
+    /// The next-profile hotkey was pressed.
+    NextProfile,
+    /// A "Launch selected" batch finished; names the pins that failed to start.
+    GroupLaunched {
+        launched: usize,
+        failed: Vec<String>,
+    },
     IconReady(IconResult),
+    /// These pinned files changed on disk; their icons should be extracted again.
+    IconsStale(Vec<PathBuf>),
+    /// Pinned targets that can't be reached right now, replacing the last
+    /// such list; sent whenever it changes.
+    PinsUnavailable(Vec<PathBuf>),
+    /// The "Open" dialog closed; `None` when it was cancelled.
+    FilePicked(FilePick, Option<PathBuf>),
+}
+
+/// What an "Open" dialog is choosing a file for.
+#[derive(Debug)]
+pub enum FilePick {
+    QuickLaunch,
+    /// The opener of the pin with this launch key.
+    Opener(String),
 }
 
 pub struct IconRequest {
     pub path: PathBuf,
     pub name_hint: Option<String>,
     pub size: u32,
+    /// `path` is a command pin's command line, not a file.
+    pub command: bool,
 }
 
 #[derive(Debug)]
 pub struct IconResult {
     pub path: PathBuf,
+    /// `IconRequest::size` it was extracted for.
+    pub size: u32,
+    /// `None` when extraction produced nothing.
     pub image: Option<egui::ColorImage>,
 }
@@ -1,18 +1,71 @@
 use eframe::egui;
 use std::collections::HashSet;
 use std::io::{Read, Write};
+#[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 use tray_icon::Icon;
+#[cfg(windows)]
 use windows::Win32::Graphics::Gdi::{
     CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO,
     BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HGDIOBJ,
 };
-use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
-use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
+#[cfg(windows)]
+use windows::Win32::Storage::FileSystem::{
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES,
+};
+#[cfg(windows)]
+use windows::Win32::UI::Shell::{
+    SHGetFileInfoW, SHFILEINFOW, SHGFI_FLAGS, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_USEFILEATTRIBUTES,
+};
+#[cfg(windows)]
 use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, ICONINFO};
 
+#[cfg(windows)]
 pub fn extract_icon_from_exe(path: &Path) -> Option<egui::ColorImage> {
+    extract_shell_icon(
+        path,
+        FILE_FLAGS_AND_ATTRIBUTES(0),
+        SHGFI_ICON | SHGFI_LARGEICON,
+    )
+}
+
+/// Icon the shell shows for a folder; falls back to the generic folder icon
+/// when the directory itself can't be queried (offline share, no access).
+#[cfg(windows)]
+pub fn extract_folder_icon(path: &Path) -> Option<egui::ColorImage> {
+    extract_icon_from_exe(path).or_else(|| {
+        extract_shell_icon(
+            path,
+            FILE_ATTRIBUTE_DIRECTORY,
+            SHGFI_ICON | SHGFI_LARGEICON | SHGFI_USEFILEATTRIBUTES,
+        )
+    })
+}
+
+/// Icon registered for the file's type, for documents whose own icon can't be read.
+#[cfg(windows)]
+pub fn extract_file_type_icon(path: &Path) -> Option<egui::ColorImage> {
+    extract_shell_icon(
+        path,
+        FILE_ATTRIBUTE_NORMAL,
+        SHGFI_ICON | SHGFI_LARGEICON | SHGFI_USEFILEATTRIBUTES,
+    )
+}
+
+/// Console host icon, shown for command pins.
+#[cfg(windows)]
+pub fn extract_terminal_icon() -> Option<egui::ColorImage> {
+    let system_root = std::env::var_os("SystemRoot")?;
+    extract_icon_from_exe(&PathBuf::from(system_root).join("System32").join("cmd.exe"))
+}
+
+#[cfg(windows)]
+fn extract_shell_icon(
+    path: &Path,
+    attributes: FILE_FLAGS_AND_ATTRIBUTES,
+    flags: SHGFI_FLAGS,
+) -> Option<egui::ColorImage> {
     unsafe {
         let mut sh_file_info = SHFILEINFOW::default();
         let path_wide: Vec<u16> = path
@@ -23,10 +76,10 @@ pub fn extract_icon_from_exe(path: &Path) -> Option<egui::ColorImage> {
 
         let result = SHGetFileInfoW(
             windows::core::PCWSTR(path_wide.as_ptr()),
-            FILE_FLAGS_AND_ATTRIBUTES(0),
+            attributes,
             Some(&mut sh_file_info),
             std::mem::size_of::<SHFILEINFOW>() as u32,
-            SHGFI_ICON | SHGFI_LARGEICON,
+            flags,
         );
 
         if result == 0 {
@@ -113,6 +166,34 @@ pub fn extract_icon_from_exe(path: &Path) -> Option<egui::ColorImage> {
     }
 }
 
+#[cfg(all(target_os = "linux", not(windows)))]
+mod freedesktop;
+#[cfg(all(target_os = "linux", not(windows)))]
+pub use freedesktop::{
+    extract_file_type_icon, extract_folder_icon, extract_icon_from_exe, extract_terminal_icon,
+};
+
+// No icon lookup elsewhere; pins keep the placeholder icon.
+#[cfg(all(not(windows), not(target_os = "linux")))]
+pub fn extract_icon_from_exe(_path: &Path) -> Option<egui::ColorImage> {
+    None
+}
+
+#[cfg(all(not(windows), not(target_os = "linux")))]
+pub fn extract_folder_icon(_path: &Path) -> Option<egui::ColorImage> {
+    None
+}
+
+#[cfg(all(not(windows), not(target_os = "linux")))]
+pub fn extract_file_type_icon(_path: &Path) -> Option<egui::ColorImage> {
+    None
+}
+
+#[cfg(all(not(windows), not(target_os = "linux")))]
+pub fn extract_terminal_icon() -> Option<egui::ColorImage> {
+    None
+}
+
 fn find_brand_icon_file() -> Option<PathBuf> {
     let names = ["app.ico", "favicon.ico"];
     for dir in icon_override_dirs() {
@@ -132,22 +213,108 @@ fn color_image_to_tray_icon(image: &egui::ColorImage) -> Option<Icon> {
     Icon::from_rgba(image.as_raw().to_vec(), width, height).ok()
 }
 
-pub fn load_tray_icon_for_app(side: usize) -> Option<Icon> {
+/// The tray's looks: the app icon, its dimmed "hidden" copy and the frames
+/// cycled while a launch starts.
+pub struct TrayIcons {
+    pub awake: Icon,
+    pub sleep: Icon,
+    pub launching: Vec<Icon>,
+}
+
+/// Frames in the launch animation's loop.
+const LAUNCH_FRAMES: usize = 8;
+
+/// Built from the app's own icon, or a plain teal square when there is none.
+pub fn load_tray_icons_for_app(side: usize) -> TrayIcons {
     let side = side.clamp(16, 256);
+    load_tray_image_for_app(side)
+        .and_then(|image| tray_icons_from(&image))
+        .or_else(|| tray_icons_from(&solid_image([45, 190, 150], side)))
+        .expect("a solid square always converts to a tray icon")
+}
 
+fn load_tray_image_for_app(side: usize) -> Option<egui::ColorImage> {
     if let Ok(exe) = std::env::current_exe() {
         if let Some(img) = extract_icon_from_exe(&exe) {
-            let sized = resize_to_square(&img, side);
-            if let Some(icon) = color_image_to_tray_icon(&sized) {
-                return Some(icon);
-            }
+            return Some(resize_to_square(&img, side));
         }
     }
 
     let brand_path = find_brand_icon_file()?;
     let img = extract_icon_from_exe(&brand_path)?;
-    let sized = resize_to_square(&img, side);
-    color_image_to_tray_icon(&sized)
+    Some(resize_to_square(&img, side))
+}
+
+fn tray_icons_from(image: &egui::ColorImage) -> Option<TrayIcons> {
+    Some(TrayIcons {
+        awake: color_image_to_tray_icon(image)?,
+        sleep: color_image_to_tray_icon(&dimmed_image(image))?,
+        launching: launch_frames(image, LAUNCH_FRAMES)
+            .iter()
+            .filter_map(color_image_to_tray_icon)
+            .collect(),
+    })
+}
+
+fn solid_image(rgb: [u8; 3], side: usize) -> egui::ColorImage {
+    let [r, g, b] = rgb;
+    egui::ColorImage::new([side, side], egui::Color32::from_rgb(r, g, b))
+}
+
+/// Gray, darkened copy of `image` for the "hidden" tray icon. Alpha is kept,
+/// so the outline stays crisp whichever way the tray reads the channels.
+fn dimmed_image(image: &egui::ColorImage) -> egui::ColorImage {
+    const KEEP_PERCENT: u32 = 55;
+    let fade = |c: u32| (c * KEEP_PERCENT / 100) as u8;
+    let pixels = image
+        .pixels
+        .iter()
+        .map(|p| {
+            // Premultiplied channels never exceed alpha, so neither does their luma.
+            let luma =
+                (u32::from(p.r()) * 299 + u32::from(p.g()) * 587 + u32::from(p.b()) * 114) / 1000;
+            let gray = fade(luma);
+            egui::Color32::from_rgba_premultiplied(gray, gray, gray, p.a())
+        })
+        .collect();
+    egui::ColorImage {
+        size: image.size,
+        pixels,
+    }
+}
+
+/// `count` copies of `image` with a white dot stepping once around its edge,
+/// for the tray's launch animation.
+fn launch_frames(image: &egui::ColorImage, count: usize) -> Vec<egui::ColorImage> {
+    let [width, height] = image.size;
+    let center = egui::vec2(width as f32, height as f32) * 0.5;
+    let orbit = width.min(height) as f32 * 0.36;
+    let dot = (width.min(height) as f32 * 0.14).max(1.5);
+    (0..count)
+        .map(|frame| {
+            let angle = frame as f32 / count as f32 * std::f32::consts::TAU;
+            let at = center + orbit * egui::vec2(angle.sin(), -angle.cos());
+            let mut out = image.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    let pixel = egui::vec2(x as f32 + 0.5, y as f32 + 0.5);
+                    // One pixel of soft edge keeps the dot round at 16 px.
+                    let cover = (dot - (pixel - at).length() + 0.5).clamp(0.0, 1.0);
+                    if cover > 0.0 {
+                        let p = &mut out.pixels[y * width + x];
+                        let mix = |c: u8| (255.0 * cover + f32::from(c) * (1.0 - cover)) as u8;
+                        *p = egui::Color32::from_rgba_premultiplied(
+                            mix(p.r()),
+                            mix(p.g()),
+                            mix(p.b()),
+                            mix(p.a()),
+                        );
+                    }
+                }
+            }
+            out
+        })
+        .collect()
 }
 
 fn stable_hash64(input: &[u8]) -> u64 {
@@ -194,6 +361,28 @@ fn load_cached_icon_at(config_dir: &Path, source_path: &Path) -> Option<egui::Co
     ))
 }
 
+/// Deletes every cached icon; returns how many files went.
+pub fn clear_icon_cache() -> usize {
+    let Some(config_dir) = crate::config::AppConfig::config_dir() else {
+        return 0;
+    };
+    let Ok(entries) = std::fs::read_dir(config_dir.join("icons")) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rgba"))
+        .filter(|path| std::fs::remove_file(path).is_ok())
+        .count()
+}
+
+pub fn remove_cached_icon(source_path: &Path) {
+    if let Some(config_dir) = crate::config::AppConfig::config_dir() {
+        let _ = std::fs::remove_file(icon_cache_path_at(&config_dir, source_path));
+    }
+}
+
 pub fn save_cached_icon(source_path: &Path, image: &egui::ColorImage) {
     let Some(config_dir) = crate::config::AppConfig::config_dir() else {
         return;
@@ -361,10 +550,20 @@ pub fn extract_icon_with_cache(
         return Some(img);
     }
 
+    if source_path.is_dir() {
+        if let Some(img) = extract_folder_icon(source_path) {
+            save_cached_icon(source_path, &img);
+            return Some(img);
+        }
+    }
+
     let icon_source = crate::system::resolve_shortcut_target(source_path)
         .filter(|p| p.exists())
         .unwrap_or_else(|| source_path.to_path_buf());
-    if let Some(img) = extract_icon_from_exe(&icon_source) {
+    // SHGetFileInfo gives documents their associated app's icon too.
+    if let Some(img) =
+        extract_icon_from_exe(&icon_source).or_else(|| extract_file_type_icon(&icon_source))
+    {
         save_cached_icon(source_path, &img);
         return Some(img);
     }
@@ -376,30 +575,26 @@ pub fn extract_icon_with_cache(
     None
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn icon_cache_roundtrip_50() {
-        let base = std::env::temp_dir().join(format!(
-            "float_launcher_icon_cache_test_{}",
-            std::process::id()
-        ));
-        let _ = std::fs::remove_dir_all(&base);
-        std::fs::create_dir_all(&base).unwrap();
-
-        for i in 0..50u32 {
-            let source = std::path::PathBuf::from(format!(r"C:\fake\app_{i}.exe"));
-            let side = 64usize;
-            let pixels = vec![(i % 255) as u8; side * side * 4];
-            let img = egui::ColorImage::from_rgba_unmultiplied([side, side], &pixels);
-            save_cached_icon_at(&base, &source, &img);
-            let loaded = load_cached_icon_at(&base, &source).expect("missing cached icon");
-            assert_eq!(loaded.size, [side, side]);
-            assert_eq!(loaded.as_raw().len(), side * side * 4);
+/// Icon for a command pin, keyed by its command line: a custom icon named
+/// after the pin, else the terminal icon, else the generic custom fallback.
+pub fn extract_command_icon_with_cache(
+    command: &Path,
+    name_hint: Option<&str>,
+) -> Option<egui::ColorImage> {
+    if let Some(custom_icon) = find_named_custom_icon(command, name_hint) {
+        if let Some(img) = load_custom_icon_with_cache(&custom_icon) {
+            return Some(img);
         }
     }
+    if let Some(img) = load_cached_icon(command) {
+        return Some(img);
+    }
+    if let Some(img) = extract_terminal_icon() {
+        save_cached_icon(command, &img);
+        return Some(img);
+    }
+    let custom_fallback = find_generic_custom_icon()?;
+    load_custom_icon_with_cache(&custom_fallback)
 }
 
 pub fn resize_to_square(image: &egui::ColorImage, side: usize) -> egui::ColorImage {
@@ -422,14 +617,57 @@ pub fn resize_to_square(image: &egui::ColorImage, side: usize) -> egui::ColorIma
     egui::ColorImage::from_rgba_unmultiplied([side, side], &out)
 }
 
-pub fn generate_colored_icon(color: [u8; 4]) -> Icon {
-    let width = 32;
-    let height = 32;
-    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
-    for _ in 0..height {
-        for _ in 0..width {
-            rgba.extend_from_slice(&color);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimmed_image_is_gray_and_fainter() {
+        let image = egui::ColorImage {
+            size: [2, 1],
+            pixels: vec![
+                egui::Color32::from_rgb(45, 190, 150),
+                egui::Color32::TRANSPARENT,
+            ],
+        };
+        let dimmed = dimmed_image(&image);
+        let p = dimmed.pixels[0];
+        assert_eq!((p.r(), p.g()), (p.g(), p.b()));
+        assert_eq!(p.a(), 255);
+        assert!(p.r() < 150);
+        assert_eq!(dimmed.pixels[1], egui::Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn launch_frames_move_a_dot_around_the_icon() {
+        let image = solid_image([0, 0, 0], 16);
+        let frames = launch_frames(&image, 4);
+        assert_eq!(frames.len(), 4);
+        let lit = |frame: &egui::ColorImage, x: usize, y: usize| frame.pixels[y * 16 + x].r() > 200;
+        // Top at the start, right side a quarter turn later.
+        assert!(lit(&frames[0], 8, 2) && !lit(&frames[0], 14, 8));
+        assert!(lit(&frames[1], 13, 8) && !lit(&frames[1], 8, 2));
+        assert_eq!(frames[2].pixels[0], image.pixels[0]);
+    }
+
+    #[test]
+    fn icon_cache_roundtrip_50() {
+        let base = std::env::temp_dir().join(format!(
+            "float_launcher_icon_cache_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base).unwrap();
+
+        for i in 0..50u32 {
+            let source = std::path::PathBuf::from(format!(r"C:\fake\app_{i}.exe"));
+            let side = 64usize;
+            let pixels = vec![(i % 255) as u8; side * side * 4];
+            let img = egui::ColorImage::from_rgba_unmultiplied([side, side], &pixels);
+            save_cached_icon_at(&base, &source, &img);
+            let loaded = load_cached_icon_at(&base, &source).expect("missing cached icon");
+            assert_eq!(loaded.size, [side, side]);
+            assert_eq!(loaded.as_raw().len(), side * side * 4);
         }
     }
-    Icon::from_rgba(rgba, width, height).expect("Failed to create icon")
 }
@@ -0,0 +1,223 @@
+use crate::system::{find_program, read_desktop_entry};
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Theme sizes tried in order: the dock draws at 32–64 px, larger
+/// downscales better than smaller upscales.
+const ICON_SIZES: [&str; 7] = [
+    "64x64", "48x48", "128x128", "96x96", "256x256", "32x32", "512x512",
+];
+const ICON_CONTEXTS: [&str; 4] = ["apps", "places", "mimetypes", "devices"];
+
+/// Icon for an executable or `.desktop` entry: the entry's `Icon=` key, or
+/// the icon of an installed entry that launches this program, or a themed
+/// icon named after the file. `.ico`/`.png` files (the `ico/` overrides)
+/// are decoded directly.
+pub fn extract_icon_from_exe(path: &Path) -> Option<egui::ColorImage> {
+    let is_image = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("ico") || ext.eq_ignore_ascii_case("png"))
+        .unwrap_or(false);
+    if is_image {
+        return load_image(path);
+    }
+    let name = if crate::system::is_shortcut_path(path) {
+        read_desktop_entry(path)?.icon?
+    } else {
+        installed_app_icons()
+            .get(path)
+            .cloned()
+            .or_else(|| Some(path.file_stem()?.to_string_lossy().into_owned()))?
+    };
+    load_named_icon(&name)
+}
+
+pub fn extract_folder_icon(_path: &Path) -> Option<egui::ColorImage> {
+    load_named_icon("folder")
+}
+
+pub fn extract_file_type_icon(path: &Path) -> Option<egui::ColorImage> {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_ascii_lowercase())
+        .unwrap_or_default();
+    let generic = match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "bmp" => "image-x-generic",
+        "mp3" | "ogg" | "flac" | "wav" | "opus" => "audio-x-generic",
+        "mp4" | "mkv" | "webm" | "avi" | "mov" => "video-x-generic",
+        "zip" | "tar" | "gz" | "xz" | "7z" | "zst" => "package-x-generic",
+        "sh" | "py" | "pl" | "rb" => "text-x-script",
+        _ => "text-x-generic",
+    };
+    load_named_icon(generic)
+}
+
+pub fn extract_terminal_icon() -> Option<egui::ColorImage> {
+    load_named_icon("utilities-terminal")
+}
+
+/// `Icon=` values are usually theme names but may be absolute paths.
+fn load_named_icon(name: &str) -> Option<egui::ColorImage> {
+    let file = if name.starts_with('/') {
+        PathBuf::from(name)
+    } else {
+        // Names aren't supposed to carry an extension, but some entries do.
+        let name = name.strip_suffix(".png").unwrap_or(name);
+        find_themed_icon(&icon_dirs(), &icon_themes(), name)?
+    };
+    load_image(&file)
+}
+
+fn load_image(path: &Path) -> Option<egui::ColorImage> {
+    let rgba = image::open(path).ok()?.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Some(egui::ColorImage::from_rgba_unmultiplied(
+        size,
+        rgba.as_raw(),
+    ))
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, with the spec's defaults.
+fn xdg_data_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut dirs: Vec<PathBuf> = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".local/share")))
+        .into_iter()
+        .collect();
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(system.split(':').map(PathBuf::from));
+    dirs
+}
+
+fn icon_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(".icons"))
+        .into_iter()
+        .collect();
+    dirs.extend(xdg_data_dirs().into_iter().map(|dir| dir.join("icons")));
+    dirs
+}
+
+/// The GTK icon theme if one is configured, then `hicolor`, which every
+/// theme inherits from and where applications install their icons.
+fn icon_themes() -> Vec<String> {
+    let configured = std::env::var_os("HOME").and_then(|home| {
+        let settings =
+            std::fs::read_to_string(PathBuf::from(home).join(".config/gtk-3.0/settings.ini"))
+                .ok()?;
+        settings.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "gtk-icon-theme-name").then(|| value.trim().to_string())
+        })
+    });
+    let mut themes: Vec<String> = configured.into_iter().collect();
+    if !themes.iter().any(|theme| theme == "hicolor") {
+        themes.push("hicolor".to_string());
+    }
+    themes
+}
+
+/// PNG only: there is no SVG rasterizer in the build, so scalable-only
+/// icons fall through to the generic placeholder.
+fn find_themed_icon(dirs: &[PathBuf], themes: &[String], name: &str) -> Option<PathBuf> {
+    let file_name = format!("{name}.png");
+    for theme in themes {
+        for dir in dirs {
+            let theme_dir = dir.join(theme);
+            if !theme_dir.is_dir() {
+                continue;
+            }
+            for size in ICON_SIZES {
+                for context in ICON_CONTEXTS {
+                    let candidate = theme_dir.join(size).join(context).join(&file_name);
+                    if candidate.is_file() {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+    }
+    dirs.iter()
+        .filter_map(|dir| dir.parent())
+        .map(|share| share.join("pixmaps").join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Program path → `Icon=` for every installed application entry, scanned
+/// once per run so pinned binaries pick up their launcher's icon.
+fn installed_app_icons() -> &'static HashMap<PathBuf, String> {
+    static ICONS: OnceLock<HashMap<PathBuf, String>> = OnceLock::new();
+    ICONS.get_or_init(|| {
+        let mut icons = HashMap::new();
+        // Earlier data dirs take precedence, so keep the first entry seen.
+        for dir in xdg_data_dirs() {
+            let Ok(entries) = std::fs::read_dir(dir.join("applications")) else {
+                continue;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if !crate::system::is_shortcut_path(&path) {
+                    continue;
+                }
+                let Some(entry) = read_desktop_entry(&path) else {
+                    continue;
+                };
+                let (Some(icon), Some(program)) =
+                    (entry.icon, entry.exec.first().and_then(|p| find_program(p)))
+                else {
+                    continue;
+                };
+                icons.entry(program).or_insert(icon);
+            }
+        }
+        icons
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themed_icon_prefers_configured_theme_then_hicolor() {
+        let base = std::env::temp_dir().join(format!(
+            "float_launcher_icon_theme_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&base);
+        let icons = base.join("share").join("icons");
+        for (theme, size) in [("hicolor", "48x48"), ("Papirus", "64x64")] {
+            let dir = icons.join(theme).join(size).join("apps");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("editor.png"), b"").unwrap();
+        }
+        let pixmaps = base.join("share").join("pixmaps");
+        std::fs::create_dir_all(&pixmaps).unwrap();
+        std::fs::write(pixmaps.join("legacy.png"), b"").unwrap();
+
+        let dirs = vec![icons.clone()];
+        let themes = vec!["Papirus".to_string(), "hicolor".to_string()];
+        assert_eq!(
+            find_themed_icon(&dirs, &themes, "editor"),
+            Some(icons.join("Papirus/64x64/apps/editor.png"))
+        );
+        assert_eq!(
+            find_themed_icon(&dirs, &themes[1..], "editor"),
+            Some(icons.join("hicolor/48x48/apps/editor.png"))
+        );
+        assert_eq!(
+            find_themed_icon(&dirs, &themes, "legacy"),
+            Some(pixmaps.join("legacy.png"))
+        );
+        assert_eq!(find_themed_icon(&dirs, &themes, "missing"), None);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
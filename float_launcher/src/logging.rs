@@ -0,0 +1,174 @@
+use crate::config::AppConfig;
+use log::{Level, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_FILE_NAME: &str = "float_dock.log";
+/// The current file rolls over to `float_dock.1.log` past this size.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// Rolled-over files kept next to the current one.
+const KEPT_LOGS: usize = 3;
+
+/// Where the log files go: `logs/` in the config folder.
+pub fn log_dir() -> Option<PathBuf> {
+    AppConfig::config_dir().map(|dir| dir.join("logs"))
+}
+
+/// Installs the file logger at `info`; `log::set_max_level` adjusts it once
+/// the config is loaded. Without a config folder nothing is logged.
+pub fn init() {
+    let Some(dir) = log_dir() else {
+        return;
+    };
+    let logger = FileLogger {
+        dir,
+        file: Mutex::new(None),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+struct FileLogger {
+    dir: PathBuf,
+    /// The open log file and how many bytes it holds; opened on first use.
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Other crates (winit, eframe) are chatty below warnings.
+        metadata.level() <= log::max_level()
+            && (metadata.level() <= Level::Warn
+                || metadata.target().starts_with(env!("CARGO_CRATE_NAME")))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            timestamp(SystemTime::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if cfg!(debug_assertions) {
+            eprint!("{line}");
+        }
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if file.as_ref().is_some_and(|(_, len)| *len >= MAX_LOG_BYTES) {
+            *file = None;
+            rotate(&self.dir);
+        }
+        if file.is_none() {
+            *file = open_log(&self.dir);
+        }
+        if let Some((out, len)) = file.as_mut() {
+            if out.write_all(line.as_bytes()).is_ok() {
+                *len += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some((out, _)) = file.as_mut() {
+                let _ = out.flush();
+            }
+        }
+    }
+}
+
+fn open_log(dir: &Path) -> Option<(File, u64)> {
+    std::fs::create_dir_all(dir).ok()?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE_NAME))
+        .ok()?;
+    let len = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    Some((file, len))
+}
+
+fn rolled_name(n: usize) -> String {
+    format!("float_dock.{n}.log")
+}
+
+/// Shifts `float_dock.log` to `.1`, `.1` to `.2` and so on, dropping the oldest.
+fn rotate(dir: &Path) {
+    let _ = std::fs::remove_file(dir.join(rolled_name(KEPT_LOGS)));
+    for n in (1..KEPT_LOGS).rev() {
+        let _ = std::fs::rename(dir.join(rolled_name(n)), dir.join(rolled_name(n + 1)));
+    }
+    let _ = std::fs::rename(dir.join(LOG_FILE_NAME), dir.join(rolled_name(1)));
+}
+
+/// UTC `YYYY-MM-DD hh:mm:ss.mmm`, without pulling in a date crate.
+fn timestamp(now: SystemTime) -> String {
+    let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Days to a civil date, after Howard Hinnant's `civil_from_days`.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}.{:03}",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn timestamps_are_utc_calendar_dates() {
+        let at = |secs| UNIX_EPOCH + Duration::from_millis(secs);
+        assert_eq!(timestamp(at(0)), "1970-01-01 00:00:00.000");
+        assert_eq!(timestamp(at(951_782_400_250)), "2000-02-29 00:00:00.250");
+        assert_eq!(timestamp(at(1_791_976_957_000)), "2026-10-14 11:22:37.000");
+    }
+
+    #[test]
+    fn rotation_shifts_files_and_drops_the_oldest() {
+        let dir = std::env::temp_dir().join(format!(
+            "float_launcher_log_rotate_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(LOG_FILE_NAME), "current").unwrap();
+        for n in 1..=KEPT_LOGS {
+            std::fs::write(dir.join(rolled_name(n)), format!("old {n}")).unwrap();
+        }
+
+        rotate(&dir);
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+        assert_eq!(read(LOG_FILE_NAME), None);
+        assert_eq!(read(&rolled_name(1)).as_deref(), Some("current"));
+        assert_eq!(read(&rolled_name(2)).as_deref(), Some("old 1"));
+        assert_eq!(read(&rolled_name(KEPT_LOGS)).as_deref(), Some("old 2"));
+        assert_eq!(read(&rolled_name(KEPT_LOGS + 1)), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
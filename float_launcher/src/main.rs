@@ -5,49 +5,89 @@ mod branding;
 mod config;
 mod events;
 mod icons;
+mod logging;
 mod system;
 
-use crate::app::{MyApp, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::app::{
+    startup_window_pos, window_level, MyApp, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, WINDOW_HEIGHT,
+    WINDOW_WIDTH,
+};
 use crate::branding::APP_DISPLAY_NAME;
 use crate::config::AppConfig;
+use crate::system::START_HIDDEN_ARG;
 use eframe::egui;
+use std::path::PathBuf;
 
 fn main() -> eframe::Result<()> {
-    let startup_size = load_startup_window_size();
+    logging::init();
+    let config = AppConfig::load();
+    log::set_max_level(config.log_level());
+    log::info!("starting {APP_DISPLAY_NAME} {}", env!("CARGO_PKG_VERSION"));
+    let (startup_pos, startup_size) = load_startup_geometry(&config);
+    let window_level = window_level(config.window_level);
+    let font_fallbacks = config.font_fallbacks.clone();
+    let start_hidden = std::env::args().skip(1).any(|arg| arg == START_HIDDEN_ARG);
 
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(startup_size)
+        .with_resizable(true)
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_window_level(window_level)
+        .with_taskbar(false)
+        .with_visible(true);
+    if let Some(pos) = startup_pos {
+        viewport = viewport.with_position(pos);
+    }
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size(startup_size)
-            .with_resizable(true)
-            .with_decorations(false)
-            .with_transparent(true)
-            .with_always_on_top()
-            .with_taskbar(false)
-            .with_visible(true),
+        viewport,
         ..Default::default()
     };
 
     eframe::run_native(
         APP_DISPLAY_NAME,
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             cc.egui_ctx.set_visuals(egui::Visuals::dark());
-            install_windows_font_fallback(&cc.egui_ctx);
-            Ok(Box::new(MyApp::new(cc)))
+            install_font_fallbacks(&cc.egui_ctx, &font_fallbacks);
+            Ok(Box::new(MyApp::new(cc, config, start_hidden)))
         }),
     )
 }
 
-fn load_startup_window_size() -> [f32; 2] {
-    let config = AppConfig::load();
-    if let Some((w, h)) = config.last_size {
-        [
-            sanitize_dimension(w, WINDOW_WIDTH, MIN_WINDOW_WIDTH),
-            sanitize_dimension(h, WINDOW_HEIGHT, MIN_WINDOW_HEIGHT),
-        ]
-    } else {
-        [WINDOW_WIDTH, WINDOW_HEIGHT]
+/// The restored window position and size, decided once here so the window is
+/// created in place instead of jumping or resizing after the first frame.
+fn load_startup_geometry(config: &AppConfig) -> (Option<egui::Pos2>, [f32; 2]) {
+    let monitor = last_monitor_id(config).or_else(crate::system::primary_monitor_id);
+    let Some(saved) = config.geometry_for(monitor.as_deref()) else {
+        let scale = config.ui_scale();
+        return (None, [WINDOW_WIDTH * scale, WINDOW_HEIGHT * scale]);
+    };
+    let (w, h) = saved.size;
+    let size = [
+        sanitize_dimension(w, WINDOW_WIDTH, MIN_WINDOW_WIDTH),
+        sanitize_dimension(h, WINDOW_HEIGHT, MIN_WINDOW_HEIGHT),
+    ];
+    // Saved geometry is in the dock's zoomed points; the window is created
+    // before the zoom applies, in the monitor's own.
+    let scale = config.ui_scale();
+    let size = size.map(|side| side * scale);
+    let (x, y) = saved.pos;
+    let pos = (x.is_finite() && y.is_finite())
+        .then(|| startup_window_pos(egui::pos2(x, y) * scale, egui::Vec2::from(size)));
+    (pos, size)
+}
+
+/// The monitor the window was last saved on, found by the centre of the
+/// saved rectangle the way saving keys it.
+fn last_monitor_id(config: &AppConfig) -> Option<String> {
+    let ((x, y), (w, h)) = config.last_pos.zip(config.last_size)?;
+    let scale = config.ui_scale() * crate::system::display_scale().unwrap_or(1.0);
+    let center = (egui::pos2(x, y) + egui::vec2(w, h) * 0.5) * scale;
+    if !(center.x.is_finite() && center.y.is_finite()) {
+        return None;
     }
+    crate::system::monitor_id_at(center.x as i32, center.y as i32)
 }
 
 fn sanitize_dimension(value: f32, fallback: f32, min: f32) -> f32 {
@@ -57,27 +97,27 @@ fn sanitize_dimension(value: f32, fallback: f32, min: f32) -> f32 {
     value.clamp(min, 4096.0)
 }
 
-fn install_windows_font_fallback(ctx: &egui::Context) {
+/// Appends every readable font in `paths` to both families, so each one
+/// fills in the glyphs the fonts before it lack.
+fn install_font_fallbacks(ctx: &egui::Context, paths: &[PathBuf]) {
     let mut fonts = egui::FontDefinitions::default();
-    let font_candidates = [
-        ("yahei", r"C:\Windows\Fonts\msyh.ttc"),
-        ("yahei_ui", r"C:\Windows\Fonts\msyhbd.ttc"),
-        ("simhei", r"C:\Windows\Fonts\simhei.ttf"),
-    ];
-
-    for (name, path) in font_candidates {
-        if let Ok(data) = std::fs::read(path) {
-            fonts
-                .font_data
-                .insert(name.to_owned(), egui::FontData::from_owned(data).into());
 
-            if let Some(family) = fonts.families.get_mut(&egui::FontFamily::Proportional) {
-                family.insert(0, name.to_owned());
-            }
-            if let Some(family) = fonts.families.get_mut(&egui::FontFamily::Monospace) {
-                family.push(name.to_owned());
+    for path in paths {
+        let name = format!("fallback:{}", path.display());
+        if fonts.font_data.contains_key(&name) {
+            continue;
+        }
+        let Ok(data) = std::fs::read(path) else {
+            continue;
+        };
+        log::info!("loaded fallback font {}", path.display());
+        fonts
+            .font_data
+            .insert(name.clone(), egui::FontData::from_owned(data));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            if let Some(list) = fonts.families.get_mut(&family) {
+                list.push(name.clone());
             }
-            break;
         }
     }
 
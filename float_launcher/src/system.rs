@@ -1,22 +1,61 @@
-use crate::branding::{APP_AUTOSTART_VALUE, LEGACY_AUTOSTART_VALUE};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use windows::core::{Interface, PCWSTR};
-use windows::Win32::Foundation::HWND;
-use windows::Win32::Storage::FileSystem::WIN32_FIND_DATAW;
-use windows::Win32::System::Com::{
-    CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile, CLSCTX_INPROC_SERVER,
-    COINIT_APARTMENTTHREADED, STGM_READ,
-};
-use windows::Win32::System::Registry::{
-    RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW, HKEY,
-    HKEY_CURRENT_USER, KEY_READ, KEY_SET_VALUE, REG_SZ,
-};
-use windows::Win32::UI::Shell::{
-    IShellLinkW, ShellExecuteW, ShellLink, SLGP_RAWPATH, SLR_ANY_MATCH, SLR_NO_UI,
+
+// Win32 is the full backend. Linux launches `.desktop` entries and
+// executables but shares the `fallback` stubs (no auto-start, monitor or
+// accessibility queries) with every other platform.
+#[cfg(not(windows))]
+mod fallback;
+#[cfg(all(target_os = "linux", not(windows)))]
+mod linux;
+#[cfg(windows)]
+mod win32;
+
+#[cfg(all(not(windows), not(target_os = "linux")))]
+use fallback as platform;
+#[cfg(all(target_os = "linux", not(windows)))]
+use linux as platform;
+#[cfg(windows)]
+use win32 as platform;
+
+#[cfg(all(target_os = "linux", not(windows)))]
+pub use linux::{find_program, read_desktop_entry};
+
+pub use platform::{
+    display_scale, foreground_is_fullscreen, get_auto_start_status, monitor_id_at, pick_file,
+    primary_monitor_id, reveal_in_folder, set_auto_start, set_capture_excluded, set_frame_effects,
+    set_tool_window, set_window_region, shell_open_as, shell_open_with, shell_run_as_admin,
+    show_properties, spawn_with_env, system_high_contrast, system_prefers_reduced_motion,
+    virtual_desktop, work_area, work_area_at, ComGuard,
 };
 
-use std::os::windows::ffi::OsStrExt;
-use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
+/// Compositor effects around the window: requested, or actually in effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameEffects {
+    pub acrylic: bool,
+    pub shadow: bool,
+}
+
+/// Rounded rectangle in physical pixels, relative to the window's top-left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundedRegion {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub radius: i32,
+}
+
+/// How a launched program's first window comes up, where the platform lets
+/// the launcher say. Stored per pin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShowState {
+    #[default]
+    Normal,
+    /// Minimized, without taking focus from the current window.
+    Minimized,
+    Maximized,
+}
 
 #[derive(Debug, Clone)]
 pub struct ShortcutResolution {
@@ -25,215 +64,176 @@ pub struct ShortcutResolution {
     pub working_dir: Option<PathBuf>,
 }
 
-fn to_wide(value: &str) -> Vec<u16> {
-    value.encode_utf16().chain(std::iter::once(0)).collect()
-}
-
-unsafe fn reg_has_value(hkey: HKEY, value_name: &str) -> bool {
-    let name_wide = to_wide(value_name);
-    RegQueryValueExW(hkey, PCWSTR(name_wide.as_ptr()), None, None, None, None)
-        .ok()
-        .is_ok()
+/// Why a launch didn't start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchError {
+    NotFound,
+    /// Includes a cancelled UAC prompt.
+    AccessDenied,
+    /// Nothing is registered to open this kind of file. Only the Windows
+    /// shell reports this; openers elsewhere fail after they've started.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    NoAssociation,
+    Failed,
 }
 
-unsafe fn reg_delete_value(hkey: HKEY, value_name: &str) {
-    let name_wide = to_wide(value_name);
-    let _ = RegDeleteValueW(hkey, PCWSTR(name_wide.as_ptr()));
-}
-
-pub fn get_auto_start_status() -> bool {
-    unsafe {
-        let run_key = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
-        let mut hkey = HKEY::default();
-        let run_key_wide = to_wide(run_key);
-
-        if RegOpenKeyExW(
-            HKEY_CURRENT_USER,
-            PCWSTR(run_key_wide.as_ptr()),
-            0,
-            KEY_READ,
-            &mut hkey,
-        )
-        .ok()
-        .is_ok()
-        {
-            let result = reg_has_value(hkey, APP_AUTOSTART_VALUE)
-                || reg_has_value(hkey, LEGACY_AUTOSTART_VALUE);
-            let _ = RegCloseKey(hkey);
-            return result;
+impl LaunchError {
+    /// For launches that spawn a process directly rather than through the shell.
+    #[cfg(not(windows))]
+    pub fn from_io(err: &std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound,
+            std::io::ErrorKind::PermissionDenied => Self::AccessDenied,
+            _ => Self::Failed,
         }
     }
-    false
 }
 
-pub fn set_auto_start(enabled: bool) -> windows::core::Result<()> {
-    unsafe {
-        let run_key = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
-        let mut hkey = HKEY::default();
-        let run_key_wide = to_wide(run_key);
-
-        RegOpenKeyExW(
-            HKEY_CURRENT_USER,
-            PCWSTR(run_key_wide.as_ptr()),
-            0,
-            KEY_SET_VALUE,
-            &mut hkey,
-        )
-        .ok()?;
-        let app_name_wide = to_wide(APP_AUTOSTART_VALUE);
-
-        if enabled {
-            let exe_path = std::env::current_exe().unwrap_or_default();
-            let exe_path_str = exe_path.to_string_lossy();
-            let path_val = format!("\"{}\"", exe_path_str);
-            let path_wide = to_wide(&path_val);
-
-            let bytes: &[u8] =
-                std::slice::from_raw_parts(path_wide.as_ptr() as *const u8, path_wide.len() * 2);
-
-            RegSetValueExW(hkey, PCWSTR(app_name_wide.as_ptr()), 0, REG_SZ, Some(bytes)).ok()?;
-            reg_delete_value(hkey, LEGACY_AUTOSTART_VALUE);
-        } else {
-            reg_delete_value(hkey, APP_AUTOSTART_VALUE);
-            reg_delete_value(hkey, LEGACY_AUTOSTART_VALUE);
-        }
-
-        RegCloseKey(hkey).ok()?;
+impl std::fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NotFound => "file not found",
+            Self::AccessDenied => "access denied or cancelled",
+            Self::NoAssociation => "no app is set to open this file type",
+            Self::Failed => "the system couldn't start it",
+        })
     }
-    Ok(())
 }
 
+/// Command-line flag the auto-start entry passes when the dock should boot into the tray.
+pub const START_HIDDEN_ARG: &str = "--hidden";
+
+/// Opens `path` with its default handler; for callers with nothing to say
+/// about why it failed.
 pub fn shell_open(path: &Path) -> bool {
-    shell_open_with(path, None, None)
+    shell_open_with(path, None, None, ShowState::Normal).is_ok()
 }
 
-pub fn shell_open_with(path: &Path, args: Option<&str>, working_dir: Option<&Path>) -> bool {
-    unsafe {
-        let operation: Vec<u16> = "open".encode_utf16().chain(std::iter::once(0)).collect();
-        let path_wide: Vec<u16> = path
-            .as_os_str()
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-        let args_wide = args.map(|value| {
-            value
-                .encode_utf16()
-                .chain(std::iter::once(0))
-                .collect::<Vec<u16>>()
-        });
-        let cwd_wide = working_dir.map(|value| {
-            value
-                .as_os_str()
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect::<Vec<u16>>()
-        });
-        let result = ShellExecuteW(
-            HWND(std::ptr::null_mut()),
-            PCWSTR(operation.as_ptr()),
-            PCWSTR(path_wide.as_ptr()),
-            args_wide
-                .as_ref()
-                .map(|w| PCWSTR(w.as_ptr()))
-                .unwrap_or(PCWSTR(std::ptr::null())),
-            cwd_wide
-                .as_ref()
-                .map(|w| PCWSTR(w.as_ptr()))
-                .unwrap_or(PCWSTR(std::ptr::null())),
-            SHOW_WINDOW_CMD(1),
-        );
-        let code = result.0 as isize;
-        code > 32
-    }
-}
+/// Shortcuts pointing at shortcuts are followed at most this many hops.
+const MAX_SHORTCUT_HOPS: usize = 8;
 
+/// Resolves a shortcut (`.lnk`, or `.desktop` on Linux) to its final target, following shortcuts that point at
+/// other shortcuts. Arguments and working directory come from the last hop.
+/// A cycle or a chain longer than `MAX_SHORTCUT_HOPS` resolves to `None`.
 pub fn resolve_shortcut(path: &Path) -> Option<ShortcutResolution> {
-    let is_lnk = path
-        .extension()
-        .and_then(|s| s.to_str())
-        .map(|s| s.eq_ignore_ascii_case("lnk"))
-        .unwrap_or(false);
-    if !is_lnk {
-        return None;
+    let mut visited = std::collections::HashSet::new();
+    visited.insert(path.to_string_lossy().to_ascii_lowercase());
+    let mut resolution = platform::resolve_shortcut_once(path)?;
+    for _ in 1..MAX_SHORTCUT_HOPS {
+        if !is_shortcut_path(&resolution.target_path) {
+            return Some(resolution);
+        }
+        let key = resolution
+            .target_path
+            .to_string_lossy()
+            .to_ascii_lowercase();
+        if !visited.insert(key) {
+            return None;
+        }
+        resolution = platform::resolve_shortcut_once(&resolution.target_path)?;
     }
+    (!is_shortcut_path(&resolution.target_path)).then_some(resolution)
+}
 
-    unsafe {
-        let com_initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
-
-        let result = (|| {
-            let shell_link: IShellLinkW =
-                CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER).ok()?;
-            let persist_file: IPersistFile = shell_link.cast().ok()?;
-
-            let shortcut_wide: Vec<u16> = path
-                .as_os_str()
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
-            persist_file
-                .Load(PCWSTR(shortcut_wide.as_ptr()), STGM_READ)
-                .ok()?;
-
-            let _ = shell_link.Resolve(
-                HWND(std::ptr::null_mut()),
-                (SLR_NO_UI | SLR_ANY_MATCH).0 as u32,
-            );
-
-            let mut target_buf = vec![0u16; 4096];
-            let mut find_data = WIN32_FIND_DATAW::default();
-            let _ = shell_link.GetPath(&mut target_buf, &mut find_data, SLGP_RAWPATH.0 as u32);
-            let mut target = utf16z_to_string(&target_buf);
-            if target.trim().is_empty() {
-                shell_link
-                    .GetPath(&mut target_buf, &mut find_data, 0)
-                    .ok()?;
-                target = utf16z_to_string(&target_buf);
-            }
-            if target.trim().is_empty() {
-                return None;
-            }
-
-            let mut args_buf = vec![0u16; 2048];
-            let _ = shell_link.GetArguments(&mut args_buf);
-            let arguments = normalize_opt_text(utf16z_to_string(&args_buf));
-
-            let mut wd_buf = vec![0u16; 2048];
-            let _ = shell_link.GetWorkingDirectory(&mut wd_buf);
-            let working_dir = normalize_opt_text(utf16z_to_string(&wd_buf)).map(PathBuf::from);
-
-            Some(ShortcutResolution {
-                target_path: PathBuf::from(target.trim()),
-                arguments,
-                working_dir,
-            })
-        })();
-
-        if com_initialized {
-            CoUninitialize();
-        }
-        result
+/// UNC paths (`\\server\share\...`), in either slash style.
+pub fn is_network_path(path: &Path) -> bool {
+    let text = path.to_string_lossy().replace('/', "\\");
+    if let Some(rest) = text.strip_prefix(r"\\?\") {
+        return rest
+            .get(..4)
+            .is_some_and(|unc| unc.eq_ignore_ascii_case(r"UNC\"));
     }
+    text.starts_with(r"\\") && !text.starts_with(r"\\.\")
+}
+
+pub fn is_shortcut_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.eq_ignore_ascii_case(platform::SHORTCUT_EXTENSION))
+        .unwrap_or(false)
+}
+
+/// Shortcuts the shell keeps for apps pinned to the taskbar.
+pub fn list_taskbar_pinned_shortcuts() -> Vec<PathBuf> {
+    let Some(app_data) = std::env::var_os("APPDATA") else {
+        return Vec::new();
+    };
+    let dir = PathBuf::from(app_data)
+        .join("Microsoft")
+        .join("Internet Explorer")
+        .join("Quick Launch")
+        .join("User Pinned")
+        .join("TaskBar");
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut shortcuts: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|s| s.to_str())
+                .map(|ext| ext.eq_ignore_ascii_case("lnk"))
+                .unwrap_or(false)
+        })
+        .collect();
+    shortcuts.sort_by_key(|path| path.to_string_lossy().to_ascii_lowercase());
+    shortcuts
 }
 
 pub fn resolve_shortcut_target(path: &Path) -> Option<PathBuf> {
     resolve_shortcut(path).map(|v| v.target_path)
 }
 
-fn utf16z_to_string(wide: &[u16]) -> String {
-    let end = wide.iter().position(|c| *c == 0).unwrap_or(wide.len());
-    String::from_utf16_lossy(&wide[..end])
+/// Splits a command line into its program and the rest, Windows style: the
+/// program is the first word, or everything inside a leading pair of quotes.
+/// `None` when there is no program at all.
+pub fn split_program(command: &str) -> Option<(PathBuf, Option<String>)> {
+    let command = command.trim();
+    let (program, rest) = match command.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+        None => command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, "")),
+    };
+    let program = program.trim();
+    if program.is_empty() {
+        return None;
+    }
+    let rest = rest.trim();
+    Some((
+        PathBuf::from(program),
+        (!rest.is_empty()).then(|| rest.to_string()),
+    ))
 }
 
-fn normalize_opt_text(text: String) -> Option<String> {
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        None
-    } else {
-        Some(trimmed.to_string())
+/// `path` with `..` and symlinks resolved, so the same file pinned through
+/// different routes compares equal. Falls back to `path` as given when it
+/// can't be resolved (e.g. a network share that is offline right now).
+pub fn canonical_path(path: &Path) -> PathBuf {
+    match std::fs::canonicalize(path) {
+        Ok(canonical) => strip_verbatim_prefix(canonical),
+        Err(_) => path.to_path_buf(),
     }
 }
 
-#[cfg(test)]
+/// `canonicalize` returns `\\?\C:\...` and `\\?\UNC\server\...` on Windows;
+/// pins are stored in the form the user sees everywhere else.
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{rest}"));
+    }
+    match text.strip_prefix(r"\\?\") {
+        // Only drive paths; `\\?\Volume{...}` has no plain spelling.
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+// These build real `.lnk` files through PowerShell and the WScript COM object.
+#[cfg(all(test, windows))]
 mod tests {
     use super::*;
     use std::process::Command;
@@ -290,9 +290,43 @@ mod tests {
         let _ = std::fs::remove_file(&target);
         let _ = std::fs::remove_dir_all(&base);
     }
-}
 
-#[allow(dead_code)]
-pub fn apply_acrylic(hwnd: HWND) {
-    println!("Applying acrylic effect to window HWND: {:?}", hwnd);
+    #[test]
+    fn resolve_shortcut_follows_chains_and_stops_on_cycles() {
+        let uniq = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time error")
+            .as_nanos();
+        let base = std::env::temp_dir().join(format!("float_launcher_chain_test_{uniq}"));
+        let folder = base.join("target dir");
+        std::fs::create_dir_all(&folder).expect("create temp dir");
+        let inner = base.join("inner.lnk");
+        let outer = base.join("outer.lnk");
+        let loop_a = base.join("loop_a.lnk");
+        let loop_b = base.join("loop_b.lnk");
+
+        let script = format!(
+            "$w=New-Object -ComObject WScript.Shell; \
+             $s=$w.CreateShortcut('{inner}'); $s.TargetPath='{folder}'; $s.Save(); \
+             $s=$w.CreateShortcut('{outer}'); $s.TargetPath='{inner}'; $s.Save(); \
+             $s=$w.CreateShortcut('{loop_a}'); $s.TargetPath='{loop_b}'; $s.Save(); \
+             $s=$w.CreateShortcut('{loop_b}'); $s.TargetPath='{loop_a}'; $s.Save()",
+            inner = ps_quote(&inner),
+            outer = ps_quote(&outer),
+            folder = ps_quote(&folder),
+            loop_a = ps_quote(&loop_a),
+            loop_b = ps_quote(&loop_b),
+        );
+        let status = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .status()
+            .expect("run powershell");
+        assert!(status.success(), "powershell failed to create shortcuts");
+
+        let resolved = resolve_shortcut(&outer).expect("chained shortcut should resolve");
+        assert_eq!(norm(&resolved.target_path), norm(&folder));
+        assert!(resolve_shortcut(&loop_a).is_none());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
 }
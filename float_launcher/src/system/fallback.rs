@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+#[cfg(not(target_os = "linux"))]
+use {
+    super::{LaunchError, ShortcutResolution, ShowState},
+    std::path::Path,
+    std::process::Command,
+};
+
+/// No COM off Windows; kept so callers can hold a guard unconditionally.
+pub struct ComGuard;
+
+impl ComGuard {
+    pub fn init() -> Self {
+        Self
+    }
+}
+
+pub fn get_auto_start_status() -> bool {
+    false
+}
+
+pub fn set_auto_start(_enabled: bool, _start_hidden: bool) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "auto-start is only implemented on Windows",
+    ))
+}
+
+// Linux launches through `linux.rs` instead of the functions gated below.
+#[cfg(not(target_os = "linux"))]
+pub const SHORTCUT_EXTENSION: &str = "lnk";
+
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+const OPENER: &str = "xdg-open";
+
+/// Hands the path to the desktop's opener. The opener has no way to forward
+/// arguments or a show state to whatever it launches, so both are dropped.
+#[cfg(not(target_os = "linux"))]
+pub fn shell_open_with(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    _show: ShowState,
+) -> Result<(), LaunchError> {
+    spawn_with_env(path, args, working_dir, &[])
+}
+
+/// `shell_open_with`, with `env` passed to the opener for it to hand on.
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_with_env(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    env: &[(String, String)],
+) -> Result<(), LaunchError> {
+    if args.is_some() {
+        log::warn!("ignoring launch arguments for {}", path.display());
+    }
+    let mut command = Command::new(OPENER);
+    command.arg(path);
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    match command.spawn() {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            log::error!("failed to run {OPENER} for {}: {err}", path.display());
+            Err(LaunchError::from_io(&err))
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn shell_run_as_admin(
+    _path: &Path,
+    _args: Option<&str>,
+    _working_dir: Option<&Path>,
+) -> Result<(), LaunchError> {
+    Err(LaunchError::Failed)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn resolve_shortcut_once(_path: &Path) -> Option<ShortcutResolution> {
+    None
+}
+
+pub fn work_area() -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+pub fn work_area_at(_x: i32, _y: i32) -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+pub fn virtual_desktop() -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+pub fn foreground_is_fullscreen() -> bool {
+    false
+}
+
+pub fn display_scale() -> Option<f32> {
+    None
+}
+
+pub fn system_prefers_reduced_motion() -> bool {
+    false
+}
+
+pub fn system_high_contrast() -> bool {
+    false
+}
+
+pub fn monitor_id_at(_x: i32, _y: i32) -> Option<String> {
+    None
+}
+
+pub fn primary_monitor_id() -> Option<String> {
+    None
+}
+
+pub fn pick_file(_title: &str) -> Option<PathBuf> {
+    None
+}
+
+/// There is no portable "Open with" picker to hand off to.
+pub fn shell_open_as(_path: &std::path::Path) -> bool {
+    false
+}
+
+/// File managers differ in how (or whether) they select an item, so this
+/// just opens the containing folder.
+pub fn reveal_in_folder(path: &std::path::Path) -> bool {
+    path.parent().is_some_and(super::shell_open)
+}
+
+/// File managers have no common way to be asked for a file's properties.
+pub fn show_properties(_path: &std::path::Path) -> bool {
+    false
+}
+
+/// Blur and shadows are left to the compositor off Windows.
+pub fn set_frame_effects(
+    _window: &impl raw_window_handle::HasWindowHandle,
+    _wanted: super::FrameEffects,
+) -> super::FrameEffects {
+    super::FrameEffects::default()
+}
+
+/// There's no Alt-Tab entry to drop without a taskbar of our own.
+pub fn set_tool_window(_window: &impl raw_window_handle::HasWindowHandle, _tool: bool) -> bool {
+    false
+}
+
+/// Screen capture is up to the compositor off Windows.
+pub fn set_capture_excluded(
+    _window: &impl raw_window_handle::HasWindowHandle,
+    _excluded: bool,
+) -> bool {
+    false
+}
+
+pub fn set_window_region(
+    _window: &impl raw_window_handle::HasWindowHandle,
+    _region: Option<super::RoundedRegion>,
+) -> bool {
+    false
+}
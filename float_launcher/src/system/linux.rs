@@ -0,0 +1,358 @@
+use super::{is_shortcut_path, LaunchError, ShortcutResolution, ShowState};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub use super::fallback::{
+    display_scale, foreground_is_fullscreen, get_auto_start_status, monitor_id_at, pick_file,
+    primary_monitor_id, reveal_in_folder, set_auto_start, set_capture_excluded, set_frame_effects,
+    set_tool_window, set_window_region, shell_open_as, show_properties, system_high_contrast,
+    system_prefers_reduced_motion, virtual_desktop, work_area, work_area_at, ComGuard,
+};
+
+pub const SHORTCUT_EXTENSION: &str = "desktop";
+
+/// The parts of a `[Desktop Entry]` group the dock uses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesktopEntry {
+    pub name: Option<String>,
+    /// `Exec=` split into program and arguments, field codes removed.
+    pub exec: Vec<String>,
+    pub working_dir: Option<PathBuf>,
+    pub icon: Option<String>,
+}
+
+pub fn read_desktop_entry(path: &Path) -> Option<DesktopEntry> {
+    let text = std::fs::read_to_string(path).ok()?;
+    parse_desktop_entry(&text)
+}
+
+fn parse_desktop_entry(text: &str) -> Option<DesktopEntry> {
+    let mut in_entry = false;
+    let mut is_application = true;
+    let mut entry = DesktopEntry::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry || line.starts_with('#') {
+            continue;
+        }
+        // Localized keys (`Name[de]=`) never match the plain names below.
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unescape_value(value.trim());
+        match key.trim() {
+            "Type" => is_application = value == "Application",
+            "Name" => entry.name = Some(value).filter(|v| !v.is_empty()),
+            "Exec" => entry.exec = strip_field_codes(split_command_line(&value)),
+            "Path" => entry.working_dir = Some(value).filter(|v| !v.is_empty()).map(PathBuf::from),
+            "Icon" => entry.icon = Some(value).filter(|v| !v.is_empty()),
+            _ => {}
+        }
+    }
+    (is_application && !entry.exec.is_empty()).then_some(entry)
+}
+
+/// Undoes the `\s`, `\n`, `\t`, `\r` and `\\` escapes of desktop-entry strings.
+fn unescape_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Drops `%f`, `%U` and friends; the dock never passes files or URLs on launch.
+fn strip_field_codes(args: Vec<String>) -> Vec<String> {
+    args.into_iter()
+        .filter(|arg| !(arg.len() == 2 && arg.starts_with('%') && arg != "%%"))
+        .map(|arg| {
+            let mut out = String::with_capacity(arg.len());
+            let mut chars = arg.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    out.push(c);
+                } else if chars.next() == Some('%') {
+                    out.push('%');
+                }
+            }
+            out
+        })
+        .filter(|arg| !arg.is_empty())
+        .collect()
+}
+
+/// Splits a command line on whitespace, honouring double and single quotes
+/// and backslash escapes the way `sh` would for plain words.
+pub fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), _) => current.push(c),
+            (Some(_), '\\') => match chars.next() {
+                Some(next @ ('"' | '`' | '$' | '\\')) => current.push(next),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => current.push('\\'),
+            },
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_word = true;
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    args
+}
+
+/// Inverse of `split_command_line`: quotes only the arguments that need it.
+fn join_command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            let plain = !arg.is_empty()
+                && !arg
+                    .chars()
+                    .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '$' | '`'));
+            if plain {
+                return arg.clone();
+            }
+            let mut quoted = String::with_capacity(arg.len() + 2);
+            quoted.push('"');
+            for c in arg.chars() {
+                if matches!(c, '"' | '\\' | '$' | '`') {
+                    quoted.push('\\');
+                }
+                quoted.push(c);
+            }
+            quoted.push('"');
+            quoted
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds `program` the way a shell would: as given when it has a slash,
+/// otherwise in `$PATH`.
+pub fn find_program(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+pub fn resolve_shortcut_once(path: &Path) -> Option<ShortcutResolution> {
+    if !is_shortcut_path(path) {
+        return None;
+    }
+    let entry = read_desktop_entry(path)?;
+    let (program, args) = entry.exec.split_first()?;
+    Some(ShortcutResolution {
+        target_path: find_program(program)?,
+        arguments: (!args.is_empty()).then(|| join_command_line(args)),
+        working_dir: entry.working_dir,
+    })
+}
+
+/// Runs executables directly so `args` reach them; everything else goes to
+/// `xdg-open`, which can't forward arguments. A bare program name, as command
+/// pins use, is looked up in `$PATH`. How the window comes up is left to
+/// the window manager, so `show` is ignored.
+pub fn shell_open_with(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    _show: ShowState,
+) -> Result<(), LaunchError> {
+    spawn_with_env(path, args, working_dir, &[])
+}
+
+/// `shell_open_with` with `env` added to the launched program's environment.
+/// `xdg-open` hands files to an already running handler at times, so the
+/// variables only reliably reach executables.
+pub fn spawn_with_env(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    env: &[(String, String)],
+) -> Result<(), LaunchError> {
+    let bare_name = path.components().count() == 1 && !path.exists();
+    if let Some(program) = bare_name
+        .then(|| find_program(&path.to_string_lossy()))
+        .flatten()
+    {
+        return spawn_with_env(&program, args, working_dir, env);
+    }
+    if is_shortcut_path(path) {
+        if let Some(shortcut) = super::resolve_shortcut(path) {
+            return spawn_with_env(
+                &shortcut.target_path,
+                shortcut.arguments.as_deref(),
+                shortcut.working_dir.as_deref(),
+                env,
+            );
+        }
+    }
+
+    let mut command = if is_executable(path) {
+        let mut command = Command::new(path);
+        command.args(args.map(split_command_line).unwrap_or_default());
+        command
+    } else {
+        if args.is_some() {
+            log::warn!("ignoring launch arguments for {}", path.display());
+        }
+        let mut command = Command::new("xdg-open");
+        command.arg(path);
+        command
+    };
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    spawn_detached(command, path)
+}
+
+/// Launches through `pkexec`, which asks for the administrator password.
+pub fn shell_run_as_admin(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+) -> Result<(), LaunchError> {
+    if !path.exists() {
+        return Err(LaunchError::NotFound);
+    }
+    if !is_executable(path) {
+        return Err(LaunchError::Failed);
+    }
+    let mut command = Command::new("pkexec");
+    command
+        .arg(path)
+        .args(args.map(split_command_line).unwrap_or_default());
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    spawn_detached(command, path)
+}
+
+fn spawn_detached(mut command: Command, path: &Path) -> Result<(), LaunchError> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    match command.spawn() {
+        Ok(mut child) => {
+            // Reap it when it exits so finished launches don't linger as zombies.
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+            Ok(())
+        }
+        Err(err) => {
+            log::error!("failed to launch {}: {err}", path.display());
+            Err(LaunchError::from_io(&err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entry_exec_drops_field_codes_and_keeps_quotes() {
+        let text = "\
+[Desktop Entry]
+Type=Application
+Name=Editor
+Name[de]=Bearbeiter
+Exec=\"/opt/My Editor/editor\" --new-window %F --label=100%%
+Path=/home/me/work
+Icon=my-editor
+
+[Desktop Action new]
+Exec=editor --other
+Icon=wrong
+";
+        let entry = parse_desktop_entry(text).expect("entry should parse");
+        assert_eq!(entry.name.as_deref(), Some("Editor"));
+        assert_eq!(
+            entry.exec,
+            ["/opt/My Editor/editor", "--new-window", "--label=100%"]
+        );
+        assert_eq!(entry.working_dir, Some(PathBuf::from("/home/me/work")));
+        assert_eq!(entry.icon.as_deref(), Some("my-editor"));
+
+        assert!(parse_desktop_entry("[Desktop Entry]\nType=Link\nURL=https://x\n").is_none());
+    }
+
+    #[test]
+    fn command_line_split_and_join_roundtrip() {
+        let args = split_command_line(r#"--title "two words" 'single $quoted' back\ slash "a\"b""#);
+        assert_eq!(
+            args,
+            [
+                "--title",
+                "two words",
+                "single $quoted",
+                "back slash",
+                "a\"b"
+            ]
+        );
+        assert_eq!(split_command_line(&join_command_line(&args)), args);
+        assert!(split_command_line("   ").is_empty());
+    }
+}
@@ -0,0 +1,829 @@
+use super::{
+    is_shortcut_path, FrameEffects, LaunchError, RoundedRegion, ShortcutResolution, ShowState,
+    START_HIDDEN_ARG,
+};
+use crate::branding::{APP_AUTOSTART_VALUE, LEGACY_AUTOSTART_VALUE};
+use std::path::{Path, PathBuf};
+use windows::core::{Interface, PCWSTR, PWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Storage::FileSystem::WIN32_FIND_DATAW;
+use windows::Win32::System::ApplicationInstallationAndServicing::{
+    MsiGetComponentPathW, MsiGetShortcutTargetW, INSTALLSTATE_LOCAL, INSTALLSTATE_SOURCE,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile, CLSCTX_INPROC_SERVER,
+    COINIT_APARTMENTTHREADED, STGM_READ,
+};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegDeleteValueW, RegGetValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW,
+    HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_SET_VALUE, REG_SZ, RRF_RT_REG_SZ,
+};
+use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+use windows::Win32::UI::Controls::Dialogs::{
+    GetOpenFileNameW, OFN_FILEMUSTEXIST, OFN_NOCHANGEDIR, OFN_PATHMUSTEXIST, OPENFILENAMEW,
+};
+use windows::Win32::UI::Controls::MARGINS;
+use windows::Win32::UI::Shell::{
+    ILCreateFromPathW, ILFree, IShellLinkW, SHObjectProperties, SHOpenFolderAndSelectItems,
+    ShellExecuteW, ShellLink, SHOP_FILEPATH, SLGP_RAWPATH, SLR_ANY_MATCH, SLR_NO_UI,
+};
+
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use std::os::windows::ffi::OsStrExt;
+use windows::Win32::Foundation::{POINT, RECT};
+use windows::Win32::Graphics::Dwm::{
+    DwmExtendFrameIntoClientArea, DwmIsCompositionEnabled, DwmSetWindowAttribute, DWMSBT_NONE,
+    DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE, DWMWA_WINDOW_CORNER_PREFERENCE,
+    DWMWCP_DEFAULT, DWMWCP_ROUND, DWM_SYSTEMBACKDROP_TYPE, DWM_WINDOW_CORNER_PREFERENCE,
+};
+use windows::Win32::Graphics::Gdi::{
+    CreateRoundRectRgn, DeleteObject, GetDC, GetDeviceCaps, GetMonitorInfoW, MonitorFromPoint,
+    MonitorFromWindow, ReleaseDC, SetWindowRgn, HRGN, LOGPIXELSX, MONITORINFO, MONITORINFOEXW,
+    MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTOPRIMARY, MONITOR_FROM_FLAGS,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetSystemMetrics,
+    GetWindowLongPtrW, GetWindowRect, GetWindowThreadProcessId, SetWindowDisplayAffinity,
+    SetWindowLongPtrW, SystemParametersInfoW, GWL_EXSTYLE, GWL_STYLE, SHOW_WINDOW_CMD,
+    SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST, SPI_GETWORKAREA, SW_SHOWMAXIMIZED,
+    SW_SHOWMINNOACTIVE, SW_SHOWNORMAL, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, WDA_EXCLUDEFROMCAPTURE,
+    WDA_MONITOR, WDA_NONE, WS_CAPTION, WS_EX_APPWINDOW, WS_EX_TOOLWINDOW, WS_THICKFRAME,
+};
+
+pub const SHORTCUT_EXTENSION: &str = "lnk";
+
+thread_local! {
+    static COM_DEPTH: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+/// Scoped COM initialization for the current thread. Nested guards (e.g.
+/// shortcut resolution inside the icon worker) reuse the outer apartment and
+/// never uninitialize it; only the outermost successful init is undone.
+pub struct ComGuard {
+    uninitialize: bool,
+}
+
+impl ComGuard {
+    pub fn init() -> Self {
+        let nested = COM_DEPTH.with(|depth| {
+            let outer = depth.get();
+            depth.set(outer + 1);
+            outer > 0
+        });
+        let uninitialize =
+            !nested && unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+        Self { uninitialize }
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        COM_DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+        if self.uninitialize {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+unsafe fn reg_has_value(hkey: HKEY, value_name: &str) -> bool {
+    let name_wide = to_wide(value_name);
+    RegQueryValueExW(hkey, PCWSTR(name_wide.as_ptr()), None, None, None, None)
+        .ok()
+        .is_ok()
+}
+
+unsafe fn reg_delete_value(hkey: HKEY, value_name: &str) {
+    let name_wide = to_wide(value_name);
+    let _ = RegDeleteValueW(hkey, PCWSTR(name_wide.as_ptr()));
+}
+
+pub fn get_auto_start_status() -> bool {
+    unsafe {
+        let run_key = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+        let mut hkey = HKEY::default();
+        let run_key_wide = to_wide(run_key);
+
+        if RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(run_key_wide.as_ptr()),
+            0,
+            KEY_READ,
+            &mut hkey,
+        )
+        .ok()
+        .is_ok()
+        {
+            let result = reg_has_value(hkey, APP_AUTOSTART_VALUE)
+                || reg_has_value(hkey, LEGACY_AUTOSTART_VALUE);
+            let _ = RegCloseKey(hkey);
+            return result;
+        }
+    }
+    false
+}
+
+pub fn set_auto_start(enabled: bool, start_hidden: bool) -> std::io::Result<()> {
+    unsafe {
+        let run_key = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+        let mut hkey = HKEY::default();
+        let run_key_wide = to_wide(run_key);
+
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(run_key_wide.as_ptr()),
+            0,
+            KEY_SET_VALUE,
+            &mut hkey,
+        )
+        .ok()?;
+        let app_name_wide = to_wide(APP_AUTOSTART_VALUE);
+
+        if enabled {
+            let exe_path = std::env::current_exe().unwrap_or_default();
+            let exe_path_str = exe_path.to_string_lossy();
+            let mut path_val = format!("\"{}\"", exe_path_str);
+            if start_hidden {
+                path_val.push(' ');
+                path_val.push_str(START_HIDDEN_ARG);
+            }
+            let path_wide = to_wide(&path_val);
+
+            let bytes: &[u8] =
+                std::slice::from_raw_parts(path_wide.as_ptr() as *const u8, path_wide.len() * 2);
+
+            RegSetValueExW(hkey, PCWSTR(app_name_wide.as_ptr()), 0, REG_SZ, Some(bytes)).ok()?;
+            reg_delete_value(hkey, LEGACY_AUTOSTART_VALUE);
+        } else {
+            reg_delete_value(hkey, APP_AUTOSTART_VALUE);
+            reg_delete_value(hkey, LEGACY_AUTOSTART_VALUE);
+        }
+
+        RegCloseKey(hkey).ok()?;
+    }
+    Ok(())
+}
+
+/// Opens `path` through the shell, asking for its first window to come up
+/// as `show` says. Programs are free to ignore that, and some do.
+pub fn shell_open_with(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    show: ShowState,
+) -> Result<(), LaunchError> {
+    let show = match show {
+        ShowState::Normal => SW_SHOWNORMAL,
+        ShowState::Minimized => SW_SHOWMINNOACTIVE,
+        ShowState::Maximized => SW_SHOWMAXIMIZED,
+    };
+    shell_execute("open", path, args, working_dir, show)
+}
+
+/// Shows the system "Open with" picker for `path`.
+pub fn shell_open_as(path: &Path) -> bool {
+    shell_execute("openas", path, None, None, SW_SHOWNORMAL).is_ok()
+}
+
+/// Launches through the `runas` verb, which raises the UAC prompt.
+pub fn shell_run_as_admin(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+) -> Result<(), LaunchError> {
+    shell_execute("runas", path, args, working_dir, SW_SHOWNORMAL)
+}
+
+/// Starts `path` as a process with `env` added to the dock's environment.
+/// `args` reach the program exactly as written. The window always comes up
+/// normally; `std::process` can't pass a show state. ShellExecute takes no
+/// environment block, so shortcuts run their target and folders and
+/// documents open through the shell without `env`.
+pub fn spawn_with_env(
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    env: &[(String, String)],
+) -> Result<(), LaunchError> {
+    use std::os::windows::process::CommandExt;
+
+    if is_shortcut_path(path) {
+        if let Some(shortcut) = super::resolve_shortcut(path) {
+            return spawn_with_env(
+                &shortcut.target_path,
+                shortcut.arguments.as_deref(),
+                shortcut.working_dir.as_deref(),
+                env,
+            );
+        }
+    }
+    // A bare command name has no extension and is looked up in PATH.
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let runs_directly = !path.is_dir()
+        && extension.is_none_or(|ext| {
+            ["exe", "com", "bat", "cmd"]
+                .iter()
+                .any(|program| ext.eq_ignore_ascii_case(program))
+        });
+    if !runs_directly {
+        log::warn!("ignoring the environment for {}", path.display());
+        return shell_open_with(path, args, working_dir, ShowState::Normal);
+    }
+
+    let mut command = std::process::Command::new(path);
+    if let Some(args) = args {
+        command.raw_arg(args);
+    }
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    match command.spawn() {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            log::error!("failed to start {}: {err}", path.display());
+            Err(match err.raw_os_error() {
+                // ERROR_FILE_NOT_FOUND, ERROR_PATH_NOT_FOUND
+                Some(2 | 3) => LaunchError::NotFound,
+                // ERROR_ACCESS_DENIED, ERROR_ELEVATION_REQUIRED
+                Some(5 | 740) => LaunchError::AccessDenied,
+                _ => LaunchError::Failed,
+            })
+        }
+    }
+}
+
+/// Classifies a `ShellExecuteW` return value; anything above 32 is success.
+fn launch_result(code: isize) -> Result<(), LaunchError> {
+    // SE_ERR_FNF, SE_ERR_PNF; SE_ERR_ACCESSDENIED, which a cancelled UAC
+    // prompt also returns; SE_ERR_ASSOCINCOMPLETE, SE_ERR_NOASSOC. A file that
+    // exists but isn't a valid program (ERROR_BAD_FORMAT) is a plain failure.
+    match code {
+        33.. => Ok(()),
+        2 | 3 => Err(LaunchError::NotFound),
+        5 => Err(LaunchError::AccessDenied),
+        27 | 31 => Err(LaunchError::NoAssociation),
+        _ => Err(LaunchError::Failed),
+    }
+}
+
+fn shell_execute(
+    verb: &str,
+    path: &Path,
+    args: Option<&str>,
+    working_dir: Option<&Path>,
+    show: SHOW_WINDOW_CMD,
+) -> Result<(), LaunchError> {
+    unsafe {
+        let operation = to_wide(verb);
+        let path_wide: Vec<u16> = path
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        let args_wide = args.map(|value| {
+            value
+                .encode_utf16()
+                .chain(std::iter::once(0))
+                .collect::<Vec<u16>>()
+        });
+        let cwd_wide = working_dir.map(|value| {
+            value
+                .as_os_str()
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect::<Vec<u16>>()
+        });
+        let result = ShellExecuteW(
+            HWND(std::ptr::null_mut()),
+            PCWSTR(operation.as_ptr()),
+            PCWSTR(path_wide.as_ptr()),
+            args_wide
+                .as_ref()
+                .map(|w| PCWSTR(w.as_ptr()))
+                .unwrap_or(PCWSTR(std::ptr::null())),
+            cwd_wide
+                .as_ref()
+                .map(|w| PCWSTR(w.as_ptr()))
+                .unwrap_or(PCWSTR(std::ptr::null())),
+            show,
+        );
+        launch_result(result.0 as isize)
+    }
+}
+
+pub fn resolve_shortcut_once(path: &Path) -> Option<ShortcutResolution> {
+    if !is_shortcut_path(path) {
+        return None;
+    }
+
+    let _com = ComGuard::init();
+    unsafe {
+        (|| {
+            let shell_link: IShellLinkW =
+                CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER).ok()?;
+            let persist_file: IPersistFile = shell_link.cast().ok()?;
+
+            let shortcut_wide: Vec<u16> = path
+                .as_os_str()
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
+            persist_file
+                .Load(PCWSTR(shortcut_wide.as_ptr()), STGM_READ)
+                .ok()?;
+
+            let _ = shell_link.Resolve(
+                HWND(std::ptr::null_mut()),
+                (SLR_NO_UI | SLR_ANY_MATCH).0 as u32,
+            );
+
+            let mut target_buf = vec![0u16; 4096];
+            let mut find_data = WIN32_FIND_DATAW::default();
+            let _ = shell_link.GetPath(&mut target_buf, &mut find_data, SLGP_RAWPATH.0 as u32);
+            let mut target = utf16z_to_string(&target_buf);
+            if target.trim().is_empty()
+                && shell_link
+                    .GetPath(&mut target_buf, &mut find_data, 0)
+                    .is_ok()
+            {
+                target = utf16z_to_string(&target_buf);
+            }
+            if target.trim().is_empty() {
+                // Installer-advertised shortcuts (Office and friends) carry no
+                // path, only a product/component pair for MSI to look up.
+                target = advertised_shortcut_target(&shortcut_wide)?;
+            }
+
+            let mut args_buf = vec![0u16; 2048];
+            let _ = shell_link.GetArguments(&mut args_buf);
+            let arguments = normalize_opt_text(utf16z_to_string(&args_buf));
+
+            let mut wd_buf = vec![0u16; 2048];
+            let _ = shell_link.GetWorkingDirectory(&mut wd_buf);
+            let working_dir = normalize_opt_text(utf16z_to_string(&wd_buf)).map(PathBuf::from);
+
+            Some(ShortcutResolution {
+                target_path: PathBuf::from(target.trim()),
+                arguments,
+                working_dir,
+            })
+        })()
+    }
+}
+
+/// Installed path behind an MSI-advertised shortcut, if the component is present.
+unsafe fn advertised_shortcut_target(shortcut_wide: &[u16]) -> Option<String> {
+    // Product and component codes are GUID strings: 38 chars plus the nul.
+    let mut product = [0u16; 39];
+    let mut feature = [0u16; 39];
+    let mut component = [0u16; 39];
+    let status = MsiGetShortcutTargetW(
+        PCWSTR(shortcut_wide.as_ptr()),
+        PWSTR(product.as_mut_ptr()),
+        PWSTR(feature.as_mut_ptr()),
+        PWSTR(component.as_mut_ptr()),
+    );
+    if status != 0 {
+        return None;
+    }
+
+    let mut path_buf = vec![0u16; 1024];
+    let mut len = path_buf.len() as u32;
+    let state = MsiGetComponentPathW(
+        PCWSTR(product.as_ptr()),
+        PCWSTR(component.as_ptr()),
+        PWSTR(path_buf.as_mut_ptr()),
+        Some(&mut len),
+    );
+    if state != INSTALLSTATE_LOCAL && state != INSTALLSTATE_SOURCE {
+        return None;
+    }
+    normalize_opt_text(utf16z_to_string(&path_buf))
+}
+
+/// Primary monitor work area (excluding the taskbar) in physical pixels:
+/// `(left, top, right, bottom)`.
+pub fn work_area() -> Option<(i32, i32, i32, i32)> {
+    let mut rect = RECT::default();
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETWORKAREA,
+            0,
+            Some(&mut rect as *mut RECT as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .ok()?;
+    }
+    Some((rect.left, rect.top, rect.right, rect.bottom))
+}
+
+/// Bounding box of all monitors in physical pixels, in the same
+/// `(left, top, right, bottom)` form as `work_area`.
+pub fn virtual_desktop() -> Option<(i32, i32, i32, i32)> {
+    let (left, top, width, height) = unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    };
+    (width > 0 && height > 0).then_some((left, top, left + width, top + height))
+}
+
+/// Work area of the monitor nearest the given physical pixel, in the same
+/// `(left, top, right, bottom)` form as `work_area`.
+pub fn work_area_at(x: i32, y: i32) -> Option<(i32, i32, i32, i32)> {
+    unsafe {
+        let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST);
+        if monitor.is_invalid() {
+            return None;
+        }
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return None;
+        }
+        let rect = info.rcWork;
+        Some((rect.left, rect.top, rect.right, rect.bottom))
+    }
+}
+
+/// Whether the foreground window covers its whole monitor without a caption
+/// or sizing border, as games and fullscreen video do. A maximized window
+/// doesn't count, nor do the desktop and the dock itself.
+pub fn foreground_is_fullscreen() -> bool {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() || hwnd == GetDesktopWindow() || hwnd == GetShellWindow() {
+            return false;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == std::process::id() {
+            return false;
+        }
+        // Clicking the wallpaper focuses one of these, and they span the monitor.
+        let mut class = [0u16; 64];
+        let len = GetClassNameW(hwnd, &mut class).max(0) as usize;
+        let class = String::from_utf16_lossy(&class[..len]);
+        if class == "Progman" || class == "WorkerW" {
+            return false;
+        }
+        let style = GetWindowLongPtrW(hwnd, GWL_STYLE);
+        if style & (WS_CAPTION.0 | WS_THICKFRAME.0) as isize != 0 {
+            return false;
+        }
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return false;
+        }
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if monitor.is_invalid() || !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return false;
+        }
+        let screen = info.rcMonitor;
+        rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom
+    }
+}
+
+/// Physical pixels per logical point for the desktop, before any window
+/// exists to ask egui.
+pub fn display_scale() -> Option<f32> {
+    unsafe {
+        let screen = GetDC(HWND::default());
+        if screen.is_invalid() {
+            return None;
+        }
+        let dpi = GetDeviceCaps(screen, LOGPIXELSX);
+        ReleaseDC(HWND::default(), screen);
+        (dpi > 0).then(|| dpi as f32 / 96.0)
+    }
+}
+
+/// Whether Windows "Show animations in Windows" is off, i.e. the user asked for less motion.
+pub fn system_prefers_reduced_motion() -> bool {
+    let mut enabled = windows::Win32::Foundation::BOOL(1);
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut enabled as *mut _ as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    ok.is_ok() && !enabled.as_bool()
+}
+
+/// Whether the Windows high-contrast accessibility mode is on.
+pub fn system_high_contrast() -> bool {
+    let mut info = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+    };
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            info.cbSize,
+            Some(&mut info as *mut HIGHCONTRASTW as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    ok.is_ok() && info.dwFlags.contains(HCF_HIGHCONTRASTON)
+}
+
+/// Device name (e.g. `\\.\DISPLAY2`) of the monitor nearest the given physical pixel.
+pub fn monitor_id_at(x: i32, y: i32) -> Option<String> {
+    monitor_id(POINT { x, y }, MONITOR_DEFAULTTONEAREST)
+}
+
+pub fn primary_monitor_id() -> Option<String> {
+    monitor_id(POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY)
+}
+
+fn monitor_id(point: POINT, flags: MONITOR_FROM_FLAGS) -> Option<String> {
+    unsafe {
+        let monitor = MonitorFromPoint(point, flags);
+        if monitor.is_invalid() {
+            return None;
+        }
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(
+            monitor,
+            &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+        )
+        .as_bool()
+        {
+            return None;
+        }
+        normalize_opt_text(utf16z_to_string(&info.szDevice))
+    }
+}
+
+/// Opens an Explorer window on the containing folder with `path` selected.
+pub fn reveal_in_folder(path: &Path) -> bool {
+    let _com = ComGuard::init();
+    let wide = to_wide(&path.to_string_lossy());
+    unsafe {
+        let item = ILCreateFromPathW(PCWSTR(wide.as_ptr()));
+        if item.is_null() {
+            return false;
+        }
+        let shown = SHOpenFolderAndSelectItems(item, None, 0).is_ok();
+        ILFree(Some(item));
+        shown
+    }
+}
+
+/// Opens Explorer's Properties dialog for `path`. The dialog runs on its own
+/// shell thread, so this returns as soon as it's up.
+pub fn show_properties(path: &Path) -> bool {
+    let wide = to_wide(&path.to_string_lossy());
+    unsafe {
+        SHObjectProperties(
+            HWND(std::ptr::null_mut()),
+            SHOP_FILEPATH,
+            PCWSTR(wide.as_ptr()),
+            PCWSTR(std::ptr::null()),
+        )
+        .as_bool()
+    }
+}
+
+/// Shows the standard "Open" dialog; `None` when the user cancels.
+pub fn pick_file(title: &str) -> Option<PathBuf> {
+    let filter: Vec<u16> = "Programs and shortcuts\0*.exe;*.lnk;*.bat;*.cmd\0All files\0*.*\0\0"
+        .encode_utf16()
+        .collect();
+    let title_wide = to_wide(title);
+    let mut file_buf = vec![0u16; 1024];
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        lpstrFilter: PCWSTR(filter.as_ptr()),
+        lpstrFile: PWSTR(file_buf.as_mut_ptr()),
+        nMaxFile: file_buf.len() as u32,
+        lpstrTitle: PCWSTR(title_wide.as_ptr()),
+        Flags: OFN_FILEMUSTEXIST | OFN_PATHMUSTEXIST | OFN_NOCHANGEDIR,
+        ..Default::default()
+    };
+    let picked = unsafe { GetOpenFileNameW(&mut ofn) };
+    if !picked.as_bool() {
+        return None;
+    }
+    let path = utf16z_to_string(&file_buf);
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+fn utf16z_to_string(wide: &[u16]) -> String {
+    let end = wide.iter().position(|c| *c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..end])
+}
+
+fn normalize_opt_text(text: String) -> Option<String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn hwnd_of(window: &impl HasWindowHandle) -> Option<HWND> {
+    match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Win32(handle) => Some(HWND(handle.hwnd.get() as *mut std::ffi::c_void)),
+        _ => None,
+    }
+}
+
+/// Turns the window into a tool window, which keeps it out of Alt-Tab and
+/// the taskbar, or back into a normal one.
+pub fn set_tool_window(window: &impl HasWindowHandle, tool: bool) -> bool {
+    let Some(hwnd) = hwnd_of(window) else {
+        return false;
+    };
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        let wanted = if tool {
+            (style | WS_EX_TOOLWINDOW.0 as isize) & !(WS_EX_APPWINDOW.0 as isize)
+        } else {
+            style & !(WS_EX_TOOLWINDOW.0 as isize)
+        };
+        if wanted != style {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, wanted);
+        }
+    }
+    true
+}
+
+/// Keeps the window out of screenshots and screen sharing while `excluded`.
+/// Before Windows 10 2004 there's no way to leave it out entirely, so it's
+/// shown as a black box instead. Returns whether the change took.
+pub fn set_capture_excluded(window: &impl HasWindowHandle, excluded: bool) -> bool {
+    let Some(hwnd) = hwnd_of(window) else {
+        return false;
+    };
+    unsafe {
+        if !excluded {
+            return SetWindowDisplayAffinity(hwnd, WDA_NONE).is_ok();
+        }
+        match SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) {
+            Ok(()) => true,
+            Err(err) => {
+                log::info!(
+                    "capture exclusion unsupported ({err}), blacking the window out instead"
+                );
+                SetWindowDisplayAffinity(hwnd, WDA_MONITOR).is_ok()
+            }
+        }
+    }
+}
+
+/// Clips the window to `region` so clicks outside it fall through to whatever
+/// is underneath; `None` removes the clip.
+pub fn set_window_region(window: &impl HasWindowHandle, region: Option<RoundedRegion>) -> bool {
+    let Some(hwnd) = hwnd_of(window) else {
+        return false;
+    };
+    unsafe {
+        let Some(region) = region else {
+            return SetWindowRgn(hwnd, HRGN::default(), true) != 0;
+        };
+        // The right and bottom edges are exclusive; the ellipse takes a diameter.
+        let hrgn = CreateRoundRectRgn(
+            region.left,
+            region.top,
+            region.right + 1,
+            region.bottom + 1,
+            region.radius * 2,
+            region.radius * 2,
+        );
+        if hrgn.is_invalid() {
+            return false;
+        }
+        // The window owns the region once it's set; only a failed call leaves it with us.
+        if SetWindowRgn(hwnd, hrgn, true) == 0 {
+            let _ = DeleteObject(hrgn);
+            return false;
+        }
+        true
+    }
+}
+
+/// First build with DWM system backdrops (Windows 11 22H2).
+const SYSTEM_BACKDROP_BUILD: u32 = 22621;
+
+/// Windows build number, read from the registry because `GetVersionEx`
+/// reports an old version to programs without a compatibility manifest.
+fn windows_build() -> Option<u32> {
+    let key = to_wide(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion");
+    let value = to_wide("CurrentBuildNumber");
+    let mut buffer = [0u16; 16];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(value.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+    }
+    utf16z_to_string(&buffer).trim().parse().ok()
+}
+
+/// Asks DWM for the acrylic backdrop and/or a system drop shadow behind the
+/// transparent window, and reports which of them it got. Acrylic needs
+/// Windows 11 22H2; the shadow needs desktop composition. Whatever isn't
+/// granted is left to the flat transparency and painted shadow.
+pub fn set_frame_effects(window: &impl HasWindowHandle, wanted: FrameEffects) -> FrameEffects {
+    let Some(hwnd) = hwnd_of(window) else {
+        return FrameEffects::default();
+    };
+    let acrylic =
+        wanted.acrylic && matches!(windows_build(), Some(build) if build >= SYSTEM_BACKDROP_BUILD);
+    let shadow = wanted.shadow && unsafe { DwmIsCompositionEnabled() }.is_ok_and(|on| on.as_bool());
+
+    // The backdrop only shows through where DWM owns the frame, so acrylic
+    // extends it over the whole client area; a one-pixel frame is enough
+    // for DWM to draw its shadow.
+    let inset = if acrylic {
+        -1
+    } else if shadow {
+        1
+    } else {
+        0
+    };
+    let margins = MARGINS {
+        cxLeftWidth: inset,
+        cxRightWidth: inset,
+        cyTopHeight: inset,
+        cyBottomHeight: inset,
+    };
+    let backdrop = if acrylic {
+        DWMSBT_TRANSIENTWINDOW
+    } else {
+        DWMSBT_NONE
+    };
+    // Windows 11 rounds the shadow along with the corners; older builds
+    // ignore the attribute.
+    let corners = if shadow { DWMWCP_ROUND } else { DWMWCP_DEFAULT };
+    unsafe {
+        if DwmExtendFrameIntoClientArea(hwnd, &margins).is_err() {
+            return FrameEffects::default();
+        }
+        let _ = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_WINDOW_CORNER_PREFERENCE,
+            &corners as *const DWM_WINDOW_CORNER_PREFERENCE as *const std::ffi::c_void,
+            std::mem::size_of::<DWM_WINDOW_CORNER_PREFERENCE>() as u32,
+        );
+        // Set even when acrylic is off, so turning it off clears a backdrop
+        // applied earlier.
+        let backdrop_set = DwmSetWindowAttribute(
+            hwnd,
+            DWMWA_SYSTEMBACKDROP_TYPE,
+            &backdrop as *const DWM_SYSTEMBACKDROP_TYPE as *const std::ffi::c_void,
+            std::mem::size_of::<DWM_SYSTEMBACKDROP_TYPE>() as u32,
+        )
+        .is_ok();
+        FrameEffects {
+            acrylic: acrylic && backdrop_set,
+            shadow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_execute_codes_map_to_launch_errors() {
+        assert_eq!(launch_result(42), Ok(()));
+        assert_eq!(launch_result(2), Err(LaunchError::NotFound));
+        assert_eq!(launch_result(3), Err(LaunchError::NotFound));
+        assert_eq!(launch_result(5), Err(LaunchError::AccessDenied));
+        assert_eq!(launch_result(31), Err(LaunchError::NoAssociation));
+        assert_eq!(launch_result(11), Err(LaunchError::Failed));
+        assert_eq!(launch_result(0), Err(LaunchError::Failed));
+    }
+}